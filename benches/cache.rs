@@ -0,0 +1,86 @@
+//! Response cache benchmarks
+//! Hits, misses, and inserts on one thread, and mixed reads and writes from many threads sharing the cache
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use yahoo_fantasy_sdk::Cache;
+
+/// Entries the cache holds before evicting
+const CAPACITY: usize = 1_000;
+
+/// A typical cached response: a few kilobytes of JSON
+fn body() -> String {
+    "x".repeat(4_096)
+}
+
+fn url(i: usize) -> String {
+    format!("https://fantasysports.yahooapis.com/fantasy/v2/player/nfl.p.{}/stats?format=json", i)
+}
+
+fn filled() -> Cache {
+    let cache = Cache::new();
+    for i in 0..CAPACITY {
+        cache.put(url(i), body());
+    }
+    cache
+}
+
+fn single_thread(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cache");
+    let cache = filled();
+    let hit = url(CAPACITY / 2);
+    let miss = url(CAPACITY * 2);
+    group.bench_function("get_hit", |b| b.iter(|| cache.get(black_box(&hit))));
+    group.bench_function("get_miss", |b| b.iter(|| cache.get(black_box(&miss))));
+    group.bench_function("put_existing", |b| b.iter(|| cache.put(black_box(hit.clone()), body())));
+    let mut next = CAPACITY;
+    group.bench_function("put_evicting", |b| {
+        b.iter(|| {
+            next += 1;
+            cache.put(url(next), body())
+        })
+    });
+    group.finish();
+}
+
+/// Every thread does `iters` operations, one in `write_every` of them a put;
+/// the time is for all threads to finish
+fn contended(threads: usize, write_every: usize, iters: u64) -> Duration {
+    let cache = Arc::new(filled());
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for t in 0..threads {
+            let cache = &cache;
+            scope.spawn(move || {
+                for i in 0..iters as usize {
+                    let key = url((t * 7_919 + i) % CAPACITY);
+                    if i % write_every == 0 {
+                        cache.put(key, body());
+                    } else {
+                        black_box(cache.get(&key));
+                    }
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn concurrent(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cache_concurrent");
+    for threads in [1, 4, 16] {
+        group.bench_with_input(BenchmarkId::new("read_heavy", threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| contended(threads, 20, iters))
+        });
+        group.bench_with_input(BenchmarkId::new("write_heavy", threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| contended(threads, 2, iters))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, single_thread, concurrent);
+criterion_main!(benches);
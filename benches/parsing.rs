@@ -0,0 +1,102 @@
+//! Response parsing benchmarks
+//! Decoding and flattening large `format=json` player collections, owned and borrowed, and serializing large XML bodies
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+use yahoo_fantasy_sdk::response;
+use yahoo_fantasy_sdk::xml::escape;
+use yahoo_fantasy_sdk::{PlayerCollection, RosterBuilder, RosterCoverage, XmlBody};
+
+/// Collection sizes to parse, up to a full league's player pool
+const SIZES: &[usize] = &[100, 1_000, 5_000];
+
+/// A league players collection the size of `count`, encoded the way Yahoo
+/// encodes one: each resource split into arrays of small objects
+fn players_fixture(count: usize) -> String {
+    let mut players = serde_json::Map::new();
+    for i in 0..count {
+        players.insert(
+            i.to_string(),
+            json!({
+                "player": [
+                    [
+                        { "player_key": format!("nfl.p.{}", 30_000 + i) },
+                        { "player_id": (30_000 + i).to_string() },
+                        { "name": { "full": format!("Player {}", i), "first": "Player", "last": i.to_string() } },
+                        { "editorial_team_abbr": "SF" },
+                        { "display_position": "WR" },
+                        { "eligible_positions": [{ "position": "WR" }, { "position": "W/R/T" }] },
+                        { "status": "" },
+                    ],
+                    { "player_points": { "coverage_type": "season", "total": format!("{}.{}", i % 300, i % 10) } },
+                    { "ownership": { "ownership_type": "freeagents", "percent_owned": (i % 100).to_string() } },
+                ]
+            }),
+        );
+    }
+    players.insert("count".to_string(), json!(count));
+    json!({
+        "fantasy_content": {
+            "league": [
+                { "league_key": "nfl.l.1", "name": "Benchmark League", "season": "2024" },
+                { "players": players },
+            ]
+        }
+    })
+    .to_string()
+}
+
+fn json_parsing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json");
+    for &count in SIZES {
+        let body = players_fixture(count);
+        group.throughput(Throughput::Bytes(body.len() as u64));
+        group.bench_with_input(BenchmarkId::new("decode", count), &body, |b, body| {
+            b.iter(|| serde_json::from_str::<Value>(black_box(body)).unwrap())
+        });
+        let value: Value = serde_json::from_str(&body).unwrap();
+        let players = &response::content(&value).unwrap()["league"][1]["players"];
+        group.bench_with_input(BenchmarkId::new("flatten", count), players, |b, players| {
+            b.iter(|| response::collection(black_box(players), "player"))
+        });
+        group.bench_with_input(BenchmarkId::new("decode_and_flatten", count), &body, |b, body| {
+            b.iter(|| {
+                let value: Value = serde_json::from_str(black_box(body)).unwrap();
+                let league = &response::content(&value).unwrap()["league"][1]["players"];
+                response::collection(league, "player")
+            })
+        });
+        let collection = PlayerCollection::new(body.clone());
+        group.bench_with_input(BenchmarkId::new("borrowed", count), &collection, |b, collection| {
+            b.iter(|| black_box(collection).players().unwrap())
+        });
+        #[cfg(feature = "arena")]
+        {
+            let mut batch = yahoo_fantasy_sdk::ParsedBatch::with_capacity(body.len() * 2);
+            group.bench_with_input(BenchmarkId::new("arena", count), &body, |b, body| {
+                b.iter(|| {
+                    batch.reset();
+                    batch.players(black_box(body)).unwrap().len()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+fn xml_serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("xml");
+    for &count in SIZES {
+        let builder = (0..count).fold(RosterBuilder::new(RosterCoverage::Week(1)), |builder, i| builder.player(&format!("nfl.p.{}", 30_000 + i), "BN"));
+        group.throughput(Throughput::Elements(count as u64));
+        // The body is serialized when it's built
+        group.bench_with_input(BenchmarkId::new("roster_body", count), &builder, |b, builder| b.iter(|| black_box(builder.clone()).build().unwrap().to_xml()));
+    }
+    let note = "Trade offer: <3 for 1> & a \"future\" pick ".repeat(100);
+    group.throughput(Throughput::Bytes(note.len() as u64));
+    group.bench_function("escape", |b| b.iter(|| escape(black_box(&note))));
+    group.finish();
+}
+
+criterion_group!(benches, json_parsing, xml_serialization);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+//! Rate limiter benchmarks
+//! The per-request fast path when tokens are available, alone and with many threads sharing the limiter
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use yahoo_fantasy_sdk::RateLimiter;
+
+fn fast_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rate_limiter");
+    let limiter = RateLimiter::new();
+    group.bench_function("can_make_request", |b| b.iter(|| black_box(&limiter).can_make_request()));
+    group.bench_function("time_until_available", |b| b.iter(|| black_box(&limiter).time_until_available()));
+    // Once the bucket is drained record_cost stops spending, so this measures the check and lock
+    group.bench_function("check_and_record", |b| {
+        b.iter(|| {
+            if limiter.can_make_request() {
+                limiter.record_request();
+            }
+        })
+    });
+    group.finish();
+}
+
+fn contended(threads: usize, iters: u64) -> Duration {
+    let limiter = Arc::new(RateLimiter::new());
+    let start = Instant::now();
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            let limiter = &limiter;
+            scope.spawn(move || {
+                for _ in 0..iters {
+                    black_box(limiter.can_make_request());
+                }
+            });
+        }
+    });
+    start.elapsed()
+}
+
+fn concurrent(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rate_limiter_concurrent");
+    for threads in [1, 4, 16] {
+        group.bench_with_input(BenchmarkId::new("can_make_request", threads), &threads, |b, &threads| {
+            b.iter_custom(|iters| contended(threads, iters))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fast_path, concurrent);
+criterion_main!(benches);
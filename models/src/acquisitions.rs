@@ -0,0 +1,88 @@
+//! Acquisition limits
+//! Weekly and season add caps and how many adds a team has used
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::LeagueSettings;
+
+/// A team's adds against its league's limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AcquisitionUsage {
+    /// Adds allowed per week; `None` when unlimited
+    pub weekly_limit: Option<u32>,
+    pub weekly_used: u32,
+    /// Adds allowed per season; `None` when unlimited
+    pub season_limit: Option<u32>,
+    pub season_used: u32,
+}
+
+impl AcquisitionUsage {
+    /// Limits from `settings` with nothing used yet
+    pub fn for_settings(settings: &LeagueSettings) -> Self {
+        Self {
+            weekly_limit: settings.max_weekly_adds,
+            weekly_used: 0,
+            season_limit: settings.max_season_adds,
+            season_used: 0,
+        }
+    }
+
+    /// Adds the team can still make this week, the tighter of both limits;
+    /// `None` when neither applies
+    pub fn remaining(&self) -> Option<u32> {
+        let weekly = self.weekly_limit.map(|limit| limit.saturating_sub(self.weekly_used));
+        let season = self.season_limit.map(|limit| limit.saturating_sub(self.season_used));
+        match (weekly, season) {
+            (Some(w), Some(s)) => Some(w.min(s)),
+            (w, s) => w.or(s),
+        }
+    }
+
+    /// The limit `adds` more adds would break, if any
+    pub fn check(&self, adds: u32) -> Result<(), AcquisitionLimitReached> {
+        let over = |limit: Option<u32>, used: u32| limit.filter(|limit| used + adds > *limit);
+        if let Some(limit) = over(self.weekly_limit, self.weekly_used) {
+            return Err(AcquisitionLimitReached {
+                period: LimitPeriod::Week,
+                limit,
+            });
+        }
+        if let Some(limit) = over(self.season_limit, self.season_used) {
+            return Err(AcquisitionLimitReached {
+                period: LimitPeriod::Season,
+                limit,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Period an acquisition limit covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum LimitPeriod {
+    Week,
+    Season,
+}
+
+/// A move would take a team past one of its acquisition limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcquisitionLimitReached {
+    pub period: LimitPeriod,
+    pub limit: u32,
+}
+
+impl fmt::Display for AcquisitionLimitReached {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let period = match self.period {
+            LimitPeriod::Week => "weekly",
+            LimitPeriod::Season => "season",
+        };
+        write!(f, "the move would exceed the {} limit of {} adds", period, self.limit)
+    }
+}
+
+impl std::error::Error for AcquisitionLimitReached {}
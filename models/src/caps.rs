@@ -0,0 +1,76 @@
+//! Weekly innings and games-played caps
+//! A cap and how much of it a team has used
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Positions whose starts count toward an innings cap
+const PITCHER_POSITIONS: &[&str] = &["SP", "RP", "P"];
+
+/// What a weekly cap limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CapKind {
+    /// Innings pitched by the team's active pitchers (MLB)
+    InningsPitched,
+    /// Games played by the team's active players (NBA, NHL)
+    GamesPlayed,
+}
+
+impl fmt::Display for CapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CapKind::InningsPitched => "innings pitched",
+            CapKind::GamesPlayed => "games played",
+        })
+    }
+}
+
+/// A weekly cap and how much of it a team has used
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CapUsage {
+    pub kind: CapKind,
+    pub limit: f64,
+    pub used: f64,
+}
+
+impl CapUsage {
+    pub fn remaining(&self) -> f64 {
+        (self.limit - self.used).max(0.0)
+    }
+
+    pub fn is_reached(&self) -> bool {
+        self.remaining() <= 0.0
+    }
+
+    /// Whether starting a player at `positions` counts toward the cap
+    pub fn applies_to(&self, positions: &[String], has_game: bool) -> bool {
+        match self.kind {
+            CapKind::InningsPitched => has_game && positions.iter().any(|p| PITCHER_POSITIONS.contains(&p.as_str())),
+            CapKind::GamesPlayed => has_game,
+        }
+    }
+
+    /// Whether the team can still start a player at `positions`. Yahoo counts
+    /// the whole outing that crosses an innings cap, so any innings left allow a start.
+    pub fn allows(&self, positions: &[String], has_game: bool) -> bool {
+        if !self.applies_to(positions, has_game) {
+            return true;
+        }
+        match self.kind {
+            CapKind::InningsPitched => !self.is_reached(),
+            CapKind::GamesPlayed => self.remaining() >= 1.0,
+        }
+    }
+
+    /// Count a start of a player at `positions` against the cap. Innings
+    /// aren't known until the game is played, so only games are counted.
+    pub fn record_start(&mut self, positions: &[String], has_game: bool) {
+        if self.kind == CapKind::GamesPlayed && self.applies_to(positions, has_game) {
+            self.used += 1.0;
+        }
+    }
+}
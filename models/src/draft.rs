@@ -0,0 +1,30 @@
+//! Draft picks and players
+//! Shared by draft boards, auction trackers, and draft result parsing
+
+use serde::{Deserialize, Serialize};
+
+use crate::Points;
+
+/// A player available in the draft
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DraftPlayer {
+    pub player_key: String,
+    pub name: String,
+    /// Eligible positions, primary first
+    pub positions: Vec<String>,
+    pub value: Points,
+}
+
+/// A completed pick, as reported by draft results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DraftPick {
+    pub pick: u32,
+    pub round: u32,
+    pub team_key: String,
+    pub player_key: String,
+    /// Winning bid in auction drafts
+    #[serde(default)]
+    pub cost: Option<u32>,
+}
@@ -0,0 +1,106 @@
+//! Head-to-head records
+//! Pairwise record and points between every two teams, tallied from scoreboards
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Points, Scoreboard};
+
+/// One team's results against one opponent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct HeadToHeadRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub points_for: Points,
+    pub points_against: Points,
+}
+
+impl HeadToHeadRecord {
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.ties
+    }
+
+    /// Ties count as half a win; 0.0 before any games
+    pub fn win_percentage(&self) -> f64 {
+        match self.games() {
+            0 => 0.0,
+            games => (self.wins as f64 + self.ties as f64 / 2.0) / games as f64,
+        }
+    }
+
+    fn add(&mut self, other: &HeadToHeadRecord) {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.ties += other.ties;
+        self.points_for += other.points_for;
+        self.points_against += other.points_against;
+    }
+}
+
+/// Every team's record against every opponent it has played
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct HeadToHeadMatrix {
+    pub league_key: String,
+    /// Team key -> opponent key -> record
+    pub records: BTreeMap<String, BTreeMap<String, HeadToHeadRecord>>,
+}
+
+impl HeadToHeadMatrix {
+    /// Tally completed weeks' scoreboards. Matchups without exactly two teams are skipped.
+    pub fn from_scoreboards(league_key: &str, scoreboards: &[Scoreboard]) -> Self {
+        let mut matrix = Self {
+            league_key: league_key.to_string(),
+            records: BTreeMap::new(),
+        };
+        for matchup in scoreboards.iter().filter(|s| s.league_key == league_key).flat_map(|s| &s.matchups) {
+            let [a, b] = matchup.teams.as_slice() else {
+                continue;
+            };
+            matrix.tally(&a.team_key, a.points, &b.team_key, b.points);
+            matrix.tally(&b.team_key, b.points, &a.team_key, a.points);
+        }
+        matrix
+    }
+
+    fn tally(&mut self, team_key: &str, points: Points, opponent_key: &str, opponent_points: Points) {
+        let record = self
+            .records
+            .entry(team_key.to_string())
+            .or_default()
+            .entry(opponent_key.to_string())
+            .or_default();
+        match points.cmp(&opponent_points) {
+            std::cmp::Ordering::Greater => record.wins += 1,
+            std::cmp::Ordering::Less => record.losses += 1,
+            std::cmp::Ordering::Equal => record.ties += 1,
+        }
+        record.points_for += points;
+        record.points_against += opponent_points;
+    }
+
+    /// Teams that appear in the matrix, sorted
+    pub fn team_keys(&self) -> Vec<&str> {
+        self.records.keys().map(String::as_str).collect()
+    }
+
+    /// `team_key`'s record against `opponent_key`; `None` if they haven't played
+    pub fn record(&self, team_key: &str, opponent_key: &str) -> Option<&HeadToHeadRecord> {
+        self.records.get(team_key)?.get(opponent_key)
+    }
+
+    /// `team_key`'s combined record against a group of opponents, as used by
+    /// head-to-head tiebreakers among tied teams. The team itself is ignored if listed.
+    pub fn record_against(&self, team_key: &str, opponents: &[&str]) -> HeadToHeadRecord {
+        let mut combined = HeadToHeadRecord::default();
+        for opponent in opponents.iter().filter(|o| **o != team_key) {
+            if let Some(record) = self.record(team_key, opponent) {
+                combined.add(record);
+            }
+        }
+        combined
+    }
+}
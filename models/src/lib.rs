@@ -0,0 +1,425 @@
+//! Yahoo Fantasy Sports data models
+//! Serde types shared by the SDK client and any server that speaks the same shapes
+
+use serde::{Deserialize, Serialize};
+
+pub mod acquisitions;
+pub mod caps;
+pub mod draft;
+pub mod head_to_head;
+pub mod points;
+pub mod position;
+pub mod stats;
+pub mod week;
+
+pub use acquisitions::{AcquisitionLimitReached, AcquisitionUsage, LimitPeriod};
+pub use caps::{CapKind, CapUsage};
+pub use draft::{DraftPick, DraftPlayer};
+pub use head_to_head::{HeadToHeadMatrix, HeadToHeadRecord};
+pub use points::Points;
+pub use position::{Position, StatGroup};
+pub use stats::{DailyStatLine, FreeAgent, PlayerGameLog, PlayerStats, StatCoverage};
+pub use week::WeekRange;
+
+/// Game data structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Game {
+    pub game_key: String,
+    pub name: String,
+    pub code: String,
+    pub season: i32,
+    /// Not reported for seasons before 2010
+    pub is_game_over: Option<bool>,
+}
+
+/// League data structure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct League {
+    pub league_key: String,
+    pub name: String,
+    pub num_teams: i32,
+    pub current_week: i32,
+    pub season: Option<i32>,
+    pub scoring_type: Option<String>,
+    /// Legacy scoring setting, only present in older seasons
+    pub uses_fractional_points: Option<bool>,
+}
+
+/// Whether a league can be joined without an invite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum LeagueVisibility {
+    Public,
+    Private,
+}
+
+/// Where a league is in its draft
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DraftStatus {
+    Predraft,
+    #[serde(alias = "draft", alias = "inprogress")]
+    Drafting,
+    Postdraft,
+}
+
+/// Join and setup state of a league for the logged-in user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LeagueStatus {
+    pub league_key: String,
+    pub visibility: LeagueVisibility,
+    pub draft_status: DraftStatus,
+    /// Key of the logged-in user's team, if they have one in this league
+    pub my_team_key: Option<String>,
+    /// The user's team exists but still needs a name/logo before the draft
+    pub team_pending_setup: bool,
+}
+
+impl LeagueStatus {
+    /// True if the user is in the league and their team is ready
+    pub fn is_ready(&self) -> bool {
+        self.my_team_key.is_some() && !self.team_pending_setup
+    }
+
+    /// True if the user could still join (public league that hasn't drafted)
+    pub fn is_joinable(&self) -> bool {
+        self.my_team_key.is_none()
+            && self.visibility == LeagueVisibility::Public
+            && self.draft_status == DraftStatus::Predraft
+    }
+}
+
+/// A public league open for browsing or joining
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PublicLeagueSummary {
+    pub league_key: String,
+    pub name: String,
+    pub scoring_type: String,
+    pub draft_type: String,
+    pub num_teams: i32,
+    pub open_slots: i32,
+}
+
+/// Filters for `search_public_leagues`; unset fields match anything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default)]
+pub struct LeagueSearchFilters {
+    /// e.g. "head", "point", "roto"
+    pub scoring_type: Option<String>,
+    /// e.g. "live", "autopick", "auction"
+    pub draft_type: Option<String>,
+    pub num_teams: Option<i32>,
+    /// Only leagues with at least one open slot
+    pub joinable_only: bool,
+}
+
+impl LeagueSearchFilters {
+    pub fn matches(&self, league: &PublicLeagueSummary) -> bool {
+        self.scoring_type.as_ref().is_none_or(|t| *t == league.scoring_type)
+            && self.draft_type.as_ref().is_none_or(|t| *t == league.draft_type)
+            && self.num_teams.is_none_or(|n| n == league.num_teams)
+            && (!self.joinable_only || league.open_slots > 0)
+    }
+}
+
+/// A roster slot from league settings, e.g. "W/R/T" accepting WR, RB, and TE
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RosterSlot {
+    pub position: String,
+    /// Player positions eligible for this slot
+    pub accepts: Vec<String>,
+    pub count: u32,
+    /// Bench and IR slots don't score
+    pub is_starting: bool,
+}
+
+impl RosterSlot {
+    /// Whether a player eligible at `position` can fill the slot. Besides the
+    /// listed positions, covering IDP slots accept their members, e.g. a DL
+    /// slot takes a DE.
+    pub fn accepts(&self, position: &str) -> bool {
+        let parsed: Position = position.parse().unwrap_or_else(|never| match never {});
+        self.accepts
+            .iter()
+            .any(|p| p == position || parsed.fills(&p.parse().unwrap_or_else(|never| match never {})))
+    }
+
+    /// Whether the slot takes more than one position, e.g. "W/R/T" or "Util"
+    pub fn is_flex(&self) -> bool {
+        self.accepts.len() > 1
+    }
+
+    /// Whether the slot is a flex that also takes quarterbacks, like "Q/W/R/T"
+    /// superflex or "OP" slots
+    pub fn is_superflex(&self) -> bool {
+        self.is_flex() && self.accepts("QB")
+    }
+}
+
+/// Scoreboard for one league week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Scoreboard {
+    pub league_key: String,
+    pub week: i32,
+    pub matchups: Vec<Matchup>,
+}
+
+/// A head-to-head matchup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Matchup {
+    pub teams: Vec<MatchupTeam>,
+}
+
+/// One team's side of a matchup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct MatchupTeam {
+    pub team_key: String,
+    pub name: String,
+    pub points: Points,
+    pub projected_points: Points,
+    pub players: Vec<PlayerPoints>,
+}
+
+/// Points scored by a started player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PlayerPoints {
+    pub player_key: String,
+    pub points: Points,
+}
+
+/// League standings as of a week
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Standings {
+    pub league_key: String,
+    pub week: i32,
+    pub teams: Vec<TeamStanding>,
+}
+
+/// One team's line in the standings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TeamStanding {
+    pub team_key: String,
+    pub name: String,
+    pub rank: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub points_for: Points,
+    pub waiver_priority: Option<u32>,
+    pub clinched_playoffs: Option<bool>,
+    pub eliminated: Option<bool>,
+}
+
+/// Points awarded per unit of a stat
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StatModifier {
+    pub stat_id: u32,
+    pub name: String,
+    /// Abbreviation used as the key in raw stat lines, e.g. "Pass Yds"
+    pub display_name: String,
+    pub value: Points,
+}
+
+/// Scoring, roster, waiver, and playoff configuration for a league
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LeagueSettings {
+    pub league_key: String,
+    pub scoring_type: String,
+    pub draft_type: String,
+    pub stat_modifiers: Vec<StatModifier>,
+    pub roster_slots: Vec<RosterSlot>,
+    /// Yahoo waiver code, e.g. "R" (rolling) or "FR" (continual rolling)
+    pub waiver_type: String,
+    /// Days a dropped player stays on waivers
+    pub waiver_days: u32,
+    /// Set when the league uses free-agent budget bidding
+    pub faab_budget: Option<u32>,
+    pub trade_end_date: Option<String>,
+    pub playoff_start_week: Option<u32>,
+    pub num_playoff_teams: Option<u32>,
+    pub has_playoff_consolation_games: bool,
+    pub uses_playoff_reseeding: bool,
+    /// Rules for ordering teams with the same record, applied in order
+    #[serde(default)]
+    pub tiebreakers: Vec<Tiebreaker>,
+    /// Weekly innings pitched cap (MLB)
+    #[serde(default)]
+    pub max_innings_pitched: Option<u32>,
+    /// Weekly games played cap (NBA, NHL)
+    #[serde(default)]
+    pub max_games_played: Option<u32>,
+    /// Adds allowed per team per week
+    #[serde(default)]
+    pub max_weekly_adds: Option<u32>,
+    /// Adds allowed per team per season
+    #[serde(default)]
+    pub max_season_adds: Option<u32>,
+}
+
+impl LeagueSettings {
+    /// Modifiers scored for a kind of player; "Int" or "Sack" mean different
+    /// things for a quarterback and a team defense
+    pub fn modifiers_for(&self, group: StatGroup) -> impl Iterator<Item = &StatModifier> {
+        self.stat_modifiers.iter().filter(move |m| group.records(&m.display_name))
+    }
+
+    /// Starting slots taking more than one position
+    pub fn flex_slots(&self) -> impl Iterator<Item = &RosterSlot> {
+        self.roster_slots.iter().filter(|s| s.is_starting && s.is_flex())
+    }
+
+    /// Whether teams can start a second quarterback in a flex slot
+    pub fn is_superflex(&self) -> bool {
+        self.flex_slots().any(RosterSlot::is_superflex)
+    }
+}
+
+/// A rule for ordering teams with the same win percentage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Tiebreaker {
+    /// Combined record in games among the tied teams
+    #[serde(alias = "h2h")]
+    HeadToHead,
+    /// Most points scored
+    #[serde(alias = "points")]
+    PointsFor,
+    /// Fewest points allowed
+    PointsAgainst,
+}
+
+impl Tiebreaker {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tiebreaker::HeadToHead => "head_to_head",
+            Tiebreaker::PointsFor => "points_for",
+            Tiebreaker::PointsAgainst => "points_against",
+        }
+    }
+}
+
+impl std::fmt::Display for Tiebreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Accepts the serialized names plus Yahoo's spellings, e.g. "Head-to-Head" or "h2h"
+impl std::str::FromStr for Tiebreaker {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+            "head_to_head" | "h2h" => Ok(Tiebreaker::HeadToHead),
+            "points_for" | "points" => Ok(Tiebreaker::PointsFor),
+            "points_against" => Ok(Tiebreaker::PointsAgainst),
+            _ => Err(format!("unknown tiebreaker: {}", s)),
+        }
+    }
+}
+
+/// Kind of league transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionType {
+    Add,
+    Drop,
+    #[serde(rename = "add/drop")]
+    AddDrop,
+    Trade,
+    /// Commissioner action
+    Commish,
+}
+
+/// One player's movement within a transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TransactionPlayer {
+    pub player_key: String,
+    pub name: String,
+    /// Team losing the player; `None` for free agents and waivers
+    pub source_team_key: Option<String>,
+    /// Team gaining the player; `None` when dropped
+    pub destination_team_key: Option<String>,
+}
+
+/// An entry from a league's transaction log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Transaction {
+    pub transaction_key: String,
+    #[serde(rename = "type")]
+    pub kind: TransactionType,
+    pub status: String,
+    /// Unix seconds
+    pub timestamp: i64,
+    pub players: Vec<TransactionPlayer>,
+    /// Set when a commissioner executed the move on a team's behalf
+    #[serde(default)]
+    pub by_commissioner: bool,
+}
+
+impl Transaction {
+    pub fn is_commissioner_action(&self) -> bool {
+        self.by_commissioner || self.kind == TransactionType::Commish
+    }
+}
+
+/// A player on a team's roster for one week or date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RosterPlayer {
+    pub player_key: String,
+    pub name: String,
+    pub eligible_positions: Vec<String>,
+    /// Slot the player is in, e.g. "WR" or "BN"
+    pub selected_position: String,
+    /// Injury or availability designation such as "O" or "IL"; `None` when healthy
+    pub status: Option<String>,
+    /// Whether the player's real team plays during the coverage period
+    pub has_game: bool,
+}
+
+impl RosterPlayer {
+    pub fn positions(&self) -> Vec<Position> {
+        self.eligible_positions.iter().map(|p| p.parse().unwrap_or_else(|never| match never {})).collect()
+    }
+
+    /// Whether this is an NFL team defense, by key or position
+    pub fn is_team_defense(&self) -> bool {
+        position::is_team_defense_key(&self.player_key) || self.positions().iter().any(Position::is_team_level)
+    }
+
+    /// Stats the player records, from their primary (first) position
+    pub fn stat_group(&self) -> StatGroup {
+        if self.is_team_defense() {
+            return StatGroup::TeamDefense;
+        }
+        self.positions().first().map_or(StatGroup::Other, Position::stat_group)
+    }
+}
+
+/// A team's roster for one week or date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Roster {
+    pub team_key: String,
+    pub players: Vec<RosterPlayer>,
+}
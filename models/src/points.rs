@@ -0,0 +1,255 @@
+//! Fantasy point values
+//! `f64` by default; exact decimal arithmetic with the `decimal` feature
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[cfg(feature = "decimal")]
+type Repr = rust_decimal::Decimal;
+#[cfg(not(feature = "decimal"))]
+type Repr = f64;
+
+/// Float deltas below this are rounding noise; decimal deltas are exact
+#[cfg(not(feature = "decimal"))]
+const NEGLIGIBLE: f64 = 0.005;
+
+/// Fantasy points, stat modifiers, and other fractional scoring values
+#[derive(Clone, Copy, Default)]
+pub struct Points(Repr);
+
+impl Points {
+    #[cfg(feature = "decimal")]
+    pub const ZERO: Points = Points(rust_decimal::Decimal::ZERO);
+    #[cfg(not(feature = "decimal"))]
+    pub const ZERO: Points = Points(0.0);
+
+    /// Convert from a float; `None` for NaN, infinities, and, with `decimal`,
+    /// values out of its range. With `decimal` this rounds to 4 places.
+    pub fn from_f64(value: f64) -> Option<Self> {
+        #[cfg(feature = "decimal")]
+        {
+            exact_decimal(value).map(|value| Points(value.round_dp(4)))
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            value.is_finite().then_some(Points(value))
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        #[cfg(feature = "decimal")]
+        {
+            use rust_decimal::prelude::ToPrimitive;
+            self.0.to_f64().unwrap_or_default()
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            self.0
+        }
+    }
+
+    pub fn abs(self) -> Self {
+        Points(self.0.abs())
+    }
+
+    /// Multiply by a float factor (e.g. a stat modifier or projection weight);
+    /// `None` if the factor isn't finite or the product overflows
+    pub fn scale(self, factor: f64) -> Option<Self> {
+        #[cfg(feature = "decimal")]
+        {
+            self.0.checked_mul(exact_decimal(factor)?).map(Points)
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            let product = self.0 * factor;
+            product.is_finite().then_some(Points(product))
+        }
+    }
+
+    /// Round to `places` decimal places (Yahoo reports two)
+    pub fn round_to(self, places: u32) -> Self {
+        #[cfg(feature = "decimal")]
+        {
+            Points(self.0.round_dp(places))
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            let factor = 10f64.powi(places as i32);
+            Points((self.0 * factor).round() / factor)
+        }
+    }
+
+    /// True if the value is zero, allowing for float rounding noise
+    pub fn is_negligible(self) -> bool {
+        #[cfg(feature = "decimal")]
+        {
+            self.0.is_zero()
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            self.0.abs() < NEGLIGIBLE
+        }
+    }
+
+    fn parse(raw: &str) -> Result<Self, String> {
+        raw.trim()
+            .parse::<Repr>()
+            .map(Points)
+            .map_err(|e| format!("invalid points value {:?}: {}", raw, e))
+    }
+}
+
+/// The decimal a float prints as, so `0.1` becomes exactly `0.1` rather than
+/// the nearest binary fraction
+#[cfg(feature = "decimal")]
+fn exact_decimal(value: f64) -> Option<rust_decimal::Decimal> {
+    if !value.is_finite() {
+        return None;
+    }
+    value.to_string().parse().ok()
+}
+
+impl PartialEq for Points {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Points {}
+
+impl PartialOrd for Points {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Points {
+    fn cmp(&self, other: &Self) -> Ordering {
+        #[cfg(feature = "decimal")]
+        {
+            self.0.cmp(&other.0)
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            self.0.total_cmp(&other.0)
+        }
+    }
+}
+
+impl Add for Points {
+    type Output = Points;
+    fn add(self, rhs: Points) -> Points {
+        Points(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Points {
+    type Output = Points;
+    fn sub(self, rhs: Points) -> Points {
+        Points(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Points {
+    type Output = Points;
+    fn neg(self) -> Points {
+        Points(-self.0)
+    }
+}
+
+impl AddAssign for Points {
+    fn add_assign(&mut self, rhs: Points) {
+        self.0 = self.0 + rhs.0;
+    }
+}
+
+impl SubAssign for Points {
+    fn sub_assign(&mut self, rhs: Points) {
+        self.0 = self.0 - rhs.0;
+    }
+}
+
+impl Sum for Points {
+    fn sum<I: Iterator<Item = Points>>(iter: I) -> Points {
+        iter.fold(Points::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Points> for Points {
+    fn sum<I: Iterator<Item = &'a Points>>(iter: I) -> Points {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Debug for Points {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Display for Points {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl std::str::FromStr for Points {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Points::parse(s)
+    }
+}
+
+/// Floats serialize as numbers; decimals as strings so no precision is lost
+impl Serialize for Points {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "decimal")]
+        {
+            serializer.serialize_str(&self.0.to_string())
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            serializer.serialize_f64(self.0)
+        }
+    }
+}
+
+/// Accepts numbers or Yahoo's string-encoded values like `"87.50"`
+impl<'de> Deserialize<'de> for Points {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Text(String),
+            Number(f64),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Text(text) => Points::parse(&text).map_err(serde::de::Error::custom),
+            Raw::Number(number) => Points::from_f64(number).ok_or_else(|| serde::de::Error::custom(format!("points value {} is out of range", number))),
+        }
+    }
+}
+
+/// Describes the serialized form: a number, or a decimal string with `decimal`
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Points {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Points".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        #[cfg(feature = "decimal")]
+        {
+            schemars::json_schema!({ "type": "string", "pattern": "^-?[0-9]+(\\.[0-9]+)?$" })
+        }
+        #[cfg(not(feature = "decimal"))]
+        {
+            schemars::json_schema!({ "type": "number" })
+        }
+    }
+}
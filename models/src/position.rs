@@ -0,0 +1,222 @@
+//! Player positions and stat groups
+//! Parsed position codes, including team-level NFL defenses, kickers, and individual defensive players (IDP),
+//! and which stats each kind of player records
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Yahoo player ids at or above this are NFL team defenses, e.g. `nfl.p.100012`
+const TEAM_DEFENSE_ID_START: u64 = 100_000;
+
+/// A player position as Yahoo spells it, e.g. "QB" or "DEF"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Position {
+    QB,
+    RB,
+    WR,
+    TE,
+    /// Kicker
+    K,
+    /// NFL team defense/special teams, a team-level "player"
+    DEF,
+    /// Defensive end (IDP)
+    DE,
+    /// Defensive tackle (IDP)
+    DT,
+    /// Defensive lineman, covering DE and DT (IDP)
+    DL,
+    /// Linebacker (IDP)
+    LB,
+    /// Cornerback (IDP)
+    CB,
+    /// Safety (IDP)
+    S,
+    /// Defensive back, covering CB and S (IDP)
+    DB,
+    /// Any individual defensive player; Yahoo's IDP flex slot
+    D,
+    /// Positions of other sports, kept verbatim
+    Other(String),
+}
+
+/// Which stats a player records, and so which scoring modifiers apply to them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum StatGroup {
+    /// Passing, rushing, receiving, and fumbles
+    Offense,
+    /// Field goals and extra points by distance
+    Kicking,
+    /// Sacks, takeaways, defensive scores, and points allowed, credited to the whole team
+    TeamDefense,
+    /// Tackles, sacks, takeaways, and pass defense by one defensive player
+    IndividualDefense,
+    Other,
+}
+
+/// Kicking stat abbreviations, as in `StatModifier::display_name`
+pub const KICKING_STATS: &[&str] = &[
+    "FG 0-19", "FG 20-29", "FG 30-39", "FG 40-49", "FG 50+", "FGM 0-19", "FGM 20-29", "FGM 30-39", "FGM 40-49", "FGM 50+", "PAT Made",
+    "PAT Miss",
+];
+
+/// Team defense stat abbreviations, as in `StatModifier::display_name`
+pub const TEAM_DEFENSE_STATS: &[&str] = &[
+    "Pts Allow", "Sack", "Int", "Fum Rec", "TD", "Safe", "Blk Kick", "Ret TD", "Pts Allow 0", "Pts Allow 1-6", "Pts Allow 7-13", "Pts Allow 14-20",
+    "Pts Allow 21-27", "Pts Allow 28-34", "Pts Allow 35+", "Yds Allow",
+];
+
+/// Individual defensive player stat abbreviations, as in `StatModifier::display_name`
+pub const IDP_STATS: &[&str] = &[
+    "Tack Solo", "Tack Ast", "Tack Loss", "Sack", "Int", "Fum Force", "Fum Rec", "TD", "Safe", "Pass Def", "Blk Kick", "QB Hit",
+];
+
+/// Offensive stat abbreviations, as in `StatModifier::display_name`
+pub const OFFENSE_STATS: &[&str] = &[
+    "Pass Att", "Comp", "Inc", "Pass Yds", "Pass TD", "Int", "Sack", "Rush Att", "Rush Yds", "Rush TD", "Rec", "Rec Yds", "Rec TD", "Ret Yds",
+    "Ret TD", "2-PT", "Fum", "Fum Lost", "Fum Ret TD",
+];
+
+impl Position {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Position::QB => "QB",
+            Position::RB => "RB",
+            Position::WR => "WR",
+            Position::TE => "TE",
+            Position::K => "K",
+            Position::DEF => "DEF",
+            Position::DE => "DE",
+            Position::DT => "DT",
+            Position::DL => "DL",
+            Position::LB => "LB",
+            Position::CB => "CB",
+            Position::S => "S",
+            Position::DB => "DB",
+            Position::D => "D",
+            Position::Other(code) => code,
+        }
+    }
+
+    /// Whether the "player" is a whole team rather than a person
+    pub fn is_team_level(&self) -> bool {
+        *self == Position::DEF
+    }
+
+    /// Whether this is an individual defensive player position or slot
+    pub fn is_idp(&self) -> bool {
+        matches!(
+            self,
+            Position::DE | Position::DT | Position::DL | Position::LB | Position::CB | Position::S | Position::DB | Position::D
+        )
+    }
+
+    /// Whether a player at this position can fill a `slot`, e.g. a DE in a DL
+    /// slot or any defensive player in a D slot
+    pub fn fills(&self, slot: &Position) -> bool {
+        match slot {
+            _ if self == slot => true,
+            Position::DL => matches!(self, Position::DE | Position::DT),
+            Position::DB => matches!(self, Position::CB | Position::S),
+            Position::D => self.is_idp(),
+            _ => false,
+        }
+    }
+
+    pub fn stat_group(&self) -> StatGroup {
+        match self {
+            Position::QB | Position::RB | Position::WR | Position::TE => StatGroup::Offense,
+            Position::K => StatGroup::Kicking,
+            Position::DEF => StatGroup::TeamDefense,
+            Position::DE | Position::DT | Position::DL | Position::LB | Position::CB | Position::S | Position::DB | Position::D => {
+                StatGroup::IndividualDefense
+            }
+            Position::Other(_) => StatGroup::Other,
+        }
+    }
+}
+
+impl StatGroup {
+    /// Whether a stat, by abbreviation, is scored for this group. Some
+    /// abbreviations, like "Int" and "Sack", mean different things per group.
+    pub fn records(&self, display_name: &str) -> bool {
+        match self {
+            StatGroup::Offense => OFFENSE_STATS.contains(&display_name),
+            StatGroup::Kicking => KICKING_STATS.contains(&display_name),
+            StatGroup::TeamDefense => TEAM_DEFENSE_STATS.contains(&display_name),
+            StatGroup::IndividualDefense => IDP_STATS.contains(&display_name),
+            StatGroup::Other => true,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Accepts Yahoo's codes plus common aliases like "D/ST", "DST", and "PK"
+impl FromStr for Position {
+    type Err = std::convert::Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_uppercase().as_str() {
+            "QB" => Position::QB,
+            "RB" => Position::RB,
+            "WR" => Position::WR,
+            "TE" => Position::TE,
+            "K" | "PK" => Position::K,
+            "DEF" | "D/ST" | "DST" => Position::DEF,
+            "DE" => Position::DE,
+            "DT" => Position::DT,
+            "DL" => Position::DL,
+            "LB" => Position::LB,
+            "CB" => Position::CB,
+            "S" => Position::S,
+            "DB" => Position::DB,
+            "D" | "IDP" => Position::D,
+            _ => Position::Other(s.trim().to_string()),
+        })
+    }
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Ok(code.parse().unwrap_or_else(|never| match never {}))
+    }
+}
+
+/// Describes the serialized form: a position code, which for sports other
+/// than NFL football is any of Yahoo's codes
+#[cfg(feature = "schemars")]
+impl schemars::JsonSchema for Position {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "Position".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "examples": ["QB", "RB", "WR", "TE", "K", "DEF", "DE", "DT", "DL", "LB", "CB", "S", "DB", "D"]
+        })
+    }
+}
+
+/// Whether a player key belongs to an NFL team defense, e.g. `nfl.p.100012`
+/// or `449.p.100012`
+pub fn is_team_defense_key(player_key: &str) -> bool {
+    player_key
+        .rsplit_once(".p.")
+        .and_then(|(_, id)| id.parse::<u64>().ok())
+        .is_some_and(|id| id >= TEAM_DEFENSE_ID_START)
+}
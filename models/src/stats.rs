@@ -0,0 +1,102 @@
+//! Player stat lines
+//! Totals over a coverage window and per-date game logs
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::Points;
+
+/// Date format used in stats selectors
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Period a stat line covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum StatCoverage {
+    /// The current season to date
+    Season,
+    Week(u32),
+    Date(NaiveDate),
+    /// The last 7 days
+    LastWeek,
+    /// The last 30 days
+    LastMonth,
+}
+
+impl StatCoverage {
+    /// Stats selector, e.g. `type=lastmonth` or `type=week;week=5`
+    pub fn param(&self) -> String {
+        match self {
+            StatCoverage::Season => "type=season".to_string(),
+            StatCoverage::Week(week) => format!("type=week;week={}", week),
+            StatCoverage::Date(date) => format!("type=date;date={}", date.format(DATE_FORMAT)),
+            StatCoverage::LastWeek => "type=lastweek".to_string(),
+            StatCoverage::LastMonth => "type=lastmonth".to_string(),
+        }
+    }
+
+    /// Players collection sort, e.g. `sort_type=lastmonth` or `sort_type=week;sort_week=5`
+    pub fn sort_param(&self) -> String {
+        match self {
+            StatCoverage::Season => "sort_type=season".to_string(),
+            StatCoverage::Week(week) => format!("sort_type=week;sort_week={}", week),
+            StatCoverage::Date(date) => format!("sort_type=date;sort_date={}", date.format(DATE_FORMAT)),
+            StatCoverage::LastWeek => "sort_type=lastweek".to_string(),
+            StatCoverage::LastMonth => "sort_type=lastmonth".to_string(),
+        }
+    }
+}
+
+/// A player's stat totals over a coverage window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PlayerStats {
+    pub player_key: String,
+    pub coverage: StatCoverage,
+    /// Stat values keyed by stat abbreviation
+    pub stats: BTreeMap<String, f64>,
+    pub points: Points,
+}
+
+/// A free agent with their stats in the search's ranking window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FreeAgent {
+    pub player_key: String,
+    pub name: String,
+    pub eligible_positions: Vec<String>,
+    pub stats: PlayerStats,
+}
+
+/// One player's stats for a single coverage date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DailyStatLine {
+    pub date: NaiveDate,
+    /// False on off days and DNPs
+    pub played: bool,
+    /// Stat values keyed by stat abbreviation
+    pub stats: BTreeMap<String, f64>,
+    pub points: Points,
+}
+
+/// A player's stat lines over a date range, in date order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PlayerGameLog {
+    pub player_key: String,
+    pub lines: Vec<DailyStatLine>,
+}
+
+impl PlayerGameLog {
+    /// Lines for dates the player actually played
+    pub fn games(&self) -> impl Iterator<Item = &DailyStatLine> {
+        self.lines.iter().filter(|l| l.played)
+    }
+
+    pub fn games_played(&self) -> usize {
+        self.games().count()
+    }
+}
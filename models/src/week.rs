@@ -0,0 +1,21 @@
+//! Fantasy weeks
+//! Date ranges only; instants within a week depend on Yahoo's time zone and live in the SDK
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Inclusive date range of a fantasy week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct WeekRange {
+    pub week: u32,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl WeekRange {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+}
@@ -0,0 +1,69 @@
+//! JSON Schemas for the public models, behind the `schemars` feature
+
+#![cfg(feature = "schemars")]
+
+use schemars::{schema_for, JsonSchema};
+use yahoo_fantasy_models::*;
+
+fn properties<T: JsonSchema>() -> Vec<String> {
+    let schema = schema_for!(T);
+    let mut names: Vec<String> = schema.get("properties").and_then(|p| p.as_object()).map(|p| p.keys().cloned().collect()).unwrap_or_default();
+    names.sort();
+    names
+}
+
+#[test]
+fn every_model_has_a_schema() {
+    let schemas = [
+        schema_for!(AcquisitionUsage),
+        schema_for!(CapKind),
+        schema_for!(CapUsage),
+        schema_for!(DailyStatLine),
+        schema_for!(DraftPick),
+        schema_for!(DraftPlayer),
+        schema_for!(DraftStatus),
+        schema_for!(FreeAgent),
+        schema_for!(Game),
+        schema_for!(HeadToHeadMatrix),
+        schema_for!(HeadToHeadRecord),
+        schema_for!(League),
+        schema_for!(LeagueSearchFilters),
+        schema_for!(LeagueSettings),
+        schema_for!(LeagueStatus),
+        schema_for!(LimitPeriod),
+        schema_for!(PlayerGameLog),
+        schema_for!(PlayerStats),
+        schema_for!(Position),
+        schema_for!(PublicLeagueSummary),
+        schema_for!(Roster),
+        schema_for!(Scoreboard),
+        schema_for!(Standings),
+        schema_for!(StatCoverage),
+        schema_for!(StatGroup),
+        schema_for!(Transaction),
+        schema_for!(WeekRange),
+    ];
+    assert!(schemas.iter().all(|schema| schema.get("title").is_some()));
+}
+
+#[test]
+fn moved_models_describe_their_fields() {
+    assert_eq!(properties::<WeekRange>(), ["end", "start", "week"]);
+    assert_eq!(properties::<DraftPick>(), ["cost", "pick", "player_key", "round", "team_key"]);
+    assert_eq!(properties::<CapUsage>(), ["kind", "limit", "used"]);
+
+    let week = schema_for!(WeekRange);
+    assert_eq!(week.as_value()["properties"]["start"]["format"], "date");
+}
+
+#[test]
+fn positions_are_codes_and_stat_groups_are_snake_case() {
+    let position = schema_for!(Position);
+    assert_eq!(position.as_value()["type"], "string");
+    assert!(position.as_value()["examples"].as_array().unwrap().contains(&"DEF".into()));
+
+    let groups = schema_for!(StatGroup).as_value().to_string();
+    for name in ["\"offense\"", "\"kicking\"", "\"team_defense\"", "\"individual_defense\"", "\"other\""] {
+        assert!(groups.contains(name), "{} missing from {}", name, groups);
+    }
+}
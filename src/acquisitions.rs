@@ -0,0 +1,42 @@
+//! Acquisition limits
+//! Weekly and season add caps from league settings, counted against a team's transaction log
+
+use chrono::{DateTime, Utc};
+use crate::time::WeekInstants;
+use crate::{AcquisitionUsage, Transaction, TransactionType, WeekRange, YahooFantasyClient};
+
+/// Count `team_key`'s adds in a transaction log against `usage`: all of them
+/// toward the season limit, and those during `week` toward the weekly one
+pub fn count_adds(mut usage: AcquisitionUsage, transactions: &[Transaction], team_key: &str, week: &WeekRange) -> AcquisitionUsage {
+    for transaction in transactions.iter().filter(|t| counts_toward_limit(t)) {
+        let adds = transaction
+            .players
+            .iter()
+            .filter(|p| p.source_team_key.is_none() && p.destination_team_key.as_deref() == Some(team_key))
+            .count() as u32;
+        usage.season_used += adds;
+        if DateTime::<Utc>::from_timestamp(transaction.timestamp, 0).is_some_and(|at| week.contains_instant(&at)) {
+            usage.weekly_used += adds;
+        }
+    }
+    usage
+}
+
+/// Completed adds a team made itself; commissioner moves and trades don't count
+fn counts_toward_limit(transaction: &Transaction) -> bool {
+    matches!(transaction.kind, TransactionType::Add | TransactionType::AddDrop) && transaction.status == "successful" && !transaction.is_commissioner_action()
+}
+
+impl YahooFantasyClient {
+    /// A team's adds against its league's acquisition limits, counting this
+    /// week's by `week`, from the league settings and transaction log
+    pub async fn get_acquisition_usage(&self, team_key: &str, week: &WeekRange) -> Result<AcquisitionUsage, Box<dyn std::error::Error + Send + Sync>> {
+        let league_key = team_key
+            .rsplit_once(".t.")
+            .map(|(league, _)| league)
+            .ok_or_else(|| format!("invalid team key {}", team_key))?;
+        let settings = self.get_league_settings(league_key).await?;
+        let transactions = self.get_transactions(league_key).await?;
+        Ok(count_adds(AcquisitionUsage::for_settings(&settings), &transactions, team_key, week))
+    }
+}
@@ -0,0 +1,174 @@
+//! Arena-allocated bulk parsing
+//! Parses whole player pools into one bump arena that is freed at once, for batch analytics jobs
+
+use std::borrow::Cow;
+use std::fmt;
+
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+use crate::player_ref::{EligiblePosition, Fields, Fragments, Name, PlayerPointsBlock, PlayerSink, Root, SelectedPosition, Text};
+use crate::{PlayerRef, Points};
+
+/// Arena a batch job's responses are parsed into. Everything parsed lives until
+/// the batch is dropped or `reset`, so parsing a full player pool costs a few
+/// large allocations instead of several per player.
+#[derive(Debug, Default)]
+pub struct ParsedBatch {
+    arena: Bump,
+}
+
+impl ParsedBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Batch whose arena starts with room for `bytes`, e.g. twice the size of the responses to parse
+    pub fn with_capacity(bytes: usize) -> Self {
+        Self {
+            arena: Bump::with_capacity(bytes),
+        }
+    }
+
+    /// Copy `body` into the arena and parse every player in its `players`
+    /// collections, in order. Text is borrowed from the arena's copy, and only
+    /// values with escapes to decode take more arena space.
+    pub fn players(&self, body: &str) -> Result<&[BatchPlayer<'_>], serde_json::Error> {
+        let body = self.arena.alloc_str(body);
+        let mut sink = ArenaSink {
+            arena: &self.arena,
+            players: BumpVec::new_in(&self.arena),
+        };
+        let mut deserializer = serde_json::Deserializer::from_str(body);
+        Fragments(&mut Root(&mut sink)).deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(sink.players.into_bump_slice())
+    }
+
+    /// Bytes the arena has taken from the allocator
+    pub fn allocated_bytes(&self) -> usize {
+        self.arena.allocated_bytes()
+    }
+
+    /// Free everything parsed so far, keeping the arena's memory for the next job
+    pub fn reset(&mut self) {
+        self.arena.reset();
+    }
+}
+
+/// A player parsed into a `ParsedBatch`, borrowing everything from its arena
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatchPlayer<'a> {
+    pub player_key: &'a str,
+    pub name: &'a str,
+    pub editorial_team_abbr: Option<&'a str>,
+    pub display_position: Option<&'a str>,
+    pub eligible_positions: &'a [&'a str],
+    /// Slot the player is in, for roster collections
+    pub selected_position: Option<&'a str>,
+    /// Injury or availability designation; `None` when healthy
+    pub status: Option<&'a str>,
+    /// Fantasy points, when the request asked for them
+    pub points: Option<Points>,
+}
+
+impl<'a> BatchPlayer<'a> {
+    /// The same player as a `PlayerRef`, for its conversions into the standard models
+    pub fn to_player_ref(&self) -> PlayerRef<'a> {
+        PlayerRef {
+            player_key: Cow::Borrowed(self.player_key),
+            name: Cow::Borrowed(self.name),
+            editorial_team_abbr: self.editorial_team_abbr.map(Cow::Borrowed),
+            display_position: self.display_position.map(Cow::Borrowed),
+            eligible_positions: self.eligible_positions.iter().copied().map(Cow::Borrowed).collect(),
+            selected_position: self.selected_position.map(Cow::Borrowed),
+            status: self.status.map(Cow::Borrowed),
+            points: self.points,
+        }
+    }
+}
+
+/// Collects players into the arena
+struct ArenaSink<'a> {
+    arena: &'a Bump,
+    players: BumpVec<'a, BatchPlayer<'a>>,
+}
+
+impl<'a> PlayerSink<'a> for ArenaSink<'a> {
+    fn player<A: MapAccess<'a>>(&mut self, map: &mut A) -> Result<(), A::Error> {
+        let mut player = ArenaPlayer {
+            arena: self.arena,
+            player: BatchPlayer::default(),
+        };
+        map.next_value_seed(Fragments(&mut player))?;
+        self.players.push(player.player);
+        Ok(())
+    }
+}
+
+/// A player being parsed, with the arena its decoded text goes into
+struct ArenaPlayer<'a> {
+    arena: &'a Bump,
+    player: BatchPlayer<'a>,
+}
+
+impl<'a> Fields<'a> for ArenaPlayer<'a> {
+    fn field<A: MapAccess<'a>>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error> {
+        let arena = self.arena;
+        let player = &mut self.player;
+        match key {
+            "player_key" => player.player_key = in_arena(arena, map.next_value::<Text>()?.0),
+            "name" => player.name = in_arena(arena, map.next_value::<Name>()?.full),
+            "editorial_team_abbr" => player.editorial_team_abbr = Some(in_arena(arena, map.next_value::<Text>()?.0)),
+            "display_position" => player.display_position = Some(in_arena(arena, map.next_value::<Text>()?.0)),
+            "eligible_positions" => player.eligible_positions = map.next_value_seed(Positions(arena))?,
+            "status" => player.status = Some(in_arena(arena, map.next_value::<Text>()?.0)).filter(|s| !s.is_empty()),
+            "selected_position" => {
+                let mut selected = None;
+                map.next_value_seed(Fragments(&mut SelectedPosition(&mut selected)))?;
+                player.selected_position = selected.map(|s| in_arena(arena, s));
+            }
+            "player_points" => player.points = Some(map.next_value::<PlayerPointsBlock>()?.points()),
+            _ => {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Text borrowed from the arena's copy of the body, or decoded into the arena
+fn in_arena<'a>(arena: &'a Bump, text: Cow<'a, str>) -> &'a str {
+    match text {
+        Cow::Borrowed(text) => text,
+        Cow::Owned(text) => arena.alloc_str(&text),
+    }
+}
+
+/// `eligible_positions`, collected into the arena
+struct Positions<'a>(&'a Bump);
+
+impl<'a> DeserializeSeed<'a> for Positions<'a> {
+    type Value = &'a [&'a str];
+
+    fn deserialize<D: Deserializer<'a>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a> Visitor<'a> for Positions<'a> {
+    type Value = &'a [&'a str];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a list of positions")
+    }
+
+    fn visit_seq<A: SeqAccess<'a>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut positions = BumpVec::new_in(self.0);
+        while let Some(EligiblePosition { position }) = seq.next_element()? {
+            positions.push(in_arena(self.0, position));
+        }
+        Ok(positions.into_bump_slice())
+    }
+}
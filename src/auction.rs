@@ -0,0 +1,161 @@
+//! Auction draft helpers
+//! Budget tracking, inflation-adjusted dollar values, and nomination suggestions
+
+use std::collections::HashMap;
+
+use crate::{DraftBoard, DraftPick, DraftPlayer};
+
+/// Every roster spot costs at least this much
+pub const MIN_BID: u32 = 1;
+
+/// Budget state for one team
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeamBudget {
+    pub team_key: String,
+    pub spent: u32,
+    pub remaining: u32,
+    pub open_spots: u32,
+    /// Most the team can bid while still filling every spot at the minimum
+    pub max_bid: u32,
+}
+
+/// A player worth nominating and why
+#[derive(Debug, Clone)]
+pub struct Nomination {
+    pub player: DraftPlayer,
+    pub inflated_value: f64,
+    /// Teams that can still afford the inflated value
+    pub bidders: usize,
+}
+
+/// Tracks auction spending and converts player values into dollars
+#[derive(Debug, Clone)]
+pub struct AuctionTracker {
+    budget: u32,
+    roster_size: u32,
+    spent: HashMap<String, u32>,
+    filled: HashMap<String, u32>,
+    /// Pre-draft dollar value per player
+    baseline: HashMap<String, f64>,
+}
+
+impl AuctionTracker {
+    /// `players` is the full pre-draft pool; baseline dollar values are assigned by
+    /// value over replacement across the players expected to be drafted
+    pub fn new(team_keys: &[String], budget: u32, roster_size: u32, players: &[DraftPlayer]) -> Self {
+        let draftable = team_keys.len() * roster_size as usize;
+        let mut pool: Vec<&DraftPlayer> = players.iter().collect();
+        pool.sort_by_key(|p| std::cmp::Reverse(p.value));
+        pool.truncate(draftable);
+
+        let replacement = pool.last().map_or(0.0, |p| p.value.to_f64());
+        let surplus: Vec<f64> = pool.iter().map(|p| (p.value.to_f64() - replacement).max(0.0)).collect();
+        let total_surplus: f64 = surplus.iter().sum();
+        let total_money = (budget as f64) * team_keys.len() as f64;
+        // Each drafted player costs at least MIN_BID; the rest is spread by surplus value
+        let spendable = (total_money - (MIN_BID as f64) * pool.len() as f64).max(0.0);
+
+        let baseline = pool
+            .iter()
+            .zip(surplus)
+            .map(|(p, s)| {
+                let share = if total_surplus > 0.0 { s / total_surplus } else { 0.0 };
+                (p.player_key.clone(), MIN_BID as f64 + spendable * share)
+            })
+            .collect();
+
+        Self {
+            budget,
+            roster_size,
+            spent: team_keys.iter().map(|t| (t.clone(), 0)).collect(),
+            filled: team_keys.iter().map(|t| (t.clone(), 0)).collect(),
+            baseline,
+        }
+    }
+
+    /// Apply a completed auction pick
+    pub fn record_pick(&mut self, pick: &DraftPick) {
+        *self.spent.entry(pick.team_key.clone()).or_default() += pick.cost.unwrap_or(MIN_BID);
+        *self.filled.entry(pick.team_key.clone()).or_default() += 1;
+    }
+
+    pub fn budget(&self, team_key: &str) -> TeamBudget {
+        let spent = self.spent.get(team_key).copied().unwrap_or(0);
+        let remaining = self.budget.saturating_sub(spent);
+        let open_spots = self.roster_size.saturating_sub(self.filled.get(team_key).copied().unwrap_or(0));
+        let max_bid = if open_spots == 0 {
+            0
+        } else {
+            remaining.saturating_sub((open_spots - 1) * MIN_BID)
+        };
+        TeamBudget {
+            team_key: team_key.to_string(),
+            spent,
+            remaining,
+            open_spots,
+            max_bid,
+        }
+    }
+
+    pub fn budgets(&self) -> Vec<TeamBudget> {
+        let mut teams: Vec<&String> = self.spent.keys().collect();
+        teams.sort();
+        teams.into_iter().map(|t| self.budget(t)).collect()
+    }
+
+    /// Money left relative to the baseline value of players still on the board.
+    /// Above 1.0, remaining players will go for more than their pre-draft value.
+    pub fn inflation(&self, board: &DraftBoard) -> f64 {
+        let money_left: u32 = self.budgets().iter().map(|b| b.remaining).sum();
+        let value_left: f64 = board
+            .available()
+            .iter()
+            .filter_map(|p| self.baseline.get(&p.player_key))
+            .sum();
+        if value_left > 0.0 {
+            money_left as f64 / value_left
+        } else {
+            1.0
+        }
+    }
+
+    pub fn baseline_value(&self, player_key: &str) -> f64 {
+        self.baseline.get(player_key).copied().unwrap_or(MIN_BID as f64)
+    }
+
+    /// Dollar value adjusted for the money left on the board
+    pub fn inflated_value(&self, board: &DraftBoard, player_key: &str) -> f64 {
+        self.baseline_value(player_key) * self.inflation(board)
+    }
+
+    /// Players to nominate: expensive players at positions my team no longer
+    /// needs, so rivals spend budget on players I wasn't going to buy
+    pub fn nomination_suggestions(&self, board: &DraftBoard, limit: usize) -> Vec<Nomination> {
+        let needed: Vec<String> = board.needs().into_iter().map(|n| n.slot).collect();
+        let inflation = self.inflation(board);
+        let budgets = self.budgets();
+
+        let mut nominations: Vec<Nomination> = board
+            .available()
+            .iter()
+            .filter(|p| !p.positions.iter().any(|pos| needed.contains(pos)))
+            .map(|player| {
+                let inflated_value = self.baseline_value(&player.player_key) * inflation;
+                let bidders = budgets
+                    .iter()
+                    .filter(|b| b.max_bid as f64 >= inflated_value)
+                    .count();
+                Nomination {
+                    player: player.clone(),
+                    inflated_value,
+                    bidders,
+                }
+            })
+            .filter(|n| n.bidders > 0)
+            .collect();
+
+        nominations.sort_by(|a, b| b.inflated_value.total_cmp(&a.inflated_value));
+        nominations.truncate(limit);
+        nominations
+    }
+}
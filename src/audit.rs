@@ -0,0 +1,141 @@
+//! Commissioner audit trail
+//! Reconstructs commissioner actions from the transaction log, settings changes, and score edits
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::score_edits::ScoreEdit;
+use crate::settings_report::SettingChange;
+use crate::Transaction;
+
+/// Something a commissioner did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    /// Players added or dropped on a team's behalf
+    RosterMove {
+        transaction_key: String,
+        added: Vec<(String, String)>,
+        dropped: Vec<(String, String)>,
+    },
+    /// A league setting changed between two polls
+    SettingChange { change: SettingChange },
+    /// A team total that differs from its recomputed score
+    ScoreEdit { edit: ScoreEdit },
+}
+
+/// A timestamped audit entry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AuditEntry {
+    /// Unix seconds
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub action: AuditAction,
+}
+
+/// Chronological record of commissioner actions for one league
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AuditTrail {
+    pub league_key: String,
+    entries: Vec<AuditEntry>,
+    seen_transactions: HashSet<String>,
+}
+
+impl AuditTrail {
+    pub fn new(league_key: &str) -> Self {
+        Self {
+            league_key: league_key.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Record commissioner actions from a transaction log; transactions already
+    /// recorded are skipped. Returns the number of new entries.
+    pub fn record_transactions(&mut self, transactions: &[Transaction]) -> usize {
+        let mut added = 0;
+        for transaction in transactions {
+            if !transaction.is_commissioner_action() || !self.seen_transactions.insert(transaction.transaction_key.clone()) {
+                continue;
+            }
+            let moved = |to_team: bool| {
+                transaction
+                    .players
+                    .iter()
+                    .filter_map(|p| {
+                        let team = if to_team { &p.destination_team_key } else { &p.source_team_key };
+                        team.as_ref().map(|t| (t.clone(), p.player_key.clone()))
+                    })
+                    .collect()
+            };
+            self.push(AuditEntry {
+                timestamp: transaction.timestamp,
+                action: AuditAction::RosterMove {
+                    transaction_key: transaction.transaction_key.clone(),
+                    added: moved(true),
+                    dropped: moved(false),
+                },
+            });
+            added += 1;
+        }
+        added
+    }
+
+    /// Record settings changes observed when a poll at `observed_at` differed from the last one
+    pub fn record_setting_changes(&mut self, observed_at: &DateTime<impl TimeZone>, changes: Vec<SettingChange>) {
+        for change in changes {
+            self.push(AuditEntry {
+                timestamp: observed_at.timestamp(),
+                action: AuditAction::SettingChange { change },
+            });
+        }
+    }
+
+    /// Record likely manual score adjustments found at `observed_at`; an edit already
+    /// recorded for the same team, week, and totals is skipped so repeated polls don't duplicate it
+    pub fn record_score_edits(&mut self, observed_at: &DateTime<impl TimeZone>, edits: Vec<ScoreEdit>) {
+        for edit in edits {
+            let known = self
+                .entries
+                .iter()
+                .any(|e| matches!(&e.action, AuditAction::ScoreEdit { edit: seen } if *seen == edit));
+            if known {
+                continue;
+            }
+            self.push(AuditEntry {
+                timestamp: observed_at.timestamp(),
+                action: AuditAction::ScoreEdit { edit },
+            });
+        }
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    /// Entries with `start <= timestamp < end`, oldest first
+    pub fn between(&self, start: &DateTime<impl TimeZone>, end: &DateTime<impl TimeZone>) -> &[AuditEntry] {
+        let from = self.entries.partition_point(|e| e.timestamp < start.timestamp());
+        let to = self.entries.partition_point(|e| e.timestamp < end.timestamp());
+        &self.entries[from..to.max(from)]
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Insert keeping entries sorted by time, after any entries with the same timestamp
+    fn push(&mut self, entry: AuditEntry) {
+        let index = self.entries.partition_point(|e| e.timestamp <= entry.timestamp);
+        self.entries.insert(index, entry);
+    }
+}
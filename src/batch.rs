@@ -0,0 +1,114 @@
+//! Multi-key request batching
+//! Splits large key sets into request-sized chunks and tracks per-key outcomes
+
+/// Maximum number of resource keys Yahoo accepts in one collection request
+pub const MAX_KEYS_PER_REQUEST: usize = 25;
+
+/// Split `keys` into chunks of at most `MAX_KEYS_PER_REQUEST`, preserving order
+pub fn chunk_keys(keys: &[String]) -> std::slice::Chunks<'_, String> {
+    keys.chunks(MAX_KEYS_PER_REQUEST)
+}
+
+/// Join keys into a collection selector, e.g. `game_keys=nfl.2024,nba.2024`
+pub fn key_selector(param: &str, keys: &[String]) -> String {
+    format!("{}={}", param, keys.join(","))
+}
+
+/// Whether `requested` names the resource whose canonical key is `key`, where
+/// `separator` splits off the game part, e.g. `.l.`. The requested game may be a
+/// code, as in `nfl.l.12345` for `449.l.12345`.
+pub(crate) fn same_resource(key: &str, requested: &str, separator: &str) -> bool {
+    match (key.split_once(separator), requested.split_once(separator)) {
+        (Some((game, id)), Some((requested_game, requested_id))) => id == requested_id && (game == requested_game || !requested_game.bytes().all(|b| b.is_ascii_digit())),
+        _ => key == requested,
+    }
+}
+
+/// A resource identified by a Yahoo key
+pub trait Keyed {
+    fn key(&self) -> &str;
+
+    /// Whether this resource is what Yahoo returns for `key`. Yahoo accepts
+    /// aliases such as `nfl` for the current season's game, but always answers
+    /// with the canonical key, so resources that have aliases should accept them.
+    fn answers(&self, key: &str) -> bool {
+        self.key() == key
+    }
+}
+
+/// A key that could not be fetched
+#[derive(Debug)]
+pub struct KeyError {
+    pub key: String,
+    pub error: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl std::fmt::Display for KeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.error)
+    }
+}
+
+impl std::error::Error for KeyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+/// Outcome of a batched operation: what was fetched and which keys failed
+#[derive(Debug)]
+pub struct BatchResult<T> {
+    pub succeeded: Vec<T>,
+    pub failed: Vec<KeyError>,
+}
+
+impl<T> Default for BatchResult<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
+
+impl<T> BatchResult<T> {
+    /// True when every requested key was fetched
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    pub fn failed_keys(&self) -> impl Iterator<Item = &str> {
+        self.failed.iter().map(|e| e.key.as_str())
+    }
+
+    /// Merge another batch's outcome into this one
+    pub fn extend(&mut self, other: BatchResult<T>) {
+        self.succeeded.extend(other.succeeded);
+        self.failed.extend(other.failed);
+    }
+
+    /// Record a chunk's response; requested keys missing from it are marked failed
+    pub(crate) fn collect_chunk(&mut self, chunk: &[String], items: Vec<T>)
+    where
+        T: Keyed,
+    {
+        for key in chunk {
+            if !items.iter().any(|item| item.answers(key)) {
+                self.failed.push(KeyError {
+                    key: key.clone(),
+                    error: "key not returned by Yahoo".into(),
+                });
+            }
+        }
+        self.succeeded.extend(items);
+    }
+
+    /// Convert to a plain result, failing if any key failed
+    pub fn into_result(mut self) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.failed.len() {
+            0 => Ok(self.succeeded),
+            1 => Err(Box::new(self.failed.remove(0))),
+            n => Err(format!("{} keys failed, first: {}", n, self.failed[0]).into()),
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Blocking client
+//! Wraps the async client in a shared background runtime for scripts and CLIs that don't run one
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio::runtime::Runtime;
+
+use crate::xml::RosterCoverage;
+use crate::{
+    AccessToken, AcquisitionUsage, BatchResult, Cache, CancellationToken, CapUsage, CircuitState, ClientBuilder, CostEstimate, DailyStatLine, DraftPick, EligibilityCache, FreeAgent, FreeAgentSearch, Game, HeadToHeadMatrix, Keyed, League, LeagueArchive, LeagueSearchFilters, LeagueSettings, LeagueStatus,
+    LineupCopy, LineupRetro, ManagerEfficiency, ManualAuthFlow, Method, Middleware, PlayerCollection, PlayerGameLog, PlayerPoints, PlayerStats, PollWatcher, Progress, PublicLeagueSummary, RateLimiter,
+    Request, RequestToken, Roster, RosterTimeline, Scope, Scoreboard, Standings, StandingsVerification, StatCoverage, StoredToken, TokenStatus, Transaction, WeekRange, Workflow,
+};
+
+/// Runtime shared by every blocking client. Like `reqwest::blocking`, a single
+/// background thread drives timers and I/O while callers block on their futures.
+fn runtime() -> std::io::Result<&'static Runtime> {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("yahoo-fantasy-blocking")
+        .enable_all()
+        .build()?;
+    // A runtime built by a racing thread is dropped; the first one wins
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// Blocking counterpart of [`crate::YahooFantasyClient`] with the same methods.
+/// Calls block the current thread; don't use it from inside an async runtime.
+#[derive(Debug)]
+pub struct YahooFantasyClient {
+    inner: crate::YahooFantasyClient,
+    runtime: &'static Runtime,
+}
+
+/// Fails only if the shared runtime can't be started
+impl TryFrom<crate::YahooFantasyClient> for YahooFantasyClient {
+    type Error = std::io::Error;
+
+    fn try_from(inner: crate::YahooFantasyClient) -> Result<Self, Self::Error> {
+        Ok(Self { inner, runtime: runtime()? })
+    }
+}
+
+impl YahooFantasyClient {
+    /// Create a new blocking Yahoo Fantasy client
+    pub fn new(consumer_key: String, consumer_secret: String) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(crate::YahooFantasyClient::new(consumer_key, consumer_secret)?.try_into()?)
+    }
+
+    /// Start configuring a client; convert the built client with `try_into()`
+    pub fn builder(consumer_key: String, consumer_secret: String) -> ClientBuilder {
+        ClientBuilder::new(consumer_key, consumer_secret)
+    }
+
+    /// Client configured from `YAHOO_*` environment variables, as
+    /// [`crate::YahooFantasyClient::from_env`]
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(crate::YahooFantasyClient::from_env()?.try_into()?)
+    }
+
+    /// The wrapped async client
+    pub fn get_ref(&self) -> &crate::YahooFantasyClient {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> crate::YahooFantasyClient {
+        self.inner
+    }
+
+    pub fn with_middleware(self, middleware: impl Middleware + 'static) -> Self {
+        Self {
+            inner: self.inner.with_middleware(middleware),
+            runtime: self.runtime,
+        }
+    }
+
+    pub fn get_request_token(&self, callback: &str) -> Result<RequestToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_request_token(callback))
+    }
+
+    pub fn get_access_token(&self, request_token: &RequestToken, verifier: &str) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_access_token(request_token, verifier))
+    }
+
+    pub fn login_with_browser(&self, timeout: Duration, show_url: impl FnOnce(&str)) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.login_with_browser(timeout, show_url))
+    }
+
+    pub fn start_manual_auth(&self, scope: Option<Scope>) -> Result<ManualAuthFlow, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.start_manual_auth(scope))
+    }
+
+    pub fn finish_manual_auth(&self, flow: &ManualAuthFlow, verifier: &str) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.finish_manual_auth(flow, verifier))
+    }
+
+    pub fn login_manually(&self, scope: Option<Scope>, input: impl std::io::BufRead, output: impl std::io::Write) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.login_manually(scope, input, output))
+    }
+
+    pub fn reauthorize(&self, scope: Scope, callback: &str) -> Result<RequestToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.reauthorize(scope, callback))
+    }
+
+    pub fn refresh_access_token(&self, token: &AccessToken) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.refresh_access_token(token))
+    }
+
+    /// Client acting as the user who granted `token`, sharing this one's runtime
+    pub fn for_user(&self, token: &AccessToken) -> Self {
+        Self {
+            inner: self.inner.for_user(token),
+            runtime: self.runtime,
+        }
+    }
+
+    /// Sign a server in with a token obtained elsewhere; see [`crate::YahooFantasyClient::bootstrap`]
+    pub fn bootstrap(&self, token: AccessToken) -> Result<StoredToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.bootstrap(token))
+    }
+
+    /// Refresh the token ahead of expiry until `cancel` is triggered from another
+    /// thread; see [`crate::YahooFantasyClient::keep_token_fresh`]
+    pub fn keep_token_fresh(&self, cancel: &CancellationToken, on_error: impl FnMut(&(dyn std::error::Error + Send + Sync))) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.keep_token_fresh(cancel, on_error))
+    }
+
+    pub fn set_tokens(&mut self, access_token: String, access_token_secret: String) {
+        self.inner.set_tokens(access_token, access_token_secret);
+    }
+
+    pub fn set_access_token(&mut self, token: AccessToken) {
+        self.inner.set_access_token(token);
+    }
+
+    pub fn access_token(&self) -> Option<AccessToken> {
+        self.inner.access_token()
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        self.inner.is_authenticated()
+    }
+
+    pub fn token_status(&self) -> Option<TokenStatus> {
+        self.inner.token_status()
+    }
+
+    pub fn token_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.inner.token_expires_at()
+    }
+
+    pub fn consumer_key(&self) -> &str {
+        self.inner.consumer_key()
+    }
+
+    pub fn base_url(&self) -> &str {
+        self.inner.base_url()
+    }
+
+    pub fn request(&self, method: Method, path: &str) -> Request {
+        self.inner.request(method, path)
+    }
+
+    pub fn request_at(&self, base_url: &str, method: Method, path: &str) -> Request {
+        self.inner.request_at(base_url, method, path)
+    }
+
+    pub fn user_agent(&self) -> &str {
+        self.inner.user_agent()
+    }
+
+    pub fn default_headers(&self) -> &[(String, String)] {
+        self.inner.default_headers()
+    }
+
+    pub fn eligibility(&self) -> &EligibilityCache {
+        self.inner.eligibility()
+    }
+
+    pub fn circuit_state(&self) -> CircuitState {
+        self.inner.circuit_state()
+    }
+
+    pub fn reset_circuit(&self) {
+        self.inner.reset_circuit()
+    }
+
+    pub fn estimate_cost(&self, workflow: &Workflow) -> CostEstimate {
+        self.inner.estimate_cost(workflow)
+    }
+
+    pub fn shutdown(&self, timeout: Duration) -> bool {
+        self.runtime.block_on(self.inner.shutdown(timeout))
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.inner.is_shut_down()
+    }
+
+    pub fn in_flight_requests(&self) -> usize {
+        self.inner.in_flight_requests()
+    }
+
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        self.inner.rate_limiter()
+    }
+
+    pub fn cache(&self) -> &Cache {
+        self.inner.cache()
+    }
+
+    pub fn execute(&self, request: &Request) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.execute(request))
+    }
+
+    /// Cancel from another thread to abandon the request
+    pub fn execute_cancellable(&self, request: &Request, cancel: &CancellationToken) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.execute_cancellable(request, cancel))
+    }
+
+    /// Poll until `cancel` is triggered from another thread; see [`crate::YahooFantasyClient::watch`]
+    pub fn watch<T, F, Fut>(&self, watcher: &PollWatcher, cancel: &CancellationToken, poll: F, on_result: impl FnMut(Result<T, Box<dyn std::error::Error + Send + Sync>>))
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        self.runtime.block_on(self.inner.watch(watcher, cancel, poll, on_result))
+    }
+
+    /// Response whose body is read chunk by chunk; see [`crate::YahooFantasyClient::execute_streaming`]
+    pub fn execute_streaming(&self, request: &Request) -> Result<StreamingResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let inner = self.runtime.block_on(self.inner.execute_streaming(request))?;
+        Ok(StreamingResponse { inner, runtime: self.runtime })
+    }
+
+    pub fn download(&self, request: &Request, writer: &mut impl std::io::Write) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.download(request, writer))
+    }
+
+    pub fn get_games(&self) -> Result<Vec<Game>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_games())
+    }
+
+    /// Fetch `keys` in batches; see [`crate::YahooFantasyClient::fetch_many`]
+    pub fn fetch_many<T, F, Fut>(&self, keys: &[String], fetch: F) -> BatchResult<T>
+    where
+        T: Keyed,
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        self.runtime.block_on(self.inner.fetch_many(keys, fetch))
+    }
+
+    /// Cancel from another thread to stop sending chunks
+    pub fn fetch_many_cancellable<T, F, Fut>(&self, keys: &[String], cancel: &CancellationToken, fetch: F) -> BatchResult<T>
+    where
+        T: Keyed,
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: std::future::Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        self.runtime.block_on(self.inner.fetch_many_cancellable(keys, cancel, fetch))
+    }
+
+    pub fn get_games_by_keys(&self, game_keys: &[String]) -> BatchResult<Game> {
+        self.runtime.block_on(self.inner.get_games_by_keys(game_keys))
+    }
+
+    pub fn get_leagues(&self, game_key: &str) -> Result<Vec<League>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_leagues(game_key))
+    }
+
+    pub fn get_game_weeks(&self, game_key: &str) -> Result<Vec<WeekRange>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_game_weeks(game_key))
+    }
+
+    pub fn get_league_status(&self, league_key: &str) -> Result<LeagueStatus, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_league_status(league_key))
+    }
+
+    pub fn search_public_leagues(&self, game_code: &str, filters: &LeagueSearchFilters) -> Result<Vec<PublicLeagueSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.search_public_leagues(game_code, filters))
+    }
+
+    pub fn get_scoreboard(&self, league_key: &str, week: i32) -> Result<Scoreboard, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_scoreboard(league_key, week))
+    }
+
+    pub fn get_standings(&self, league_key: &str) -> Result<Standings, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_standings(league_key))
+    }
+
+    pub fn get_league_settings(&self, league_key: &str) -> Result<LeagueSettings, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_league_settings(league_key))
+    }
+
+    pub fn get_transactions(&self, league_key: &str) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_transactions(league_key))
+    }
+
+    pub fn get_acquisition_usage(&self, team_key: &str, week: &WeekRange) -> Result<AcquisitionUsage, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_acquisition_usage(team_key, week))
+    }
+
+    pub fn get_cap_usage(&self, team_key: &str, week: u32) -> Result<Vec<CapUsage>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_cap_usage(team_key, week))
+    }
+
+    pub fn get_roster(&self, team_key: &str, coverage: &RosterCoverage) -> Result<Roster, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_roster(team_key, coverage))
+    }
+
+    pub fn copy_lineup(&self, team_key: &str, from: RosterCoverage, to: RosterCoverage) -> Result<LineupCopy, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.copy_lineup(team_key, from, to))
+    }
+
+    pub fn get_player_game_log(&self, player_key: &str, start: NaiveDate, end: NaiveDate) -> Result<PlayerGameLog, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_player_game_log(player_key, start, end))
+    }
+
+    pub fn get_player_stats(&self, player_key: &str, coverage: &StatCoverage) -> Result<PlayerStats, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_player_stats(player_key, coverage))
+    }
+
+    pub fn get_players_by_keys(&self, player_keys: &[String], coverage: &StatCoverage) -> BatchResult<PlayerStats> {
+        self.runtime.block_on(self.inner.get_players_by_keys(player_keys, coverage))
+    }
+
+    pub fn get_player_collection(&self, path: &str) -> Result<PlayerCollection, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_player_collection(path))
+    }
+
+    pub fn search_free_agents(&self, league_key: &str, search: &FreeAgentSearch) -> Result<Vec<FreeAgent>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.search_free_agents(league_key, search))
+    }
+
+    pub fn get_player_stats_for_date(&self, player_key: &str, date: NaiveDate) -> Result<DailyStatLine, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_player_stats_for_date(player_key, date))
+    }
+
+    pub fn roster_timeline(&self, team_key: &str) -> Result<RosterTimeline, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.roster_timeline(team_key))
+    }
+
+    pub fn get_roster_points(&self, team_key: &str, week: u32) -> Result<Vec<PlayerPoints>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_roster_points(team_key, week))
+    }
+
+    pub fn optimal_lineup_retro(&self, team_key: &str, week: u32) -> Result<LineupRetro, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.optimal_lineup_retro(team_key, week))
+    }
+
+    pub fn manager_efficiency(&self, league_key: &str, through_week: u32) -> Result<Vec<ManagerEfficiency>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.manager_efficiency(league_key, through_week))
+    }
+
+    pub fn manager_efficiency_with_progress(
+        &self,
+        league_key: &str,
+        through_week: u32,
+        on_progress: impl FnMut(&Progress),
+    ) -> Result<Vec<ManagerEfficiency>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime
+            .block_on(self.inner.manager_efficiency_with_progress(league_key, through_week, on_progress))
+    }
+
+    pub fn head_to_head_matrix(&self, league_key: &str) -> Result<HeadToHeadMatrix, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.head_to_head_matrix(league_key))
+    }
+
+    pub fn verify_standings(&self, league_key: &str) -> Result<StandingsVerification, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.verify_standings(league_key))
+    }
+
+    pub fn get_draft_results(&self, league_key: &str) -> Result<Vec<DraftPick>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.get_draft_results(league_key))
+    }
+
+    pub fn league_archive(&self, league: &League) -> Result<LeagueArchive, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.league_archive(league))
+    }
+
+    pub fn league_archive_with_progress(
+        &self,
+        league: &League,
+        on_progress: impl FnMut(&Progress),
+    ) -> Result<LeagueArchive, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.league_archive_with_progress(league, on_progress))
+    }
+}
+
+/// Blocking counterpart of [`crate::StreamingResponse`]
+#[derive(Debug)]
+pub struct StreamingResponse {
+    inner: crate::StreamingResponse,
+    runtime: &'static Runtime,
+}
+
+impl StreamingResponse {
+    pub fn status(&self) -> u16 {
+        self.inner.status
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.inner.headers
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.inner.is_success()
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.inner.header(name)
+    }
+
+    /// Next chunk of the body, or `None` once it has all been read
+    pub fn chunk(&mut self) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.chunk())
+    }
+
+    /// Copy the rest of the body to `writer`, returning the bytes written
+    pub fn write_to(&mut self, writer: &mut impl std::io::Write) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.write_to(writer))
+    }
+
+    /// Read the rest of the body into a string
+    pub fn text(self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.runtime.block_on(self.inner.text())
+    }
+}
@@ -0,0 +1,62 @@
+//! Offline auth bootstrap
+//! Starts long-running servers from a token obtained on another machine and keeps it alive by refreshing it
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{AccessToken, StoredToken, YahooFantasyClient};
+
+/// Called with every token the client refreshes to, e.g. to write a rotated
+/// session handle to a secrets manager. See `ClientBuilder::on_token_refresh`.
+#[derive(Clone)]
+pub struct TokenHook(Arc<HookFn>);
+
+type HookFn = dyn Fn(&StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync;
+
+impl TokenHook {
+    pub fn new(hook: impl Fn(&StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, token: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        (self.0)(token)
+    }
+}
+
+impl fmt::Debug for TokenHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenHook")
+    }
+}
+
+impl YahooFantasyClient {
+    /// Sign a server in with a token obtained elsewhere, e.g. by logging in
+    /// with the CLI on a workstation. The token is refreshed straight away,
+    /// which checks its session handle is still good and learns when the new
+    /// token expires, and then used for one cheap call to check the API
+    /// accepts it. From then on the client refreshes it as it expires, saving
+    /// every refresh to its token store and `on_token_refresh` hook.
+    ///
+    /// Fails with `TokenRevoked` if the user's authorization was revoked. If
+    /// the refresh fails the client keeps the token it had before; once it
+    /// succeeds the refreshed token is kept even if the check call fails,
+    /// since Yahoo may have rotated the session handle and the old token can't
+    /// be refreshed again.
+    pub async fn bootstrap(&self, token: AccessToken) -> Result<StoredToken, Box<dyn std::error::Error + Send + Sync>> {
+        if token.session_handle.is_none() {
+            return Err("the token has no session handle, so it can't be kept alive; export it with its session handle".into());
+        }
+        let current = StoredToken { token, expires_at: None };
+        let previous = self.token.lock().unwrap().replace(current.clone());
+        if let Err(e) = self.refresh_stored_token(&current).await {
+            // A refresh whose token store or hook failed has still replaced the token
+            let mut token = self.token.lock().unwrap();
+            if token.as_ref().is_some_and(|t| t.token == current.token) {
+                *token = previous;
+            }
+            return Err(e);
+        }
+        self.execute(&self.json_request("users;use_login=1")).await?;
+        Ok(self.stored_token().ok_or("the client has no access token")?)
+    }
+}
@@ -0,0 +1,115 @@
+//! One-call interactive login for CLI tools
+//! Captures Yahoo's OAuth redirect on a temporary localhost listener instead of asking the user to paste a code
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use percent_encoding::percent_decode_str;
+
+use crate::{AccessToken, TimeoutError, YahooFantasyClient};
+
+/// Path Yahoo redirects back to on the local listener
+const CALLBACK_PATH: &str = "/callback";
+
+/// How often the listener checks for Yahoo's redirect
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Time allowed for the browser to send its request once connected
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+const DONE_PAGE: &str = "<html><body><p>Signed in. You can close this window.</p></body></html>";
+
+impl YahooFantasyClient {
+    /// Log in interactively: listen on a random localhost port, open Yahoo's
+    /// approval page in the browser, wait up to `timeout` for Yahoo to redirect
+    /// back, and exchange the verifier for an access token. `show_url` also gets
+    /// the approval page, to print in case no browser opens. The app's redirect
+    /// settings must allow `http://127.0.0.1` callbacks.
+    pub async fn login_with_browser(&self, timeout: Duration, show_url: impl FnOnce(&str)) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let callback = format!("http://127.0.0.1:{}{}", listener.local_addr()?.port(), CALLBACK_PATH);
+
+        let request_token = self.get_request_token(&callback).await?;
+        show_url(&request_token.authorize_url);
+        // The URL was just shown, so a missing browser isn't fatal
+        let _ = open_in_browser(&request_token.authorize_url);
+
+        let deadline = Instant::now() + timeout;
+        let verifier = loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    if let Some(verifier) = answer_redirect(stream, &request_token.token)? {
+                        break verifier;
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Err(TimeoutError::Deadline(timeout).into());
+                    }
+                    tokio::time::sleep(ACCEPT_POLL_INTERVAL).await;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        self.get_access_token(&request_token, &verifier).await
+    }
+}
+
+/// Open `url` in the user's default browser
+pub fn open_in_browser(url: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(url).spawn().map(drop)
+}
+
+/// Read one request from the browser and reply to it. Returns the verifier if
+/// it was Yahoo's redirect for `request_token`, and `None` for anything else,
+/// like a favicon request.
+fn answer_redirect(mut stream: TcpStream, request_token: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != CALLBACK_PATH {
+        stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")?;
+        return Ok(None);
+    }
+
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| percent_decode_str(&v.replace('+', " ")).decode_utf8_lossy().into_owned())
+    };
+    let result = match (param("oauth_token"), param("oauth_verifier")) {
+        (Some(token), Some(verifier)) if token == request_token => Ok(verifier),
+        (Some(_), Some(_)) => Err("the redirect was for a different login"),
+        _ => Err("the login was not approved"),
+    };
+
+    let (status, body) = match &result {
+        Ok(_) => ("200 OK", DONE_PAGE.to_string()),
+        Err(reason) => ("400 Bad Request", format!("<html><body><p>Sign-in failed: {}.</p></body></html>", reason)),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(Some(result?))
+}
@@ -0,0 +1,250 @@
+//! Client builder
+//! Configuration for `YahooFantasyClient` beyond the consumer credentials
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::session::StoreBinding;
+use crate::{Cache, CancellationToken, CircuitBreaker, CircuitBreakerPolicy, DEFAULT_CACHE_SIZE, DEFAULT_CACHE_TTL, DEFAULT_MAX_TOKENS, DEFAULT_REFILL_RATE, EligibilityCache, HttpTransport, Middleware, PoolConfig, ProxyConfig, RateLimiter, ReqwestTransport, RetryPolicy, Scope, Secret, StoredToken, TokenHook, TokenStore, YahooFantasyClient};
+
+/// Default API endpoint
+pub const DEFAULT_BASE_URL: &str = "https://fantasysports.yahooapis.com/fantasy/v2";
+
+/// User-Agent sent when none is configured
+pub const DEFAULT_USER_AGENT: &str = concat!("yahoo-fantasy-sdk/", env!("CARGO_PKG_VERSION"));
+
+/// Time allowed to establish a connection
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Time allowed for a whole request, from sending to the last byte of the response
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Builder for `YahooFantasyClient`
+#[derive(Debug, Clone)]
+pub struct ClientBuilder {
+    consumer_key: String,
+    consumer_secret: Secret,
+    base_url: String,
+    user_agent: Option<String>,
+    application: Option<String>,
+    default_headers: Vec<(String, String)>,
+    transport: Option<Arc<dyn HttpTransport>>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    eligibility: Option<Arc<EligibilityCache>>,
+    proxy: Option<ProxyConfig>,
+    pool: PoolConfig,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+    retry: RetryPolicy,
+    scope: Option<Scope>,
+    circuit_breaker: CircuitBreakerPolicy,
+    weighted_costs: bool,
+    token_store: Option<StoreBinding>,
+    refresh_hook: Option<TokenHook>,
+    max_tokens: f64,
+    refill_rate: f64,
+    cache_size: usize,
+    cache_ttl: Duration,
+}
+
+impl ClientBuilder {
+    pub fn new(consumer_key: String, consumer_secret: String) -> Self {
+        Self {
+            consumer_key,
+            consumer_secret: consumer_secret.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: None,
+            application: None,
+            default_headers: Vec::new(),
+            transport: None,
+            middleware: Vec::new(),
+            eligibility: None,
+            proxy: None,
+            pool: PoolConfig::default(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            retry: RetryPolicy::default(),
+            scope: None,
+            circuit_breaker: CircuitBreakerPolicy::default(),
+            weighted_costs: false,
+            token_store: None,
+            refresh_hook: None,
+            max_tokens: DEFAULT_MAX_TOKENS,
+            refill_rate: DEFAULT_REFILL_RATE,
+            cache_size: DEFAULT_CACHE_SIZE,
+            cache_ttl: DEFAULT_CACHE_TTL,
+        }
+    }
+
+    /// Send requests to another API root, e.g. a mock server or recording proxy
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.to_string();
+        self
+    }
+
+    /// Replace the User-Agent entirely
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Identify your application; it is prepended to the SDK's User-Agent,
+    /// e.g. `my-app/1.2.0 yahoo-fantasy-sdk/0.1.0`
+    pub fn application(mut self, name: &str, version: &str) -> Self {
+        self.application = Some(format!("{}/{}", name, version));
+        self
+    }
+
+    /// Send `name: value` with every request, replacing any earlier default of the same name.
+    /// Use `user_agent` or `application` for the User-Agent.
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.default_headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Send requests through a custom transport instead of the default reqwest one
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Add middleware around the transport; the first added sees requests first and responses last
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Share an eligibility cache, e.g. one persisted with `EligibilityCache::open`
+    pub fn eligibility_cache(mut self, cache: Arc<EligibilityCache>) -> Self {
+        self.eligibility = Some(cache);
+        self
+    }
+
+    /// Send requests through an HTTP/HTTPS proxy. Ignored when a custom transport is set.
+    pub fn proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Tune connection reuse. Ignored when a custom transport is set.
+    pub fn pool(mut self, pool: PoolConfig) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Bound how long connecting may take. Ignored when a custom transport is set.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Bound how long a whole request may take; applies to every transport. Override it per request with `RequestOptions`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Retry network failures and 5xx responses; `RetryPolicy::none()` disables retries
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Ask for `scope` when logging users in; by default Yahoo grants what the app is registered for
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.scope = Some(scope);
+        self
+    }
+
+    /// Fail fast after repeated transient failures; `CircuitBreakerPolicy::disabled()` turns it off
+    pub fn circuit_breaker(mut self, policy: CircuitBreakerPolicy) -> Self {
+        self.circuit_breaker = policy;
+        self
+    }
+
+    /// Charge collection and `;out=` requests more than one rate limit token,
+    /// per `transport::weighted_cost`, so the budget tracks server load
+    pub fn weighted_costs(mut self, enabled: bool) -> Self {
+        self.weighted_costs = enabled;
+        self
+    }
+
+    /// Override Yahoo's default limits: bursts of up to `max_tokens` requests,
+    /// regaining `refill_rate` per second
+    pub fn rate_limits(mut self, max_tokens: f64, refill_rate: f64) -> Self {
+        self.max_tokens = max_tokens;
+        self.refill_rate = refill_rate;
+        self
+    }
+
+    /// Keep up to `max_size` responses in the response cache, each for `ttl`
+    pub fn cache(mut self, max_size: usize, ttl: Duration) -> Self {
+        self.cache_size = max_size;
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Start with `user`'s token from `store`, and save it back whenever the
+    /// client refreshes it. `build` fails if the store can't load the token.
+    pub fn token_store(mut self, store: Arc<dyn TokenStore>, user: &str) -> Self {
+        self.token_store = Some(StoreBinding {
+            store,
+            user: user.to_string(),
+        });
+        self
+    }
+
+    /// Call `hook` with every token the client refreshes to, before the old
+    /// one is dropped, e.g. to persist rotated session handles somewhere a
+    /// `TokenStore` doesn't cover. A failing hook fails the refresh's request,
+    /// but the new token is still used.
+    pub fn on_token_refresh(mut self, hook: impl Fn(&StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> + Send + Sync + 'static) -> Self {
+        self.refresh_hook = Some(TokenHook::new(hook));
+        self
+    }
+
+    /// Fails if the token store can't load the user's token or the default
+    /// HTTP transport can't be created
+    pub fn build(self) -> Result<YahooFantasyClient, Box<dyn std::error::Error + Send + Sync>> {
+        let user_agent = match (self.user_agent, self.application) {
+            (Some(user_agent), _) => user_agent,
+            (None, Some(application)) => format!("{} {}", application, DEFAULT_USER_AGENT),
+            (None, None) => DEFAULT_USER_AGENT.to_string(),
+        };
+        // A store that can't be read fails here rather than leaving the client signed out
+        let token = match &self.token_store {
+            Some(binding) => binding.store.load(&binding.user)?,
+            None => None,
+        };
+
+        let transport: Arc<dyn HttpTransport> = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(ReqwestTransport::configured(self.proxy.as_ref(), self.connect_timeout, &self.pool)?),
+        };
+
+        Ok(YahooFantasyClient {
+            consumer_key: self.consumer_key,
+            consumer_secret: self.consumer_secret,
+            token: Mutex::new(token),
+            refresh: Default::default(),
+            token_store: self.token_store,
+            refresh_hook: self.refresh_hook,
+            base_url: self.base_url,
+            user_agent,
+            default_headers: self.default_headers,
+            rate_limiter: Arc::new(RateLimiter::with_limits(self.max_tokens, self.refill_rate)),
+            cache: Arc::new(Cache::with_settings(self.cache_size, self.cache_ttl)),
+            eligibility: self.eligibility.unwrap_or_default(),
+            shutdown: CancellationToken::new(),
+            in_flight: Arc::default(),
+            transport,
+            middleware: self.middleware,
+            request_timeout: self.request_timeout,
+            retry: self.retry,
+            scope: self.scope,
+            circuit_breaker: Arc::new(CircuitBreaker::new(self.circuit_breaker)),
+            weighted_costs: self.weighted_costs,
+        })
+    }
+}
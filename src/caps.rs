@@ -0,0 +1,97 @@
+//! Weekly innings and games-played caps
+//! Reads a league's caps from its settings and tracks how much of them a team has used this week
+
+use crate::player_stats::game_key;
+use crate::{response, CapKind, CapUsage, FreeAgent, LeagueSettings, RosterPlayer, YahooFantasyClient};
+
+/// The weekly caps a league sets, with nothing used yet
+pub fn weekly_caps(settings: &LeagueSettings) -> Vec<CapUsage> {
+    let cap = |kind: CapKind, limit: Option<u32>| {
+        limit.map(|limit| CapUsage {
+            kind,
+            limit: limit as f64,
+            used: 0.0,
+        })
+    };
+    [
+        cap(CapKind::InningsPitched, settings.max_innings_pitched),
+        cap(CapKind::GamesPlayed, settings.max_games_played),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Innings as Yahoo writes them, where the digit after the point counts outs:
+/// "45.2" is 45⅔ innings
+pub fn parse_innings(value: &str) -> Result<f64, Box<dyn std::error::Error + Send + Sync>> {
+    let (whole, outs) = value.trim().split_once('.').unwrap_or((value.trim(), "0"));
+    let outs: u32 = outs.parse()?;
+    if outs > 2 {
+        return Err(format!("invalid innings value {}", value).into());
+    }
+    Ok(whole.parse::<u32>()? as f64 + outs as f64 / 3.0)
+}
+
+/// Whether `player` can start without breaking any of `caps`, counting the start if so
+pub(crate) fn try_start(caps: &mut [CapUsage], player: &RosterPlayer) -> Result<(), CapKind> {
+    if let Some(cap) = caps.iter().find(|cap| !cap.allows(&player.eligible_positions, player.has_game)) {
+        return Err(cap.kind);
+    }
+    for cap in caps.iter_mut() {
+        cap.record_start(&player.eligible_positions, player.has_game);
+    }
+    Ok(())
+}
+
+/// Free agents worth streaming given the team's remaining capacity: pitchers
+/// are dropped once the innings cap is reached, and everyone once games run out
+pub fn streamable<'a>(caps: &[CapUsage], free_agents: &'a [FreeAgent]) -> Vec<&'a FreeAgent> {
+    free_agents
+        .iter()
+        .filter(|p| caps.iter().all(|cap| cap.allows(&p.eligible_positions, true)))
+        .collect()
+}
+
+impl YahooFantasyClient {
+    /// A team's usage of its league's weekly caps in `week`, from the league
+    /// settings and the team's weekly stats; empty when the league sets none
+    pub async fn get_cap_usage(&self, team_key: &str, week: u32) -> Result<Vec<CapUsage>, Box<dyn std::error::Error + Send + Sync>> {
+        let league_key = team_key
+            .rsplit_once(".t.")
+            .map(|(league, _)| league)
+            .ok_or_else(|| format!("invalid team key {}", team_key))?;
+        let mut caps = weekly_caps(&self.get_league_settings(league_key).await?);
+        if caps.is_empty() {
+            return Ok(caps);
+        }
+
+        let names = self.stat_names(game_key(team_key)).await?;
+        let body = self.get_json(&format!("team/{}/stats;type=week;week={}", team_key, week)).await?;
+        let team = response::resource(&response::content(&body)?["team"]);
+        let stats = response::resource(response::member(&team, "team_stats"));
+        for cap in &mut caps {
+            let name = match cap.kind {
+                CapKind::InningsPitched => "IP",
+                CapKind::GamesPlayed => "GP",
+            };
+            let value = response::member(&stats, "stats")
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| entry.get("stat").map(response::resource))
+                .find(|stat| response::field::<u32>(stat, "stat_id").and_then(|id| names.get(&id)).is_some_and(|n| n == name))
+                .and_then(|stat| response::field::<String>(&stat, "value"));
+            // Yahoo sends "-" for a stat with nothing recorded yet
+            cap.used = match value.as_deref() {
+                None | Some("-") | Some("") => 0.0,
+                Some(value) => match cap.kind {
+                    CapKind::InningsPitched => parse_innings(value)?,
+                    CapKind::GamesPlayed => value.parse()?,
+                },
+            };
+        }
+
+        Ok(caps)
+    }
+}
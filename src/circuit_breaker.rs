@@ -0,0 +1,142 @@
+//! Circuit breaker
+//! Fails fast for a cooldown after repeated upstream failures instead of queuing requests against a dead endpoint
+
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When the breaker trips and for how long
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    /// Consecutive transient failures that open the circuit; 0 disables the breaker
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a single probe request is let through
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+impl CircuitBreakerPolicy {
+    /// Never trip
+    pub fn disabled() -> Self {
+        Self {
+            failure_threshold: 0,
+            ..Self::default()
+        }
+    }
+}
+
+/// Observable breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally
+    Closed,
+    /// Requests fail fast with `CircuitOpen` for `retry_in`
+    Open { retry_in: Duration },
+    /// The cooldown has passed; the next request is a probe that closes or reopens the circuit
+    HalfOpen,
+}
+
+/// A request refused because the circuit is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitOpen {
+    pub retry_in: Duration,
+}
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Yahoo is failing repeatedly; not sending requests for another {:?}", self.retry_in)
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// Consecutive-failure breaker shared by every request of a client
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// When the half-open probe was admitted; other requests fail fast until
+    /// it finishes, or for one more cooldown if it was abandoned
+    probe_started: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    pub fn policy(&self) -> CircuitBreakerPolicy {
+        self.policy
+    }
+
+    pub fn state(&self) -> CircuitState {
+        let state = self.state.lock().unwrap();
+        match state.open_until {
+            None => CircuitState::Closed,
+            Some(until) => match until.checked_duration_since(Instant::now()) {
+                Some(retry_in) if !retry_in.is_zero() => CircuitState::Open { retry_in },
+                _ => CircuitState::HalfOpen,
+            },
+        }
+    }
+
+    /// Admit a request, or refuse it while the circuit is open. After the
+    /// cooldown, one request is admitted as a probe.
+    pub fn check(&self) -> Result<(), CircuitOpen> {
+        let mut state = self.state.lock().unwrap();
+        let Some(until) = state.open_until else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        if now < until {
+            return Err(CircuitOpen { retry_in: until - now });
+        }
+        if let Some(started) = state.probe_started {
+            let probe_deadline = started + self.policy.cooldown;
+            if now < probe_deadline {
+                return Err(CircuitOpen { retry_in: probe_deadline - now });
+            }
+        }
+        state.probe_started = Some(now);
+        Ok(())
+    }
+
+    pub fn record_success(&self) {
+        *self.state.lock().unwrap() = BreakerState::default();
+    }
+
+    /// Count a transient failure, opening the circuit at the threshold or when a probe fails
+    pub fn record_failure(&self) {
+        if self.policy.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        if state.probe_started.is_some() || state.consecutive_failures >= self.policy.failure_threshold {
+            state.open_until = Some(Instant::now() + self.policy.cooldown);
+            state.probe_started = None;
+        }
+    }
+
+    /// Close the circuit and forget past failures
+    pub fn reset(&self) {
+        self.record_success();
+    }
+}
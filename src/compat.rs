@@ -0,0 +1,147 @@
+//! Season compatibility layer
+//! Normalizes historical payload differences into the current models
+
+use serde_json::{Map, Value};
+
+use crate::{response, Game, League};
+
+/// First season in which the legacy fractional points flag was dropped
+const FRACTIONAL_POINTS_REMOVED: i32 = 2013;
+
+/// A field that was renamed between seasons
+struct FieldAlias {
+    legacy: &'static str,
+    current: &'static str,
+    /// Last season that used the legacy name
+    until: i32,
+}
+
+const GAME_ALIASES: &[FieldAlias] = &[
+    FieldAlias { legacy: "game_code", current: "code", until: 2009 },
+    FieldAlias { legacy: "year", current: "season", until: 2009 },
+];
+
+const LEAGUE_ALIASES: &[FieldAlias] = &[
+    FieldAlias { legacy: "num_team", current: "num_teams", until: 2011 },
+    FieldAlias { legacy: "week", current: "current_week", until: 2011 },
+];
+
+const GAME_INT_FIELDS: &[&str] = &["season"];
+const GAME_BOOL_FIELDS: &[&str] = &["is_game_over"];
+const LEAGUE_INT_FIELDS: &[&str] = &["num_teams", "current_week", "end_week", "season"];
+const LEAGUE_BOOL_FIELDS: &[&str] = &["uses_fractional_points"];
+
+/// Normalizes raw Yahoo payloads from a given season into the current models
+#[derive(Debug, Clone, Copy)]
+pub struct SeasonCompat {
+    /// `None` when the payload doesn't say; no season-specific fixups apply then
+    season: Option<i32>,
+}
+
+impl SeasonCompat {
+    /// Create a compat layer for payloads from `season`
+    pub fn for_season(season: i32) -> Self {
+        Self { season: Some(season) }
+    }
+
+    /// Create a compat layer for payloads of unknown season. Legacy field
+    /// names are left alone, since guessing the season could rename a current field.
+    pub fn unknown() -> Self {
+        Self { season: None }
+    }
+
+    /// Compat layer for a resource's own season, see `response::season`
+    pub fn for_resource(fields: &Map<String, Value>) -> Self {
+        response::season(fields).map_or_else(Self::unknown, Self::for_season)
+    }
+
+    /// Season this layer normalizes for, if known
+    pub fn season(&self) -> Option<i32> {
+        self.season
+    }
+
+    /// Parse a game payload, applying any season-specific fixups
+    pub fn parse_game(&self, payload: Value) -> Result<Game, Box<dyn std::error::Error + Send + Sync>> {
+        let mut fields = into_object(payload)?;
+        self.apply_aliases(&mut fields, GAME_ALIASES);
+        coerce_ints(&mut fields, GAME_INT_FIELDS);
+        coerce_bools(&mut fields, GAME_BOOL_FIELDS);
+
+        if let (false, Some(season)) = (fields.contains_key("season"), self.season) {
+            fields.insert("season".to_string(), Value::from(season));
+        }
+
+        Ok(serde_json::from_value(Value::Object(fields))?)
+    }
+
+    /// Parse a league payload, applying any season-specific fixups
+    pub fn parse_league(&self, payload: Value) -> Result<League, Box<dyn std::error::Error + Send + Sync>> {
+        let mut fields = into_object(payload)?;
+        self.apply_aliases(&mut fields, LEAGUE_ALIASES);
+        coerce_ints(&mut fields, LEAGUE_INT_FIELDS);
+        coerce_bools(&mut fields, LEAGUE_BOOL_FIELDS);
+
+        // Archived leagues omit the current week; they are pinned to their last week
+        if !fields.contains_key("current_week") {
+            let end_week = fields.get("end_week").cloned().unwrap_or(Value::from(0));
+            fields.insert("current_week".to_string(), end_week);
+        }
+
+        if self.season.is_some_and(|season| season >= FRACTIONAL_POINTS_REMOVED) {
+            fields.remove("uses_fractional_points");
+        }
+
+        if let (false, Some(season)) = (fields.contains_key("season"), self.season) {
+            fields.insert("season".to_string(), Value::from(season));
+        }
+
+        Ok(serde_json::from_value(Value::Object(fields))?)
+    }
+
+    fn apply_aliases(&self, fields: &mut Map<String, Value>, aliases: &[FieldAlias]) {
+        for alias in aliases {
+            if self.season.is_none_or(|season| season > alias.until) || fields.contains_key(alias.current) {
+                continue;
+            }
+            if let Some(value) = fields.remove(alias.legacy) {
+                fields.insert(alias.current.to_string(), value);
+            }
+        }
+    }
+}
+
+fn into_object(payload: Value) -> Result<Map<String, Value>, Box<dyn std::error::Error + Send + Sync>> {
+    match payload {
+        Value::Object(fields) => Ok(fields),
+        other => Err(format!("expected an object payload, got {}", other).into()),
+    }
+}
+
+/// Yahoo encodes most numbers as strings
+fn coerce_ints(fields: &mut Map<String, Value>, names: &[&str]) {
+    for name in names {
+        if let Some(Value::String(raw)) = fields.get(*name) {
+            if let Ok(parsed) = raw.trim().parse::<i64>() {
+                fields.insert(name.to_string(), Value::from(parsed));
+            }
+        }
+    }
+}
+
+/// Yahoo encodes flags as "1"/"0" (and occasionally as numbers)
+fn coerce_bools(fields: &mut Map<String, Value>, names: &[&str]) {
+    for name in names {
+        let flag = match fields.get(*name) {
+            Some(Value::String(raw)) => match raw.trim() {
+                "1" | "true" => Some(true),
+                "0" | "false" | "" => Some(false),
+                _ => None,
+            },
+            Some(Value::Number(n)) => n.as_i64().map(|n| n != 0),
+            _ => None,
+        };
+        if let Some(flag) = flag {
+            fields.insert(name.to_string(), Value::Bool(flag));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! Config file
+//! Credentials, default leagues, and cache and rate limit overrides from a TOML file, for CLIs and desktop apps
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ClientBuilder, Secret, DEFAULT_CACHE_SIZE, DEFAULT_CACHE_TTL, DEFAULT_MAX_TOKENS, DEFAULT_REFILL_RATE};
+
+/// Location of the config file under the user's config directory
+pub const CONFIG_FILE: &str = "yahoo-fantasy/config.toml";
+
+/// Settings from a config file such as
+///
+/// ```toml
+/// [credentials]
+/// consumer_key = "dj0y..."
+/// consumer_secret = "8f3a..."
+///
+/// [leagues]
+/// nfl = "449.l.123456"
+///
+/// [cache]
+/// max_entries = 500
+/// ttl_secs = 600
+///
+/// [rate_limit]
+/// max_tokens = 50
+/// refill_per_sec = 0.5
+/// ```
+///
+/// Every section is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub credentials: CredentialsConfig,
+    /// Default league key by game code, e.g. `nfl`
+    pub leagues: BTreeMap<String, String>,
+    pub cache: CacheConfig,
+    pub rate_limit: RateLimitConfig,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct CredentialsConfig {
+    pub consumer_key: Option<String>,
+    pub consumer_secret: Option<Secret>,
+}
+
+/// Response cache overrides; unset values keep the defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    pub max_entries: Option<usize>,
+    pub ttl_secs: Option<u64>,
+}
+
+/// Rate limiter overrides; unset values keep Yahoo's defaults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub max_tokens: Option<f64>,
+    pub refill_per_sec: Option<f64>,
+}
+
+impl Config {
+    /// `~/.config/yahoo-fantasy/config.toml`, or under `$XDG_CONFIG_HOME` when
+    /// it is set; `None` when there's no home directory to look in
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?).join(".config"),
+        };
+        Some(config_dir.join(CONFIG_FILE))
+    }
+
+    /// Read and check the config file at `path`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        let config: Config = toml::from_str(&contents).map_err(|e| format!("invalid config file {}: {}", path.display(), e))?;
+        config.check().map_err(|e| format!("invalid config file {}: {}", path.display(), e))?;
+        Ok(config)
+    }
+
+    /// The config file at `default_path`; `None` if there isn't one
+    pub fn load_default() -> Result<Option<Self>, Box<dyn std::error::Error + Send + Sync>> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load(path).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Default league for a game code such as `nfl`
+    pub fn default_league(&self, game_code: &str) -> Option<&str> {
+        self.leagues.get(game_code).map(String::as_str)
+    }
+
+    /// Builder with the file's credentials and overrides
+    pub fn builder(&self) -> Result<ClientBuilder, Box<dyn std::error::Error + Send + Sync>> {
+        ClientBuilder::from_config(self)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if let Some(max_tokens) = self.rate_limit.max_tokens.filter(|t| !(t.is_finite() && *t >= 1.0)) {
+            return Err(format!("rate_limit.max_tokens must be at least 1, not {}", max_tokens));
+        }
+        if let Some(refill) = self.rate_limit.refill_per_sec.filter(|r| !(r.is_finite() && *r > 0.0)) {
+            return Err(format!("rate_limit.refill_per_sec must be positive, not {}", refill));
+        }
+        if self.cache.max_entries == Some(0) {
+            return Err("cache.max_entries must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl ClientBuilder {
+    /// Builder with `config`'s credentials, cache and rate limits; fails if
+    /// the credentials are missing or an override is out of range
+    pub fn from_config(config: &Config) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        config.check()?;
+        let credentials = &config.credentials;
+        let consumer_key = credentials.consumer_key.clone().filter(|key| !key.is_empty()).ok_or("config has no credentials.consumer_key")?;
+        let consumer_secret = credentials.consumer_secret.as_ref().filter(|secret| !secret.is_empty()).ok_or("config has no credentials.consumer_secret")?;
+        let cache = config.cache;
+        let rate_limit = config.rate_limit;
+        Ok(ClientBuilder::new(consumer_key, consumer_secret.expose().to_string())
+            .cache(
+                cache.max_entries.unwrap_or(DEFAULT_CACHE_SIZE),
+                cache.ttl_secs.map_or(DEFAULT_CACHE_TTL, Duration::from_secs),
+            )
+            .rate_limits(rate_limit.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS), rate_limit.refill_per_sec.unwrap_or(DEFAULT_REFILL_RATE)))
+    }
+}
@@ -0,0 +1,166 @@
+//! Field-level diffs
+//! Compares two instances of any serializable model and lists what changed, by field path
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Fields that identify an element of a list, tried in order. Lists whose
+/// elements all carry one are matched by it instead of by position, so a
+/// reordered roster or standings table isn't reported as every row changing.
+const IDENTITY_KEYS: &[&str] = &[
+    "team_key",
+    "player_key",
+    "league_key",
+    "game_key",
+    "transaction_key",
+    "stat_id",
+    "position",
+];
+
+/// One changed field. Paths look like `teams[nfl.l.1.t.2].rank` for lists
+/// matched by identity and `matchups[0].teams` for lists matched by position.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FieldChange {
+    Added { path: String, value: Value },
+    Removed { path: String, value: Value },
+    Changed { path: String, before: Value, after: Value },
+}
+
+impl FieldChange {
+    pub fn path(&self) -> &str {
+        match self {
+            FieldChange::Added { path, .. } | FieldChange::Removed { path, .. } | FieldChange::Changed { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldChange::Added { path, value } => write!(f, "+ {}: {}", path, value),
+            FieldChange::Removed { path, value } => write!(f, "- {}: {}", path, value),
+            FieldChange::Changed { path, before, after } => write!(f, "~ {}: {} -> {}", path, before, after),
+        }
+    }
+}
+
+/// Changes from `before` to `after`
+pub fn diff<T: Serialize + ?Sized>(before: &T, after: &T) -> Result<Vec<FieldChange>, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(diff_values(&serde_json::to_value(before)?, &serde_json::to_value(after)?))
+}
+
+/// Changes between two JSON values
+pub fn diff_values(before: &Value, after: &Value) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    walk("", before, after, &mut changes);
+    changes
+}
+
+fn walk(path: &str, before: &Value, after: &Value, changes: &mut Vec<FieldChange>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => walk_object(path, old, new, changes),
+        (Value::Array(old), Value::Array(new)) => match identity_key(old, new) {
+            Some(key) => walk_keyed(path, key, old, new, changes),
+            None => walk_indexed(path, old, new, changes),
+        },
+        _ if before != after => changes.push(FieldChange::Changed {
+            path: path.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn walk_object(path: &str, old: &Map<String, Value>, new: &Map<String, Value>, changes: &mut Vec<FieldChange>) {
+    for (field, before) in old {
+        let child = field_path(path, field);
+        match new.get(field) {
+            Some(after) => walk(&child, before, after, changes),
+            None => changes.push(FieldChange::Removed {
+                path: child,
+                value: before.clone(),
+            }),
+        }
+    }
+    for (field, after) in new.iter().filter(|(field, _)| !old.contains_key(*field)) {
+        changes.push(FieldChange::Added {
+            path: field_path(path, field),
+            value: after.clone(),
+        });
+    }
+}
+
+fn walk_indexed(path: &str, old: &[Value], new: &[Value], changes: &mut Vec<FieldChange>) {
+    for (i, before) in old.iter().enumerate() {
+        let child = format!("{}[{}]", path, i);
+        match new.get(i) {
+            Some(after) => walk(&child, before, after, changes),
+            None => changes.push(FieldChange::Removed {
+                path: child,
+                value: before.clone(),
+            }),
+        }
+    }
+    for (i, after) in new.iter().enumerate().skip(old.len()) {
+        changes.push(FieldChange::Added {
+            path: format!("{}[{}]", path, i),
+            value: after.clone(),
+        });
+    }
+}
+
+fn walk_keyed(path: &str, key: &str, old: &[Value], new: &[Value], changes: &mut Vec<FieldChange>) {
+    let find = |items: &[Value], id: &str| items.iter().find(|v| identity(v, key).as_deref() == Some(id)).cloned();
+
+    for before in old {
+        let id = identity(before, key).unwrap_or_default();
+        let child = format!("{}[{}]", path, id);
+        match find(new, &id) {
+            Some(after) => walk(&child, before, &after, changes),
+            None => changes.push(FieldChange::Removed {
+                path: child,
+                value: before.clone(),
+            }),
+        }
+    }
+    for after in new {
+        let id = identity(after, key).unwrap_or_default();
+        if find(old, &id).is_none() {
+            changes.push(FieldChange::Added {
+                path: format!("{}[{}]", path, id),
+                value: after.clone(),
+            });
+        }
+    }
+}
+
+/// First identity key that every element of both lists has, with no duplicates
+fn identity_key(old: &[Value], new: &[Value]) -> Option<&'static str> {
+    IDENTITY_KEYS.iter().copied().find(|key| {
+        [old, new].iter().all(|items| {
+            let ids: Vec<String> = items.iter().filter_map(|v| identity(v, key)).collect();
+            ids.len() == items.len() && ids.iter().enumerate().all(|(i, id)| !ids[..i].contains(id))
+        })
+    })
+}
+
+fn identity(value: &Value, key: &str) -> Option<String> {
+    match value.get(key)? {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn field_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
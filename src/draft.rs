@@ -0,0 +1,282 @@
+//! Draft board and pick recommendations
+//! Tracks remaining players, roster needs, and positional runs as picks come in
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{response, DraftPick, DraftPlayer, Points, RosterSlot, YahooFantasyClient};
+
+/// Recent picks considered when looking for positional runs
+const RUN_WINDOW: usize = 8;
+/// Picks at one position within the window that make a run
+const RUN_THRESHOLD: usize = 3;
+/// Score multiplier for positions in a run (scarcity is accelerating)
+const RUN_BONUS: f64 = 1.1;
+/// Score multiplier for players that would only fill the bench
+const BENCH_FACTOR: f64 = 0.4;
+
+/// Open starting slots for my team
+#[derive(Debug, Clone, PartialEq)]
+pub struct RosterNeed {
+    pub slot: String,
+    pub open: u32,
+}
+
+/// A suggested pick and why
+#[derive(Debug, Clone)]
+pub struct PickRecommendation {
+    pub player: DraftPlayer,
+    pub score: f64,
+    /// Value above the best player likely available next round at the position
+    pub value_over_replacement: Points,
+    pub fills_slot: Option<String>,
+    pub in_run: bool,
+}
+
+/// Live draft board for one team
+#[derive(Debug, Clone)]
+pub struct DraftBoard {
+    my_team_key: String,
+    num_teams: usize,
+    slots: Vec<RosterSlot>,
+    available: Vec<DraftPlayer>,
+    drafted: HashMap<String, DraftPlayer>,
+    picks: Vec<DraftPick>,
+}
+
+impl DraftBoard {
+    pub fn new(my_team_key: &str, num_teams: usize, slots: Vec<RosterSlot>, mut players: Vec<DraftPlayer>) -> Self {
+        players.sort_by_key(|p| std::cmp::Reverse(p.value));
+        Self {
+            my_team_key: my_team_key.to_string(),
+            num_teams: num_teams.max(1),
+            slots,
+            available: players,
+            drafted: HashMap::new(),
+            picks: Vec::new(),
+        }
+    }
+
+    /// Apply a pick from the draft stream; unknown or repeated players are ignored
+    pub fn record_pick(&mut self, pick: DraftPick) {
+        if self.picks.iter().any(|p| p.player_key == pick.player_key) {
+            return;
+        }
+        if let Some(index) = self.available.iter().position(|p| p.player_key == pick.player_key) {
+            let player = self.available.remove(index);
+            self.drafted.insert(player.player_key.clone(), player);
+        }
+        self.picks.push(pick);
+    }
+
+    pub fn picks(&self) -> &[DraftPick] {
+        &self.picks
+    }
+
+    /// Remaining players, best first
+    pub fn available(&self) -> &[DraftPlayer] {
+        &self.available
+    }
+
+    /// Remaining players eligible at a position, best first
+    pub fn available_at(&self, position: &str) -> Vec<&DraftPlayer> {
+        self.available
+            .iter()
+            .filter(|p| p.positions.iter().any(|pos| pos == position))
+            .collect()
+    }
+
+    /// Players I've drafted
+    pub fn my_players(&self) -> Vec<&DraftPlayer> {
+        self.picks
+            .iter()
+            .filter(|p| p.team_key == self.my_team_key)
+            .filter_map(|p| self.drafted.get(&p.player_key))
+            .collect()
+    }
+
+    /// Starting slots I still need to fill
+    pub fn needs(&self) -> Vec<RosterNeed> {
+        let open = self.open_slots();
+        self.slots
+            .iter()
+            .filter(|s| s.is_starting)
+            .filter_map(|s| {
+                let remaining = open.get(s.position.as_str()).copied().unwrap_or(0);
+                (remaining > 0).then(|| RosterNeed {
+                    slot: s.position.clone(),
+                    open: remaining,
+                })
+            })
+            .collect()
+    }
+
+    /// Positions taken at least `RUN_THRESHOLD` times in the last `RUN_WINDOW` picks
+    pub fn positional_runs(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for pick in self.picks.iter().rev().take(RUN_WINDOW) {
+            if let Some(position) = self.drafted.get(&pick.player_key).and_then(|p| p.positions.first()) {
+                *counts.entry(position.as_str()).or_default() += 1;
+            }
+        }
+        let mut runs: Vec<(String, usize)> = counts
+            .into_iter()
+            .filter(|(_, n)| *n >= RUN_THRESHOLD)
+            .map(|(pos, n)| (pos.to_string(), n))
+            .collect();
+        runs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        runs
+    }
+
+    /// Best pick for my team right now
+    pub fn recommend_pick(&self) -> Option<PickRecommendation> {
+        self.recommendations(1).into_iter().next()
+    }
+
+    /// Top `limit` picks for my team, best first
+    pub fn recommendations(&self, limit: usize) -> Vec<PickRecommendation> {
+        let open = self.open_slots();
+        let runs: HashSet<String> = self.positional_runs().into_iter().map(|(pos, _)| pos).collect();
+        let starters = self.starters_by_position();
+
+        let mut scored: Vec<PickRecommendation> = self
+            .available
+            .iter()
+            .map(|player| {
+                let position = player.positions.first().map(String::as_str).unwrap_or("");
+                let value_over_replacement = player.value - self.replacement_value(position, &starters);
+                let fills_slot = self
+                    .slots
+                    .iter()
+                    .filter(|s| s.is_starting && open.get(s.position.as_str()).copied().unwrap_or(0) > 0)
+                    .find(|s| player.positions.iter().any(|p| s.accepts(p)))
+                    .map(|s| s.position.clone());
+                let in_run = runs.contains(position);
+
+                let mut score = value_over_replacement.to_f64();
+                if fills_slot.is_none() {
+                    score *= BENCH_FACTOR;
+                }
+                if in_run {
+                    score *= RUN_BONUS;
+                }
+
+                PickRecommendation {
+                    player: player.clone(),
+                    score,
+                    value_over_replacement,
+                    fills_slot,
+                    in_run,
+                }
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored.truncate(limit);
+        scored
+    }
+
+    /// Value of the player at a position expected to survive until my next
+    /// pick, or deeper when the league still has more starters there to fill
+    /// than teams, as with three-linebacker IDP lineups or superflex QBs
+    fn replacement_value(&self, position: &str, starters: &HashMap<String, usize>) -> Points {
+        let pool = self.available_at(position);
+        let drafted = self.drafted.values().filter(|p| p.positions.first().is_some_and(|p| p == position)).count();
+        let open_starters = starters.get(position).copied().unwrap_or(0).saturating_sub(drafted);
+        pool.get(open_starters.max(self.num_teams))
+            .or(pool.last())
+            .map_or(Points::ZERO, |p| p.value)
+    }
+
+    /// League-wide starters per primary position. Each starting slot, most
+    /// specific first, goes to whichever position it accepts has the best
+    /// player left, so a superflex slot counts as a second QB when QBs outscore
+    /// the other positions, and a W/R/T slot as whichever of WR, RB, or TE runs deepest.
+    fn starters_by_position(&self) -> HashMap<String, usize> {
+        let mut pools: HashMap<&str, Vec<Points>> = HashMap::new();
+        for player in self.available.iter().chain(self.drafted.values()) {
+            if let Some(position) = player.positions.first() {
+                pools.entry(position).or_default().push(player.value);
+            }
+        }
+        for pool in pools.values_mut() {
+            pool.sort_by_key(|value| std::cmp::Reverse(*value));
+        }
+
+        let mut slots: Vec<(&RosterSlot, Vec<&str>)> = self
+            .slots
+            .iter()
+            .filter(|s| s.is_starting)
+            .map(|s| {
+                let mut positions: Vec<&str> = pools.keys().copied().filter(|p| s.accepts(p)).collect();
+                positions.sort();
+                (s, positions)
+            })
+            .collect();
+        slots.sort_by_key(|(_, positions)| positions.len());
+
+        let mut starters: HashMap<String, usize> = HashMap::new();
+        for (slot, positions) in slots {
+            for _ in 0..slot.count as usize * self.num_teams {
+                let best = positions
+                    .iter()
+                    .max_by_key(|p| pools[**p].get(starters.get(**p).copied().unwrap_or(0)).copied());
+                if let Some(best) = best {
+                    *starters.entry(best.to_string()).or_default() += 1;
+                }
+            }
+        }
+        starters
+    }
+
+    /// Open count per starting slot, filling the most specific slots first
+    fn open_slots(&self) -> HashMap<&str, u32> {
+        let mut open: HashMap<&str, u32> = self
+            .slots
+            .iter()
+            .filter(|s| s.is_starting)
+            .map(|s| (s.position.as_str(), s.count))
+            .collect();
+
+        let mut by_specificity: Vec<&RosterSlot> = self.slots.iter().filter(|s| s.is_starting).collect();
+        by_specificity.sort_by_key(|s| s.accepts.len());
+
+        for player in self.my_players() {
+            if let Some(slot) = by_specificity.iter().find(|s| {
+                open.get(s.position.as_str()).copied().unwrap_or(0) > 0
+                    && player.positions.iter().any(|p| s.accepts(p))
+            }) {
+                *open.get_mut(slot.position.as_str()).unwrap() -= 1;
+            }
+        }
+        open
+    }
+}
+
+impl YahooFantasyClient {
+    /// Get a league's completed picks in pick order
+    pub async fn get_draft_results(&self, league_key: &str) -> Result<Vec<DraftPick>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("league/{}/draftresults", league_key)).await?;
+        parse_draft_results(&body)
+    }
+}
+
+/// Picks from a `league/{key}/draftresults` response. Before and during the
+/// draft Yahoo lists every slot, with no player on the ones still to be made.
+fn parse_draft_results(body: &serde_json::Value) -> Result<Vec<DraftPick>, Box<dyn std::error::Error + Send + Sync>> {
+    let league = response::resource(&response::content(body)?["league"]);
+    let mut picks = Vec::new();
+    for result in response::collection(response::member(&league, "draft_results"), "draft_result") {
+        let Some(player_key) = response::field(&result, "player_key").filter(|key: &String| !key.is_empty()) else {
+            continue;
+        };
+        picks.push(DraftPick {
+            pick: response::require(&result, "pick", "draft result")?,
+            round: response::require(&result, "round", "draft result")?,
+            team_key: response::require(&result, "team_key", "draft result")?,
+            player_key,
+            cost: response::field(&result, "cost"),
+        });
+    }
+    picks.sort_by_key(|pick| pick.pick);
+    Ok(picks)
+}
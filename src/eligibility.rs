@@ -0,0 +1,153 @@
+//! Position eligibility cache
+//! Long-lived player eligibility shared by the lineup validator and optimizer, invalidated by news and roster changes
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::Roster;
+
+/// How long eligibility is trusted without being seen again
+pub const DEFAULT_ELIGIBILITY_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Words in a headline suggesting a player's positions changed
+const POSITION_NEWS: &[&str] = &["eligib", "position", "moved to", "moving to", "switch to", "converted to", "playing at"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    positions: Vec<String>,
+    seen: DateTime<Utc>,
+}
+
+/// Eligible positions by player key, optionally persisted to a JSON file.
+/// Every roster the client fetches refreshes it.
+#[derive(Debug)]
+pub struct EligibilityCache {
+    path: Option<PathBuf>,
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl Default for EligibilityCache {
+    fn default() -> Self {
+        Self {
+            path: None,
+            ttl: DEFAULT_ELIGIBILITY_TTL,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl EligibilityCache {
+    /// Cache that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the cache from `path` if it exists; `flush` writes it back
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+            ..Self::default()
+        })
+    }
+
+    /// Trust entries for `ttl` after they were last seen
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// A player's eligible positions, unless unknown or older than the TTL
+    pub fn get(&self, player_key: &str) -> Option<Vec<String>> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(player_key)?;
+        let age = (Utc::now() - entry.seen).to_std().unwrap_or_default();
+        (age <= self.ttl).then(|| entry.positions.clone())
+    }
+
+    pub fn put(&self, player_key: &str, positions: Vec<String>) {
+        self.entries.lock().unwrap().insert(player_key.to_string(), Entry { positions, seen: Utc::now() });
+    }
+
+    /// Record the eligibility in a freshly fetched roster. Returns the players
+    /// whose positions differ from what was cached.
+    pub fn observe(&self, roster: &Roster) -> Vec<String> {
+        let now = Utc::now();
+        let mut entries = self.entries.lock().unwrap();
+        let mut changed = Vec::new();
+        for player in roster.players.iter().filter(|p| !p.eligible_positions.is_empty()) {
+            let previous = entries.insert(
+                player.player_key.clone(),
+                Entry {
+                    positions: player.eligible_positions.clone(),
+                    seen: now,
+                },
+            );
+            if previous.is_some_and(|e| e.positions != player.eligible_positions) {
+                changed.push(player.player_key.clone());
+            }
+        }
+        changed
+    }
+
+    /// Fill in players without eligibility, e.g. in a roster built from a
+    /// transaction or draft, from the cache. Returns the players still unknown.
+    pub fn fill(&self, roster: &mut Roster) -> Vec<String> {
+        let mut unknown = Vec::new();
+        for player in roster.players.iter_mut().filter(|p| p.eligible_positions.is_empty()) {
+            match self.get(&player.player_key) {
+                Some(positions) => player.eligible_positions = positions,
+                None => unknown.push(player.player_key.clone()),
+            }
+        }
+        unknown
+    }
+
+    /// Drop a player's eligibility so the next roster fetch decides it
+    pub fn invalidate_player(&self, player_key: &str) {
+        self.entries.lock().unwrap().remove(player_key);
+    }
+
+    /// Invalidate a player if `headline` suggests their positions changed.
+    /// Returns whether it did.
+    pub fn on_news(&self, player_key: &str, headline: &str) -> bool {
+        let headline = headline.to_lowercase();
+        let relevant = POSITION_NEWS.iter().any(|word| headline.contains(word));
+        if relevant {
+            self.invalidate_player(player_key);
+        }
+        relevant
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write the cache to its file, atomically replacing the previous contents
+    pub fn flush(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec(&*self.entries.lock().unwrap())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Encrypted token storage
+//! A file token store sealed under a passphrase or key file, so saved refresh tokens aren't readable on shared machines
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+use crate::session::write_atomically;
+use crate::{Secret, StoredToken, TokenStore};
+
+/// Marks a file as an encrypted token store
+const FORMAT: &str = "yahoo-fantasy-sdk/encrypted-tokens";
+
+const VERSION: u32 = 1;
+
+const KEY_LEN: usize = 32;
+
+const SALT_LEN: usize = 16;
+
+/// XChaCha20's nonces are long enough to pick at random for every write
+const NONCE_LEN: usize = 24;
+
+/// Secret an `EncryptedFileTokenStore` is sealed with
+#[derive(Clone)]
+pub enum TokenKey {
+    /// Stretched into a key with Argon2id and a random salt kept in the file
+    Passphrase(Secret),
+    /// A random key, e.g. from `TokenKey::generate_key_file`
+    Key([u8; KEY_LEN]),
+}
+
+impl fmt::Debug for TokenKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TokenKey::Passphrase(_) => "TokenKey::Passphrase(..)",
+            TokenKey::Key(_) => "TokenKey::Key(..)",
+        })
+    }
+}
+
+impl TokenKey {
+    pub fn passphrase(passphrase: &str) -> Self {
+        TokenKey::Passphrase(Secret::new(passphrase))
+    }
+
+    /// Read a key written by `generate_key_file`
+    pub fn from_key_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let encoded = std::fs::read_to_string(path)?;
+        let key: [u8; KEY_LEN] = STANDARD
+            .decode(encoded.trim())?
+            .try_into()
+            .map_err(|_| format!("key file must hold a {}-byte base64 key", KEY_LEN))?;
+        Ok(TokenKey::Key(key))
+    }
+
+    /// Write a new random key to `path`, readable only by its owner on Unix.
+    /// Fails if the file exists rather than replace a key tokens are sealed with.
+    pub fn generate_key_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut key = [0u8; KEY_LEN];
+        OsRng.fill_bytes(&mut key);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        writeln!(options.open(path)?, "{}", STANDARD.encode(key))?;
+        Ok(TokenKey::Key(key))
+    }
+
+    /// Key derivation recorded in the file, so the wrong kind of key is reported as such
+    fn kdf(&self) -> &'static str {
+        match self {
+            TokenKey::Passphrase(_) => "argon2id",
+            TokenKey::Key(_) => "none",
+        }
+    }
+
+    /// Cipher for a file with `salt`; only passphrases use it
+    fn cipher(&self, salt: &[u8]) -> Result<XChaCha20Poly1305, Box<dyn std::error::Error + Send + Sync>> {
+        let mut key = match self {
+            TokenKey::Passphrase(passphrase) => {
+                let mut key = [0u8; KEY_LEN];
+                Argon2::default()
+                    .hash_password_into(passphrase.expose().as_bytes(), salt, &mut key)
+                    .map_err(|e| format!("deriving the token file key failed: {}", e))?;
+                key
+            }
+            TokenKey::Key(key) => *key,
+        };
+        let cipher = XChaCha20Poly1305::new(&key.into());
+        key.zeroize();
+        Ok(cipher)
+    }
+}
+
+impl Drop for TokenKey {
+    fn drop(&mut self) {
+        if let TokenKey::Key(key) = self {
+            key.zeroize();
+        }
+    }
+}
+
+/// An encrypted store as written to disk
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    format: String,
+    version: u32,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Tokens by user id in a file encrypted with XChaCha20-Poly1305 and rewritten
+/// on every change. Without the passphrase or key file the tokens can't be read,
+/// and a tampered file fails to open.
+pub struct EncryptedFileTokenStore {
+    path: PathBuf,
+    kdf: &'static str,
+    salt: Vec<u8>,
+    cipher: XChaCha20Poly1305,
+    tokens: Mutex<BTreeMap<String, StoredToken>>,
+}
+
+impl fmt::Debug for EncryptedFileTokenStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptedFileTokenStore").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+impl EncryptedFileTokenStore {
+    /// Load the store from `path` with `key` if it exists; changes are written
+    /// back immediately. A plaintext `FileTokenStore` file at `path` is encrypted in place.
+    pub fn open(path: impl AsRef<Path>, key: &TokenKey) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let envelope = contents.as_deref().and_then(|c| serde_json::from_str::<Envelope>(c).ok());
+
+        let (salt, tokens) = match &envelope {
+            Some(envelope) => (unseal_salt(envelope, key)?, BTreeMap::new()),
+            None => {
+                let mut salt = vec![0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                let tokens = match contents.as_deref() {
+                    Some(plaintext) => serde_json::from_str(plaintext).map_err(|e| format!("{} is neither an encrypted nor a plaintext token file: {}", path.display(), e))?,
+                    None => BTreeMap::new(),
+                };
+                (salt, tokens)
+            }
+        };
+        let store = Self {
+            cipher: key.cipher(&salt)?,
+            kdf: key.kdf(),
+            salt,
+            path,
+            tokens: Mutex::new(tokens),
+        };
+
+        match envelope {
+            Some(envelope) => *store.tokens.lock().unwrap() = store.unseal(&envelope)?,
+            // Replace the plaintext tokens straight away rather than on the next save
+            None if contents.is_some() => store.persist(&store.tokens.lock().unwrap())?,
+            None => {}
+        }
+        Ok(store)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn unseal(&self, envelope: &Envelope) -> Result<BTreeMap<String, StoredToken>, Box<dyn std::error::Error + Send + Sync>> {
+        let nonce = STANDARD.decode(&envelope.nonce)?;
+        if nonce.len() != NONCE_LEN {
+            return Err("token file has an invalid nonce".into());
+        }
+        let plaintext = self
+            .cipher
+            .decrypt(XNonce::from_slice(&nonce), STANDARD.decode(&envelope.ciphertext)?.as_slice())
+            .map_err(|_| "could not decrypt the token file: the passphrase or key is wrong, or the file was modified")?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Encrypt the store under a fresh nonce and write it to its file,
+    /// atomically replacing the previous contents
+    fn persist(&self, tokens: &BTreeMap<String, StoredToken>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), serde_json::to_vec(tokens)?.as_slice())
+            .map_err(|_| "encrypting the token file failed")?;
+        let envelope = Envelope {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            kdf: self.kdf.to_string(),
+            salt: STANDARD.encode(&self.salt),
+            nonce: STANDARD.encode(nonce),
+            ciphertext: STANDARD.encode(ciphertext),
+        };
+        write_atomically(&self.path, &serde_json::to_vec(&envelope)?)
+    }
+}
+
+/// Salt of an encrypted file, once it's known to be one `key` can open
+fn unseal_salt(envelope: &Envelope, key: &TokenKey) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    if envelope.format != FORMAT || envelope.version != VERSION {
+        return Err(format!("unsupported token file format {} version {}", envelope.format, envelope.version).into());
+    }
+    if envelope.kdf != key.kdf() {
+        let sealed_with = if envelope.kdf == "argon2id" { "a passphrase" } else { "a key file" };
+        return Err(format!("the token file is sealed with {}", sealed_with).into());
+    }
+    Ok(STANDARD.decode(&envelope.salt)?)
+}
+
+impl TokenStore for EncryptedFileTokenStore {
+    fn load(&self, user: &str) -> Result<Option<StoredToken>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tokens.lock().unwrap().get(user).cloned())
+    }
+
+    fn save(&self, user: &str, token: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.insert(user.to_string(), token.clone());
+        self.persist(&tokens)
+    }
+
+    fn clear(&self, user: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tokens = self.tokens.lock().unwrap();
+        if tokens.remove(user).is_some() {
+            self.persist(&tokens)?;
+        }
+        Ok(())
+    }
+
+    fn users(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tokens.lock().unwrap().keys().cloned().collect())
+    }
+}
@@ -0,0 +1,127 @@
+//! Environment configuration
+//! Creates a client from `YAHOO_*` environment variables, for deployments configured through their environment
+
+use std::ffi::OsString;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::{AccessToken, StoredToken, YahooFantasyClient};
+
+pub const CONSUMER_KEY_VAR: &str = "YAHOO_CONSUMER_KEY";
+
+pub const CONSUMER_SECRET_VAR: &str = "YAHOO_CONSUMER_SECRET";
+
+/// Optional; needs `ACCESS_TOKEN_SECRET_VAR` too
+pub const ACCESS_TOKEN_VAR: &str = "YAHOO_ACCESS_TOKEN";
+
+pub const ACCESS_TOKEN_SECRET_VAR: &str = "YAHOO_ACCESS_TOKEN_SECRET";
+
+/// Optional; lets the access token be refreshed
+pub const SESSION_HANDLE_VAR: &str = "YAHOO_SESSION_HANDLE";
+
+/// Optional RFC 3339 expiry of the access token, e.g. `2026-10-15T18:30:00Z`
+pub const TOKEN_EXPIRES_AT_VAR: &str = "YAHOO_TOKEN_EXPIRES_AT";
+
+/// What's wrong with an environment variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvProblem {
+    Missing,
+    /// Set, but empty or only whitespace
+    Empty,
+    NotUnicode,
+    /// Only meaningful alongside another variable, which isn't set
+    Requires(&'static str),
+    /// Set to something unusable, and why
+    Invalid(String),
+}
+
+/// An environment variable `YahooFantasyClient::from_env` couldn't use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvError {
+    pub var: &'static str,
+    pub problem: EnvProblem,
+}
+
+impl fmt::Display for EnvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.problem {
+            EnvProblem::Missing => write!(f, "{} is not set", self.var),
+            EnvProblem::Empty => write!(f, "{} is set but empty", self.var),
+            EnvProblem::NotUnicode => write!(f, "{} is not valid UTF-8", self.var),
+            EnvProblem::Requires(other) => write!(f, "{} is set but {} is not", self.var, other),
+            EnvProblem::Invalid(reason) => write!(f, "{} is invalid: {}", self.var, reason),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+impl YahooFantasyClient {
+    /// Client with app credentials from `YAHOO_CONSUMER_KEY` and
+    /// `YAHOO_CONSUMER_SECRET`, authenticated if `YAHOO_ACCESS_TOKEN` and
+    /// `YAHOO_ACCESS_TOKEN_SECRET` are set. `YAHOO_SESSION_HANDLE` lets the
+    /// token be refreshed, and `YAHOO_TOKEN_EXPIRES_AT` refreshed ahead of
+    /// expiry. Surrounding whitespace is ignored. A bad variable fails with
+    /// `EnvError`; the only other failure is the HTTP client failing to build.
+    pub fn from_env() -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let get = |var| optional(var, std::env::var_os(var));
+        let require = |var| get(var)?.ok_or(EnvError { var, problem: EnvProblem::Missing });
+
+        let consumer_key = require(CONSUMER_KEY_VAR)?;
+        let consumer_secret = require(CONSUMER_SECRET_VAR)?;
+        let token = get(ACCESS_TOKEN_VAR)?;
+        let token_secret = get(ACCESS_TOKEN_SECRET_VAR)?;
+        let session_handle = get(SESSION_HANDLE_VAR)?;
+        let expires_at = get(TOKEN_EXPIRES_AT_VAR)?
+            .map(|at| {
+                DateTime::parse_from_rfc3339(&at).map(|at| at.with_timezone(&Utc)).map_err(|e| EnvError {
+                    var: TOKEN_EXPIRES_AT_VAR,
+                    problem: EnvProblem::Invalid(format!("expected an RFC 3339 time like 2026-10-15T18:30:00Z: {}", e)),
+                })
+            })
+            .transpose()?;
+
+        let mut client = Self::new(consumer_key, consumer_secret)?;
+        let (token, secret) = match (token, token_secret) {
+            (Some(token), Some(secret)) => (token, secret),
+            (Some(_), None) => return Err(requires(ACCESS_TOKEN_VAR, ACCESS_TOKEN_SECRET_VAR).into()),
+            (None, Some(_)) => return Err(requires(ACCESS_TOKEN_SECRET_VAR, ACCESS_TOKEN_VAR).into()),
+            (None, None) => {
+                // Token details without a token would be silently ignored
+                return match (session_handle, expires_at) {
+                    (Some(_), _) => Err(requires(SESSION_HANDLE_VAR, ACCESS_TOKEN_VAR).into()),
+                    (_, Some(_)) => Err(requires(TOKEN_EXPIRES_AT_VAR, ACCESS_TOKEN_VAR).into()),
+                    (None, None) => Ok(client),
+                };
+            }
+        };
+        let token = AccessToken {
+            token: token.into(),
+            secret: secret.into(),
+            session_handle: session_handle.map(Into::into),
+            expires_in: None,
+        };
+        *client.token.get_mut().unwrap() = Some(StoredToken { token, expires_at });
+        Ok(client)
+    }
+}
+
+/// A variable's trimmed value, `None` when unset
+fn optional(var: &'static str, value: Option<OsString>) -> Result<Option<String>, EnvError> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    let value = value.into_string().map_err(|_| EnvError { var, problem: EnvProblem::NotUnicode })?;
+    match value.trim() {
+        "" => Err(EnvError { var, problem: EnvProblem::Empty }),
+        trimmed => Ok(Some(trimmed.to_string())),
+    }
+}
+
+fn requires(var: &'static str, other: &'static str) -> EnvError {
+    EnvError {
+        var,
+        problem: EnvProblem::Requires(other),
+    }
+}
@@ -0,0 +1,72 @@
+//! Pre-flight quota estimation
+//! Counts the requests a multi-request workflow will make, so callers can check them against the rate limiter first
+
+use std::time::Duration;
+
+use crate::YahooFantasyClient;
+
+/// A client operation that makes many requests, sized by what it will cover
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Workflow {
+    /// `league_archive`: standings, one scoreboard per week, draft results, and transactions
+    LeagueArchive { weeks: u32 },
+    /// `head_to_head_matrix`: standings and one scoreboard per week
+    HeadToHeadMatrix { weeks: u32 },
+    /// `verify_standings`: settings, standings, and one scoreboard per week
+    VerifyStandings { weeks: u32 },
+    /// `manager_efficiency`: standings, then settings, a roster, and roster points per team and week
+    ManagerEfficiency { teams: u32, weeks: u32 },
+    /// `roster_timeline`: the current roster and the transaction log
+    RosterTimeline,
+}
+
+impl Workflow {
+    /// Requests the workflow sends, before any are served from the response cache
+    pub fn requests(&self) -> u32 {
+        match *self {
+            Workflow::LeagueArchive { weeks } => 3 + weeks,
+            Workflow::HeadToHeadMatrix { weeks } => 1 + weeks,
+            Workflow::VerifyStandings { weeks } => 2 + weeks,
+            // get_roster_points fetches the roster again before the points
+            Workflow::ManagerEfficiency { teams, weeks } => 1 + teams * weeks * 4,
+            Workflow::RosterTimeline => 2,
+        }
+    }
+}
+
+/// Expected cost of a workflow against the client's current quota
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostEstimate {
+    pub requests: u32,
+    /// Rate limit tokens available when the estimate was made
+    pub tokens_available: f64,
+    /// Time spent waiting on the rate limiter if the workflow ran now; zero when it fits
+    pub wait: Duration,
+}
+
+impl CostEstimate {
+    /// Whether the workflow can run now without waiting on the rate limiter
+    pub fn fits_now(&self) -> bool {
+        self.wait.is_zero()
+    }
+}
+
+impl YahooFantasyClient {
+    /// Estimate what `workflow` would cost against the remaining quota, without sending anything
+    pub fn estimate_cost(&self, workflow: &Workflow) -> CostEstimate {
+        let limiter = self.rate_limiter();
+        let requests = workflow.requests();
+        let tokens_available = limiter.get_remaining_tokens();
+        let shortfall = requests as f64 - tokens_available;
+        let refill = if shortfall <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(shortfall / limiter.refill_rate()).unwrap_or(Duration::MAX)
+        };
+        CostEstimate {
+            requests,
+            tokens_available,
+            wait: refill.max(limiter.time_until_available()),
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! League event log
+//! Archives scoreboard events across seasons, with retention and compaction so archives stay bounded
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::{Points, ScoreboardEvent};
+
+/// Seasons kept by default, including the latest
+const DEFAULT_KEEP_SEASONS: u32 = 5;
+/// Age after which scoring plays are folded into summaries by default
+const DEFAULT_COMPACT_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// What an archived entry records
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoggedEvent {
+    Scoreboard { event: ScoreboardEvent },
+    /// A player's scoring plays for one week, folded together by compaction
+    ScoringSummary {
+        week: i32,
+        team_key: String,
+        player_key: String,
+        plays: u32,
+        points: Points,
+    },
+}
+
+/// An event with when and in which season it happened
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LogEntry {
+    pub season: i32,
+    /// Unix seconds
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: LoggedEvent,
+}
+
+/// How much history `EventLog::compact` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Seasons kept, counting back from the latest in the log; `None` keeps all
+    pub keep_seasons: Option<u32>,
+    /// Scoring plays older than this become per-player weekly summaries; `None` never compacts
+    pub compact_score_ticks_after: Option<Duration>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_seasons: Some(DEFAULT_KEEP_SEASONS),
+            compact_score_ticks_after: Some(DEFAULT_COMPACT_AFTER),
+        }
+    }
+}
+
+/// Outcome of one compaction pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    /// Entries removed with their expired seasons
+    pub expired: usize,
+    /// Scoring plays folded into summaries
+    pub compacted: usize,
+    /// Scoring summaries in the log after the pass
+    pub summaries: usize,
+}
+
+/// Append-only event archive for one league, optionally persisted to a JSON file
+#[derive(Debug, Default)]
+pub struct EventLog {
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<LogEntry>>,
+}
+
+impl EventLog {
+    /// Log that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the log from `path` if it exists; every change is written back immediately
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    /// Archive scoreboard events observed at `observed_at`
+    pub fn record(
+        &self,
+        season: i32,
+        observed_at: &DateTime<impl TimeZone>,
+        events: Vec<ScoreboardEvent>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.extend(events.into_iter().map(|event| LogEntry {
+            season,
+            timestamp: observed_at.timestamp(),
+            event: LoggedEvent::Scoreboard { event },
+        }));
+        self.persist(&entries)
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Vec<LogEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Apply `policy` as of `now`: drop expired seasons, then fold old scoring
+    /// plays into one summary per season, week, team, and player. Summaries
+    /// from earlier passes are merged, so running it repeatedly is safe.
+    pub fn compact(
+        &self,
+        policy: &RetentionPolicy,
+        now: &DateTime<impl TimeZone>,
+    ) -> Result<CompactionReport, Box<dyn std::error::Error + Send + Sync>> {
+        let mut entries = self.entries.lock().unwrap();
+        let mut report = CompactionReport::default();
+
+        if let (Some(keep), Some(latest)) = (policy.keep_seasons, entries.iter().map(|e| e.season).max()) {
+            let oldest = latest - keep.max(1) as i32 + 1;
+            let before = entries.len();
+            entries.retain(|e| e.season >= oldest);
+            report.expired = before - entries.len();
+        }
+
+        if let Some(after) = policy.compact_score_ticks_after {
+            let cutoff = now.timestamp() - after.as_secs() as i64;
+            let mut summaries: BTreeMap<(i32, i32, String, String), LogEntry> = BTreeMap::new();
+            let mut kept = Vec::with_capacity(entries.len());
+
+            for entry in entries.drain(..) {
+                let (week, team_key, player_key, plays, points) = match &entry.event {
+                    LoggedEvent::Scoreboard {
+                        event:
+                            ScoreboardEvent::ScoringPlay {
+                                week,
+                                team_key,
+                                player_key,
+                                points_delta,
+                            },
+                    } if entry.timestamp < cutoff => {
+                        report.compacted += 1;
+                        (*week, team_key, player_key, 1, *points_delta)
+                    }
+                    LoggedEvent::ScoringSummary {
+                        week,
+                        team_key,
+                        player_key,
+                        plays,
+                        points,
+                    } => (*week, team_key, player_key, *plays, *points),
+                    _ => {
+                        kept.push(entry);
+                        continue;
+                    }
+                };
+                let summary = summaries
+                    .entry((entry.season, week, team_key.clone(), player_key.clone()))
+                    .or_insert_with(|| LogEntry {
+                        season: entry.season,
+                        timestamp: entry.timestamp,
+                        event: LoggedEvent::ScoringSummary {
+                            week,
+                            team_key: team_key.clone(),
+                            player_key: player_key.clone(),
+                            plays: 0,
+                            points: Points::ZERO,
+                        },
+                    });
+                summary.timestamp = summary.timestamp.max(entry.timestamp);
+                if let LoggedEvent::ScoringSummary {
+                    plays: total_plays,
+                    points: total_points,
+                    ..
+                } = &mut summary.event
+                {
+                    *total_plays += plays;
+                    *total_points += points;
+                }
+            }
+
+            report.summaries = summaries.len();
+            kept.extend(summaries.into_values());
+            kept.sort_by_key(|e| e.timestamp);
+            *entries = kept;
+        }
+
+        self.persist(&entries)?;
+        Ok(report)
+    }
+
+    /// Write the log to its file, atomically replacing the previous contents
+    fn persist(&self, entries: &[LogEntry]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(entries)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+//! Recent-form and consistency metrics
+//! Rolling averages, boom/bust rates, and floor/ceiling percentiles from player game logs
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::PlayerGameLog;
+
+/// Point thresholds that define a boom or bust game
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FormThresholds {
+    pub boom: f64,
+    pub bust: f64,
+}
+
+impl Default for FormThresholds {
+    fn default() -> Self {
+        Self { boom: 40.0, bust: 15.0 }
+    }
+}
+
+/// Form and consistency summary for one player
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct FormMetrics {
+    pub player_key: String,
+    pub games: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Rolling average ending on each game date
+    pub rolling: Vec<(NaiveDate, f64)>,
+    /// Average over the most recent window
+    pub recent_average: f64,
+    pub boom_rate: f64,
+    pub bust_rate: f64,
+    /// 10th percentile game
+    pub floor: f64,
+    pub median: f64,
+    /// 90th percentile game
+    pub ceiling: f64,
+}
+
+impl FormMetrics {
+    /// Recent average relative to the season mean; above 1.0 means trending up
+    pub fn trend(&self) -> f64 {
+        if self.mean > 0.0 {
+            self.recent_average / self.mean
+        } else {
+            0.0
+        }
+    }
+
+    /// 1 minus the coefficient of variation, clamped to 0..=1; higher is steadier
+    pub fn consistency(&self) -> f64 {
+        if self.mean > 0.0 {
+            (1.0 - self.std_dev / self.mean).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Compute form metrics over the games a player played, with a rolling `window` in games
+pub fn form_metrics(log: &PlayerGameLog, window: usize, thresholds: FormThresholds) -> FormMetrics {
+    let games: Vec<(NaiveDate, f64)> = log.games().map(|l| (l.date, l.points.to_f64())).collect();
+    let points: Vec<f64> = games.iter().map(|(_, p)| *p).collect();
+
+    let mean = mean(&points);
+    let std_dev = if points.len() > 1 {
+        (points.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / (points.len() - 1) as f64).sqrt()
+    } else {
+        0.0
+    };
+
+    let rolling: Vec<(NaiveDate, f64)> = games
+        .iter()
+        .map(|(date, _)| *date)
+        .zip(rolling_average(&points, window))
+        .collect();
+    let recent_average = rolling.last().map_or(0.0, |(_, avg)| *avg);
+
+    let rate = |hit: &dyn Fn(f64) -> bool| {
+        if points.is_empty() {
+            0.0
+        } else {
+            points.iter().filter(|p| hit(**p)).count() as f64 / points.len() as f64
+        }
+    };
+
+    let mut sorted = points.clone();
+    sorted.sort_by(f64::total_cmp);
+
+    FormMetrics {
+        player_key: log.player_key.clone(),
+        games: points.len(),
+        mean,
+        std_dev,
+        rolling,
+        recent_average,
+        boom_rate: rate(&|p| p >= thresholds.boom),
+        bust_rate: rate(&|p| p < thresholds.bust),
+        floor: percentile(&sorted, 0.10),
+        median: percentile(&sorted, 0.50),
+        ceiling: percentile(&sorted, 0.90),
+    }
+}
+
+/// Trailing average over up to `window` values, one per input
+pub fn rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    (0..values.len())
+        .map(|i| mean(&values[i.saturating_sub(window - 1)..=i]))
+        .collect()
+}
+
+/// Linearly interpolated percentile (`p` in 0..=1) of ascending values
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        n => {
+            let rank = p.clamp(0.0, 1.0) * (n - 1) as f64;
+            let lower = rank.floor() as usize;
+            let upper = rank.ceil() as usize;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
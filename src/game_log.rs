@@ -0,0 +1,62 @@
+//! Player game logs
+//! Per-date stat lines over a range, cached permanently once a date is final
+
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate};
+
+use crate::player_stats::{game_key, stat_values};
+use crate::{response, time, DailyStatLine, PlayerGameLog, YahooFantasyClient};
+
+/// Days after which a date's stats are considered final (stat corrections settled)
+const FINAL_AFTER_DAYS: u64 = 2;
+
+/// Whether a date's stats can no longer change
+pub fn is_final(date: NaiveDate) -> bool {
+    time::today()
+        .checked_sub_days(Days::new(FINAL_AFTER_DAYS))
+        .is_some_and(|cutoff| date <= cutoff)
+}
+
+impl YahooFantasyClient {
+    /// Get a player's per-date stat lines for `start..=end`.
+    /// Each uncached date is one date-coverage stats request; final dates are cached without expiry.
+    pub async fn get_player_game_log(&self, player_key: &str, start: NaiveDate, end: NaiveDate) -> Result<PlayerGameLog, Box<dyn std::error::Error + Send + Sync>> {
+        if end < start {
+            return Err(format!("invalid date range {}..={}", start, end).into());
+        }
+
+        let mut lines = Vec::new();
+        for date in start.iter_days().take_while(|d| *d <= end) {
+            let cache_key = format!("game_log:{}:{}", player_key, time::format_date(date));
+            if let Some(cached) = self.cache().get(&cache_key) {
+                lines.push(serde_json::from_str(&cached)?);
+                continue;
+            }
+
+            let line = self.get_player_stats_for_date(player_key, date).await?;
+            let ttl = if is_final(date) { Duration::MAX } else { Duration::from_secs(300) };
+            self.cache().put_with_ttl(cache_key, serde_json::to_string(&line)?, ttl);
+            lines.push(line);
+        }
+
+        Ok(PlayerGameLog {
+            player_key: player_key.to_string(),
+            lines,
+        })
+    }
+
+    /// Get a player's stats for one date. A player didn't play when Yahoo
+    /// reports no nonzero stat for the date.
+    pub async fn get_player_stats_for_date(&self, player_key: &str, date: NaiveDate) -> Result<DailyStatLine, Box<dyn std::error::Error + Send + Sync>> {
+        let names = self.stat_names(game_key(player_key)).await?;
+        let body = self.get_json(&format!("player/{}/stats;type=date;date={}", player_key, time::format_date(date))).await?;
+        let (stats, points) = stat_values(&response::resource(&response::content(&body)?["player"]), &names);
+        Ok(DailyStatLine {
+            date,
+            played: stats.values().any(|value| *value != 0.0),
+            stats,
+            points,
+        })
+    }
+}
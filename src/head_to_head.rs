@@ -0,0 +1,16 @@
+//! Head-to-head records
+//! Pairwise record and points between every two teams, built from scoreboard history
+
+use crate::{HeadToHeadMatrix, YahooFantasyClient};
+
+impl YahooFantasyClient {
+    /// Pairwise head-to-head records for every completed week of the season
+    pub async fn head_to_head_matrix(&self, league_key: &str) -> Result<HeadToHeadMatrix, Box<dyn std::error::Error + Send + Sync>> {
+        let standings = self.get_standings(league_key).await?;
+        let mut scoreboards = Vec::new();
+        for week in 1..=standings.week {
+            scoreboards.push(self.get_scoreboard(league_key, week).await?);
+        }
+        Ok(HeadToHeadMatrix::from_scoreboards(league_key, &scoreboards))
+    }
+}
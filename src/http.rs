@@ -0,0 +1,302 @@
+//! HTTP execution
+//! Signs requests, sends them to the Yahoo API, and surfaces non-success responses
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+
+use crate::login::{AccessToken, InsufficientScope, Scope, TokenExpired, TokenRevoked, REQUEST_AUTH_URL};
+use crate::oauth::{self, Credentials};
+use crate::retry;
+use crate::transport::{HttpResponse, Method, Request, StreamingResponse};
+use crate::middleware::Next;
+use crate::{CancellationToken, Cancelled, Validators, YahooFantasyClient};
+
+/// A non-success HTTP response
+#[derive(Debug, Clone)]
+pub struct HttpError {
+    pub status: u16,
+    pub body: String,
+    /// Delay the server asked for with `Retry-After`, typically on 429 or 503
+    pub retry_after: Option<Duration>,
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// A request that ran past its connect or total timeout, or a group of
+/// requests that ran past its overall deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeoutError {
+    Connect(Duration),
+    Request(Duration),
+    Deadline(Duration),
+}
+
+impl fmt::Display for TimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeoutError::Connect(timeout) => write!(f, "connecting timed out after {:?}", timeout),
+            TimeoutError::Request(timeout) => write!(f, "request timed out after {:?}", timeout),
+            TimeoutError::Deadline(deadline) => write!(f, "deadline of {:?} passed before the task finished", deadline),
+        }
+    }
+}
+
+impl std::error::Error for TimeoutError {}
+
+impl YahooFantasyClient {
+    /// Send a request through the rate limiter and return the response body.
+    /// Transient failures of retryable requests are retried per the client's
+    /// `RetryPolicy`, and count toward its circuit breaker. A request Yahoo
+    /// rejects with `token_expired` is retried once after refreshing the token,
+    /// failing with `TokenExpired` if the token can't be refreshed, or
+    /// `TokenRevoked` if the user's authorization was revoked. Successful GET
+    /// responses are cached by URL; once expired, entries Yahoo sent an `ETag`
+    /// or `Last-Modified` for are revalidated, and a 304 reuses the cached body.
+    /// Dropping the returned future before the response arrives consumes no
+    /// rate limit token.
+    pub async fn execute(&self, request: &Request) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.execute_cancellable(request, &CancellationToken::new()).await
+    }
+
+    /// Like `execute`, but gives up with `Cancelled` as soon as `cancel` is
+    /// triggered, including while the request is in flight. An abandoned
+    /// attempt consumes no rate limit token.
+    pub async fn execute_cancellable(&self, request: &Request, cancel: &CancellationToken) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let original = request;
+        let mut stale = None;
+        if request.is_cacheable() {
+            if let Some(cached) = self.cache.get(request.url()) {
+                return Ok(cached);
+            }
+            stale = self.cache.stale(request.url());
+        }
+        // Revalidate an expired entry instead of downloading it again
+        let conditional = stale.as_ref().map(|(_, validators)| validators.apply(request.clone()));
+        let request = conditional.as_ref().unwrap_or(request);
+
+        let mut attempt = 1;
+        let mut refreshed = false;
+        let response = loop {
+            let result = {
+                self.refresh_token_if_due().await?;
+                self.circuit_breaker.check()?;
+                let token = self.token();
+                let mut in_flight = self.begin_weighted_request(request.cost(), Some(cancel)).await?;
+                // Once dispatch starts the request may reach Yahoo, so cancelling
+                // from here on leaves its tokens spent
+                in_flight.sent();
+                let result = tokio::select! {
+                    biased;
+                    result = self.dispatch_response(request, token.as_ref().map(AccessToken::pair)) => result,
+                    _ = cancel.cancelled() => return Err(Box::new(Cancelled)),
+                };
+                match &result {
+                    Err(e) if retry::is_transient(e.as_ref()) => self.circuit_breaker.record_failure(),
+                    _ => self.circuit_breaker.record_success(),
+                }
+                result
+            };
+            match result {
+                // Refresh an expired token and retry once, whatever the retry policy
+                Err(e) if is_token_expired(e.as_ref()) => {
+                    if refreshed {
+                        return Err(Box::new(refreshed_token_rejected()));
+                    }
+                    self.refresh_rejected_token().await?;
+                    refreshed = true;
+                }
+                Err(e) if request.is_retryable() && attempt < self.retry.max_attempts && retry::is_transient(e.as_ref()) => {
+                    // With Retry-After the rate limiter is already paused; begin_request waits it out
+                    if retry::retry_after(e.as_ref()).is_none() {
+                        tokio::select! {
+                            _ = tokio::time::sleep(self.retry.delay(attempt)) => {}
+                            _ = cancel.cancelled() => return Err(Box::new(Cancelled)),
+                        }
+                    }
+                    attempt += 1;
+                }
+                result => break result?,
+            }
+        };
+
+        if let (304, Some((body, validators))) = (response.status, stale) {
+            self.cache.put_validated(original.url().to_string(), body.clone(), validators);
+            return Ok(body);
+        }
+        if request.is_cacheable() && response.is_success() {
+            let validators = Validators::from_response(&response);
+            self.cache.put_validated(request.url().to_string(), response.body.clone(), validators);
+        }
+        Ok(response.body)
+    }
+
+    /// Send a request through the rate limiter and stream the response body
+    /// instead of buffering it, for very large collections. Bypasses the cache,
+    /// retries, and middleware, though a request rejected for an expired token is
+    /// refreshed and sent once more; each chunk must arrive within the request's timeout.
+    pub async fn execute_streaming(&self, request: &Request) -> Result<StreamingResponse, Box<dyn std::error::Error + Send + Sync>> {
+        match self.send_streaming(request).await {
+            Err(e) if is_token_expired(e.as_ref()) => {
+                self.refresh_rejected_token().await?;
+                match self.send_streaming(request).await {
+                    Err(e) if is_token_expired(e.as_ref()) => Err(Box::new(refreshed_token_rejected())),
+                    result => result,
+                }
+            }
+            result => result,
+        }
+    }
+
+    /// One attempt of `execute_streaming`
+    async fn send_streaming(&self, request: &Request) -> Result<StreamingResponse, Box<dyn std::error::Error + Send + Sync>> {
+        self.refresh_token_if_due().await?;
+        let token = self.token();
+        let signed = self.sign(request, token.as_ref().map(AccessToken::pair))?;
+        let timeout = request.options().timeout.unwrap_or(self.request_timeout);
+
+        self.circuit_breaker.check()?;
+        let response = {
+            let mut in_flight = self.begin_weighted_request(request.cost(), None).await?;
+            let response = tokio::time::timeout(timeout, self.transport.send_streaming(&signed))
+                .await
+                .unwrap_or(Err(Box::new(TimeoutError::Request(timeout))));
+            in_flight.sent();
+            response
+        };
+        let response = match response {
+            Err(e) if retry::is_transient(e.as_ref()) => {
+                self.circuit_breaker.record_failure();
+                return Err(e);
+            }
+            response => response?,
+        };
+        if response.status == 429 || response.status >= 500 {
+            self.circuit_breaker.record_failure();
+        } else {
+            self.circuit_breaker.record_success();
+        }
+
+        if !response.is_success() {
+            let status = response.status;
+            let retry_after = response.header("Retry-After").and_then(retry::parse_retry_after);
+            if let Some(delay) = retry_after {
+                self.rate_limiter.pause_for(delay);
+            }
+            let body = response.with_chunk_timeout(timeout).text().await?;
+            return Err(Box::new(HttpError { status, body, retry_after }));
+        }
+        Ok(response.with_chunk_timeout(timeout))
+    }
+
+    /// Stream a response body straight to `writer`, e.g. a file, returning the bytes written
+    pub async fn download(&self, request: &Request, writer: &mut impl std::io::Write) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        self.execute_streaming(request).await?.write_to(writer).await
+    }
+
+    /// GET `path` as JSON through `execute`
+    pub(crate) async fn get_json(&self, path: &str) -> Result<Value, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(serde_json::from_str(&self.execute(&self.json_request(path)).await?)?)
+    }
+
+    /// GET request for `path` asking Yahoo for a JSON response
+    pub(crate) fn json_request(&self, path: &str) -> Request {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        self.request(Method::Get, &format!("{}{}format=json", path, separator))
+    }
+
+    /// The client's current access token, if it has one
+    fn token(&self) -> Option<AccessToken> {
+        self.access_token()
+    }
+
+    /// Add the OAuth `Authorization` header, signing with `token` if given
+    fn sign(&self, request: &Request, token: Option<(&str, &str)>) -> Result<Request, Box<dyn std::error::Error + Send + Sync>> {
+        let credentials = Credentials {
+            consumer_key: &self.consumer_key,
+            consumer_secret: self.consumer_secret.expose(),
+            token,
+        };
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let authorization = oauth::authorization_header(request.method(), request.url(), &credentials, timestamp, &oauth::nonce())?;
+        Ok(request.clone().header("Authorization", &authorization))
+    }
+
+    /// Sign a request with `token` and send it through the middleware and transport, bounded by its own timeout or the client's.
+    /// Only touches the rate limiter to pause it when the server sends `Retry-After`.
+    pub(crate) async fn dispatch_with_token(&self, request: &Request, token: Option<(&str, &str)>) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.dispatch_response(request, token).await?.body)
+    }
+
+    /// `dispatch_with_token`, returning the whole response. 304 Not Modified
+    /// counts as success, for conditional requests.
+    async fn dispatch_response(&self, request: &Request, token: Option<(&str, &str)>) -> Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let signed = self.sign(request, token)?;
+        let timeout = request.options().timeout.unwrap_or(self.request_timeout);
+        let response = tokio::time::timeout(timeout, Next::new(&*self.transport, &self.middleware).run(signed))
+            .await
+            .map_err(|_| TimeoutError::Request(timeout))??;
+        if !response.is_success() && response.status != 304 {
+            let retry_after = response.header("Retry-After").and_then(retry::parse_retry_after);
+            if let Some(delay) = retry_after {
+                self.rate_limiter.pause_for(delay);
+            }
+            if response.status == 401 && response.body.contains("token_revoked") {
+                return Err(Box::new(TokenRevoked {
+                    status: response.status,
+                    body: response.body,
+                }));
+            }
+            if let Some(scope) = missing_scope(request, response.status, &response.body) {
+                return Err(Box::new(InsufficientScope {
+                    scope,
+                    reauthorize_url: REQUEST_AUTH_URL.to_string(),
+                    status: response.status,
+                    body: response.body,
+                }));
+            }
+            return Err(Box::new(HttpError {
+                status: response.status,
+                body: response.body,
+                retry_after,
+            }));
+        }
+        Ok(response)
+    }
+}
+
+/// Scope a 401/403 rejection says the token lacks: the body names a scope or
+/// permission problem, or a write was forbidden outright
+fn missing_scope(request: &Request, status: u16, body: &str) -> Option<Scope> {
+    if status != 401 && status != 403 {
+        return None;
+    }
+    let body = body.to_ascii_lowercase();
+    let scope_problem = body.contains("scope") || body.contains("permission_denied") || body.contains("insufficient");
+    let forbidden_write = status == 403 && request.method() != Method::Get;
+    if !scope_problem && !forbidden_write {
+        return None;
+    }
+    Some(if request.method() == Method::Get { Scope::Read } else { Scope::Write })
+}
+
+/// Yahoo still called the token expired after it was refreshed
+fn refreshed_token_rejected() -> TokenExpired {
+    TokenExpired {
+        reason: "Yahoo rejected the refreshed token as well".to_string(),
+    }
+}
+
+/// Whether Yahoo rejected the request because the access token expired
+fn is_token_expired(error: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    error
+        .downcast_ref::<HttpError>()
+        .is_some_and(|e| e.status == 401 && e.body.contains("token_expired"))
+}
@@ -0,0 +1,5 @@
+//! Framework integrations
+//! Glue for using the client from web frameworks, each behind its own feature
+
+#[cfg(feature = "axum")]
+pub mod web;
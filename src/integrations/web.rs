@@ -0,0 +1,281 @@
+//! Axum integration
+//! Shared client state, ready-made login and OAuth callback handlers, and a per-session client extractor
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{FromRef, FromRequestParts, Query, State};
+use axum::http::header::{COOKIE, SET_COOKIE};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{AppendHeaders, IntoResponse, Redirect, Response};
+use serde::Deserialize;
+
+use crate::login::{AccessToken, RequestToken};
+use crate::session::{MemoryTokenStore, SessionManager, TokenStore};
+use crate::{oauth, YahooFantasyClient};
+
+/// Cookie holding the session id set by `oauth_callback`
+pub const SESSION_COOKIE: &str = "yahoo_session";
+
+/// Cookie tying a login started by `login` to the browser that started it
+pub const LOGIN_STATE_COOKIE: &str = "yahoo_login_state";
+
+/// How long a user has to approve a login before its request token is dropped
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Logins awaiting approval at once; starting another drops the oldest
+pub const MAX_PENDING_LOGINS: usize = 1024;
+
+/// How long a session lasts by default before the user has to sign in again
+pub const DEFAULT_SESSION_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// App state sharing one client, plus logins in progress and signed-in sessions.
+/// Add it to the router with `with_state`, or expose it through `FromRef`.
+#[derive(Debug, Clone)]
+pub struct YahooState {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// Session tokens and per-session clients, with session ids as user ids
+    sessions: SessionManager,
+    callback_url: String,
+    session_ttl: Duration,
+    /// Request tokens awaiting the user's approval, by token
+    pending: Mutex<HashMap<String, PendingLogin>>,
+    /// When each session started, by session id
+    started: Mutex<HashMap<String, Instant>>,
+}
+
+#[derive(Debug)]
+struct PendingLogin {
+    request_token: RequestToken,
+    /// Value of the login state cookie set for this login
+    state: String,
+    started: Instant,
+}
+
+impl YahooState {
+    /// `callback_url` is where `oauth_callback` is mounted, as Yahoo should redirect to it.
+    /// Sessions are kept in memory and last `DEFAULT_SESSION_TTL`.
+    pub fn new(client: YahooFantasyClient, callback_url: &str) -> Self {
+        Self::with_store(client, Arc::new(MemoryTokenStore::new()), callback_url, DEFAULT_SESSION_TTL)
+    }
+
+    /// Keep session tokens in `store`, by session id, and end sessions `session_ttl`
+    /// after they start. Sessions found in the store after a restart start afresh.
+    pub fn with_store(client: YahooFantasyClient, store: Arc<dyn TokenStore>, callback_url: &str, session_ttl: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                sessions: SessionManager::new(client, store),
+                callback_url: callback_url.to_string(),
+                session_ttl,
+                pending: Mutex::new(HashMap::new()),
+                started: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// The shared app-level client
+    pub fn client(&self) -> &YahooFantasyClient {
+        self.inner.sessions.client()
+    }
+
+    /// Session tokens and the clients acting for each session
+    pub fn sessions(&self) -> &SessionManager {
+        &self.inner.sessions
+    }
+
+    /// The session's current tokens, or `None` once it has ended or expired
+    pub fn session(&self, session_id: &str) -> Option<AccessToken> {
+        if !self.session_live(session_id) {
+            return None;
+        }
+        self.inner.sessions.store().load(session_id).ok().flatten().map(|stored| stored.token)
+    }
+
+    /// Store tokens under a new random session id, e.g. when restoring them from a database
+    pub fn insert_session(&self, token: AccessToken) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        self.remove_expired_sessions();
+        let session_id = oauth::nonce();
+        self.inner.sessions.insert(&session_id, token)?;
+        self.inner.started.lock().unwrap().insert(session_id.clone(), Instant::now());
+        Ok(session_id)
+    }
+
+    /// Sign a session out
+    pub fn remove_session(&self, session_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.inner.started.lock().unwrap().remove(session_id);
+        self.inner.sessions.remove(session_id)
+    }
+
+    /// Whether the session is within its TTL. Sessions this process hasn't seen
+    /// start (restored from a persistent store) are timed from first use.
+    fn session_live(&self, session_id: &str) -> bool {
+        let started = *self.inner.started.lock().unwrap().entry(session_id.to_string()).or_insert_with(Instant::now);
+        if started.elapsed() < self.inner.session_ttl {
+            return true;
+        }
+        // Best effort: an expired session is refused either way
+        let _ = self.remove_session(session_id);
+        false
+    }
+
+    fn remove_expired_sessions(&self) {
+        let ttl = self.inner.session_ttl;
+        let expired: Vec<String> = self.inner.started.lock().unwrap().iter().filter(|(_, started)| started.elapsed() >= ttl).map(|(id, _)| id.clone()).collect();
+        for session_id in expired {
+            let _ = self.remove_session(&session_id);
+        }
+    }
+
+    /// Remember a login until the user approves it, returning the value for its state cookie
+    fn insert_pending(&self, request_token: RequestToken) -> String {
+        let state = oauth::nonce();
+        let mut pending = self.inner.pending.lock().unwrap();
+        pending.retain(|_, login| login.started.elapsed() < LOGIN_TIMEOUT);
+        if pending.len() >= MAX_PENDING_LOGINS {
+            let oldest = pending.iter().min_by_key(|(_, login)| login.started).map(|(token, _)| token.clone());
+            if let Some(oldest) = oldest {
+                pending.remove(&oldest);
+            }
+        }
+        pending.insert(
+            request_token.token.clone(),
+            PendingLogin {
+                request_token,
+                state: state.clone(),
+                started: Instant::now(),
+            },
+        );
+        state
+    }
+
+    /// Take the login for `token` if it hasn't timed out and was started with `state`
+    fn take_pending(&self, token: &str, state: &str) -> Option<RequestToken> {
+        let mut pending = self.inner.pending.lock().unwrap();
+        let login = pending.get(token)?;
+        if login.started.elapsed() >= LOGIN_TIMEOUT {
+            pending.remove(token);
+            return None;
+        }
+        if login.state != state {
+            return None;
+        }
+        pending.remove(token).map(|login| login.request_token)
+    }
+
+    /// `; Secure` when the app is served over https
+    fn secure(&self) -> &'static str {
+        if self.inner.callback_url.starts_with("https://") {
+            "; Secure"
+        } else {
+            ""
+        }
+    }
+}
+
+/// Handler that starts a login and redirects the user to Yahoo to approve it.
+/// Sets a short-lived cookie that `oauth_callback` checks, so a callback only
+/// completes in the browser that started the login.
+pub async fn login(State(state): State<YahooState>) -> Result<Response, WebError> {
+    let request_token = state.client().get_request_token(&state.inner.callback_url).await?;
+    let authorize_url = request_token.authorize_url.clone();
+    let login_state = state.insert_pending(request_token);
+
+    let cookie = format!("{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax{}", LOGIN_STATE_COOKIE, login_state, LOGIN_TIMEOUT.as_secs(), state.secure());
+    Ok(([(SET_COOKIE, cookie)], Redirect::to(&authorize_url)).into_response())
+}
+
+/// Query Yahoo adds when redirecting back after approval
+#[derive(Debug, Deserialize)]
+pub struct CallbackParams {
+    pub oauth_token: String,
+    pub oauth_verifier: String,
+}
+
+/// Handler for the OAuth callback: exchanges the verifier for an access token,
+/// starts a session, sets the session cookie, and redirects to `/`. Rejects
+/// with 401 unless the login state cookie matches the login being completed.
+pub async fn oauth_callback(State(state): State<YahooState>, headers: HeaderMap, Query(params): Query<CallbackParams>) -> Result<Response, WebError> {
+    let login_state = cookie(&headers, LOGIN_STATE_COOKIE).ok_or(WebError::Unauthorized)?;
+    let request_token = state.take_pending(&params.oauth_token, &login_state).ok_or(WebError::Unauthorized)?;
+    let access_token = state.client().get_access_token(&request_token, &params.oauth_verifier).await?;
+    let session_id = state.insert_session(access_token)?;
+
+    let secure = state.secure();
+    let session = format!("{}={}; Path=/; HttpOnly; SameSite=Lax{}", SESSION_COOKIE, session_id, secure);
+    let clear_state = format!("{}=; Path=/; Max-Age=0; HttpOnly; SameSite=Lax{}", LOGIN_STATE_COOKIE, secure);
+    Ok((AppendHeaders([(SET_COOKIE, session), (SET_COOKIE, clear_state)]), Redirect::to("/")).into_response())
+}
+
+/// Extractor for a client acting as the signed-in user, found by the session
+/// cookie. The client is the session's cached one, so tokens it refreshes are
+/// kept for the next request. Rejects with 401 when there is no live session.
+#[derive(Debug)]
+pub struct UserClient(pub Arc<YahooFantasyClient>);
+
+impl<S> FromRequestParts<S> for UserClient
+where
+    YahooState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = WebError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let state = YahooState::from_ref(state);
+        let session_id = cookie(&parts.headers, SESSION_COOKIE).ok_or(WebError::Unauthorized)?;
+        state.session(&session_id).ok_or(WebError::Unauthorized)?;
+        Ok(UserClient(state.inner.sessions.client_for(&session_id).await?))
+    }
+}
+
+/// Value of the named cookie in the request
+fn cookie(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie, _)| *cookie == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// Failure in a handler: a missing session, or an error talking to Yahoo
+#[derive(Debug)]
+pub enum WebError {
+    Unauthorized,
+    Upstream(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for WebError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebError::Unauthorized => f.write_str("not signed in to Yahoo"),
+            WebError::Upstream(error) => write!(f, "Yahoo request failed: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for WebError {}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for WebError {
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        WebError::Upstream(error)
+    }
+}
+
+impl IntoResponse for WebError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            WebError::Unauthorized => StatusCode::UNAUTHORIZED,
+            WebError::Upstream(_) => StatusCode::BAD_GATEWAY,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,223 @@
+//! League integrity checks
+//! Advisory heuristics for lopsided trades, unset lineups, and mass drops before the playoffs
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::time::WeekInstants;
+use crate::{LeagueSettings, Points, Transaction, TransactionType, WeekRange};
+
+/// Tunable limits for the integrity heuristics
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IntegrityThresholds {
+    /// Flag trades where the weaker side receives less than this share of the stronger side's value
+    pub trade_value_ratio: f64,
+    /// Ignore trades whose value gap is below this
+    pub min_trade_gap: f64,
+    /// Flag teams that leave fillable slots empty in at least this many snapshots
+    pub unset_lineups: usize,
+    /// Flag teams that drop at least this many players in the pre-playoff window
+    pub mass_drops: usize,
+    /// Weeks before the playoffs that count as the pre-playoff window
+    pub drop_window_weeks: u32,
+}
+
+impl Default for IntegrityThresholds {
+    fn default() -> Self {
+        Self {
+            trade_value_ratio: 0.5,
+            min_trade_gap: 20.0,
+            unset_lineups: 2,
+            mass_drops: 5,
+            drop_window_weeks: 2,
+        }
+    }
+}
+
+/// A bench player in a lineup snapshot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct BenchPlayer {
+    pub player_key: String,
+    pub positions: Vec<String>,
+    /// Playing this coverage period (not injured, on bye, or off)
+    pub is_active: bool,
+}
+
+/// A team's lineup for one week or date, as far as the checks need it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LineupSnapshot {
+    pub team_key: String,
+    /// Week or date coverage, e.g. "week:12"
+    pub coverage: String,
+    /// Starting slot positions left empty
+    pub empty_slots: Vec<String>,
+    pub bench: Vec<BenchPlayer>,
+}
+
+/// A suspicious pattern worth a commissioner's attention
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityFlag {
+    LopsidedTrade {
+        transaction_key: String,
+        /// Value each team received
+        received: Vec<(String, Points)>,
+        ratio: f64,
+    },
+    UnsetLineups {
+        team_key: String,
+        /// Coverages where an active bench player could have filled an empty slot
+        coverages: Vec<String>,
+    },
+    MassDrops {
+        team_key: String,
+        player_keys: Vec<String>,
+    },
+}
+
+/// Advisory output of the integrity checks for one league
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct IntegrityReport {
+    pub league_key: String,
+    pub flags: Vec<IntegrityFlag>,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.flags.is_empty()
+    }
+}
+
+/// Runs the integrity heuristics with a set of thresholds
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityChecker {
+    thresholds: IntegrityThresholds,
+}
+
+impl IntegrityChecker {
+    pub fn new(thresholds: IntegrityThresholds) -> Self {
+        Self { thresholds }
+    }
+
+    /// Run every check and collect the flags
+    pub fn check(
+        &self,
+        settings: &LeagueSettings,
+        weeks: &[WeekRange],
+        transactions: &[Transaction],
+        lineups: &[LineupSnapshot],
+        values: &HashMap<String, Points>,
+    ) -> IntegrityReport {
+        let mut flags = self.lopsided_trades(transactions, values);
+        flags.extend(self.unset_lineups(settings, lineups));
+        flags.extend(self.mass_drops(settings, weeks, transactions));
+        IntegrityReport {
+            league_key: settings.league_key.clone(),
+            flags,
+        }
+    }
+
+    /// Trades where one side got far less value than the other; players without a value count as zero
+    pub fn lopsided_trades(&self, transactions: &[Transaction], values: &HashMap<String, Points>) -> Vec<IntegrityFlag> {
+        transactions
+            .iter()
+            .filter(|t| t.kind == TransactionType::Trade)
+            .filter_map(|trade| {
+                let mut received: BTreeMap<String, Points> = BTreeMap::new();
+                for player in &trade.players {
+                    if let Some(team) = &player.destination_team_key {
+                        *received.entry(team.clone()).or_default() +=
+                            values.get(&player.player_key).copied().unwrap_or(Points::ZERO);
+                    }
+                }
+                let low = received.values().copied().min()?;
+                let high = received.values().copied().max()?;
+                if (high - low).to_f64() < self.thresholds.min_trade_gap {
+                    return None;
+                }
+                let ratio = if high.to_f64() > 0.0 { low.to_f64() / high.to_f64() } else { 1.0 };
+                (ratio < self.thresholds.trade_value_ratio).then(|| IntegrityFlag::LopsidedTrade {
+                    transaction_key: trade.transaction_key.clone(),
+                    received: received.into_iter().collect(),
+                    ratio,
+                })
+            })
+            .collect()
+    }
+
+    /// Teams that repeatedly leave starting slots empty while an eligible active player sits on the bench
+    pub fn unset_lineups(&self, settings: &LeagueSettings, lineups: &[LineupSnapshot]) -> Vec<IntegrityFlag> {
+        let mut by_team: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for lineup in lineups {
+            let fillable = lineup.empty_slots.iter().any(|slot| {
+                settings
+                    .roster_slots
+                    .iter()
+                    .find(|s| s.position == *slot)
+                    .is_some_and(|s| {
+                        lineup
+                            .bench
+                            .iter()
+                            .any(|p| p.is_active && p.positions.iter().any(|pos| s.accepts(pos)))
+                    })
+            });
+            if fillable {
+                by_team.entry(&lineup.team_key).or_default().push(lineup.coverage.clone());
+            }
+        }
+
+        by_team
+            .into_iter()
+            .filter(|(_, coverages)| coverages.len() >= self.thresholds.unset_lineups)
+            .map(|(team_key, coverages)| IntegrityFlag::UnsetLineups {
+                team_key: team_key.to_string(),
+                coverages,
+            })
+            .collect()
+    }
+
+    /// Teams dropping many players in the weeks leading up to the playoffs
+    pub fn mass_drops(&self, settings: &LeagueSettings, weeks: &[WeekRange], transactions: &[Transaction]) -> Vec<IntegrityFlag> {
+        let Some(playoff_week) = settings.playoff_start_week else {
+            return Vec::new();
+        };
+        let first_week = playoff_week.saturating_sub(self.thresholds.drop_window_weeks);
+        let Some(window_start) = weeks.iter().find(|w| w.week == first_week.max(1)) else {
+            return Vec::new();
+        };
+        let Some(window_end) = weeks.iter().find(|w| w.week == playoff_week) else {
+            return Vec::new();
+        };
+        let (start, end) = (window_start.starts_at().timestamp(), window_end.starts_at().timestamp());
+
+        let mut by_team: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for transaction in transactions {
+            if !matches!(transaction.kind, TransactionType::Drop | TransactionType::AddDrop)
+                || transaction.timestamp < start
+                || transaction.timestamp >= end
+            {
+                continue;
+            }
+            for player in &transaction.players {
+                if let (Some(team), None) = (&player.source_team_key, &player.destination_team_key) {
+                    by_team.entry(team).or_default().push(player.player_key.clone());
+                }
+            }
+        }
+
+        by_team
+            .into_iter()
+            .filter(|(_, dropped)| dropped.len() >= self.thresholds.mass_drops)
+            .map(|(team_key, player_keys)| IntegrityFlag::MassDrops {
+                team_key: team_key.to_string(),
+                player_keys,
+            })
+            .collect()
+    }
+}
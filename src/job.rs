@@ -0,0 +1,168 @@
+//! Resumable jobs
+//! Runs a step per work item, checkpointing each result so a crashed or quota-limited run resumes where it stopped
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{CancellationToken, YahooFantasyClient};
+
+/// Saved state of a job: results of completed items and errors of failed ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct JobCheckpoint<T> {
+    pub completed: BTreeMap<String, T>,
+    /// Failed items with their error; retried on the next run
+    pub failed: BTreeMap<String, String>,
+}
+
+impl<T> Default for JobCheckpoint<T> {
+    fn default() -> Self {
+        Self {
+            completed: BTreeMap::new(),
+            failed: BTreeMap::new(),
+        }
+    }
+}
+
+/// Why a run stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStop {
+    /// Every pending item was attempted
+    Finished,
+    /// The rate limiter fell below the reserved token floor
+    QuotaLow,
+    Cancelled,
+}
+
+/// A list of work items processed one at a time. Completed items are skipped
+/// on later runs, so re-running after a crash or `JobStop::QuotaLow` only
+/// fetches what's left. With `open`, the checkpoint is written after every item.
+#[derive(Debug)]
+pub struct Job<T> {
+    items: Vec<String>,
+    path: Option<PathBuf>,
+    requests_per_item: u32,
+    reserve_tokens: f64,
+    checkpoint: JobCheckpoint<T>,
+}
+
+impl<T: Serialize + DeserializeOwned> Job<T> {
+    /// Job whose checkpoint lives only as long as the process
+    pub fn in_memory(items: Vec<String>) -> Self {
+        Self::resume(items, JobCheckpoint::default())
+    }
+
+    /// Continue from a checkpoint saved elsewhere
+    pub fn resume(items: Vec<String>, checkpoint: JobCheckpoint<T>) -> Self {
+        Self {
+            items,
+            path: None,
+            requests_per_item: 1,
+            reserve_tokens: 0.0,
+            checkpoint,
+        }
+    }
+
+    /// Load the checkpoint from `path` if it exists; progress is written back after every item
+    pub fn open(items: Vec<String>, path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let checkpoint = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => JobCheckpoint::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            ..Self::resume(items, checkpoint)
+        })
+    }
+
+    /// Requests each step makes, used to check the reserve before starting it
+    pub fn requests_per_item(mut self, requests: u32) -> Self {
+        self.requests_per_item = requests.max(1);
+        self
+    }
+
+    /// Stop rather than wait when the rate limiter has fewer tokens than this
+    pub fn reserve_tokens(mut self, tokens: f64) -> Self {
+        self.reserve_tokens = tokens;
+        self
+    }
+
+    pub fn checkpoint(&self) -> &JobCheckpoint<T> {
+        &self.checkpoint
+    }
+
+    /// Items not yet completed, including failed ones, in the original order
+    pub fn pending(&self) -> Vec<&str> {
+        self.items
+            .iter()
+            .filter(|item| !self.checkpoint.completed.contains_key(*item))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending().is_empty()
+    }
+
+    /// Results of completed items, by item
+    pub fn results(&self) -> &BTreeMap<String, T> {
+        &self.checkpoint.completed
+    }
+
+    pub fn into_results(self) -> BTreeMap<String, T> {
+        self.checkpoint.completed
+    }
+
+    /// Run `step` for each pending item, checkpointing after each one. Errors
+    /// from `step` are recorded and don't stop the run; only failing to write
+    /// the checkpoint does.
+    pub async fn run<'c, F, Fut>(
+        &mut self,
+        client: &'c YahooFantasyClient,
+        cancel: &CancellationToken,
+        mut step: F,
+    ) -> Result<JobStop, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: FnMut(&'c YahooFantasyClient, String) -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        let pending: Vec<String> = self.pending().into_iter().map(str::to_string).collect();
+        for item in pending {
+            if cancel.is_cancelled() || client.is_shut_down() {
+                return Ok(JobStop::Cancelled);
+            }
+            if client.rate_limiter().get_remaining_tokens() < self.reserve_tokens + self.requests_per_item as f64 {
+                return Ok(JobStop::QuotaLow);
+            }
+
+            match step(client, item.clone()).await {
+                Ok(result) => {
+                    self.checkpoint.failed.remove(&item);
+                    self.checkpoint.completed.insert(item, result);
+                }
+                Err(error) => {
+                    self.checkpoint.failed.insert(item, error.to_string());
+                }
+            }
+            self.persist()?;
+        }
+        Ok(JobStop::Finished)
+    }
+
+    /// Write the checkpoint to its file, atomically replacing the previous one
+    fn persist(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&self.checkpoint)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
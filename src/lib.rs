@@ -2,113 +2,895 @@
 //! Core API client with authentication, rate limiting, and caching
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant, SystemTime};
-use serde::{Deserialize, Serialize};
+
+use tokio::sync::Notify;
+
+pub mod acquisitions;
+#[cfg(feature = "arena")]
+pub mod arena;
+pub mod auction;
+pub mod audit;
+pub mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod bootstrap;
+pub mod browser_login;
+pub mod builder;
+pub mod cancel;
+pub mod caps;
+pub mod circuit_breaker;
+pub mod compat;
+#[cfg(feature = "config")]
+pub mod config;
+pub mod diff;
+pub mod draft;
+pub mod eligibility;
+#[cfg(feature = "encryption")]
+pub mod encrypted_store;
+pub mod env;
+pub mod estimate;
+pub mod event_log;
+pub mod form;
+pub mod game_log;
+pub mod head_to_head;
+pub mod http;
+pub mod integrations;
+pub mod integrity;
+pub mod job;
+pub mod lineup;
+pub mod login;
+pub mod middleware;
+pub mod oauth;
+pub mod pagination;
+pub mod player_ref;
+pub mod player_stats;
+pub mod polling;
+pub mod progress;
+pub mod projection_cache;
+pub mod projections;
+pub mod reports;
+pub mod quota_history;
+pub mod research;
+pub mod response;
+pub mod retro;
+pub mod retry;
+pub mod roster_timeline;
+pub mod schedule;
+pub mod score_edits;
+pub mod scoreboard_diff;
+pub mod season_sim;
+pub mod secret;
+pub mod session;
+pub mod settings_report;
+pub mod site;
+pub mod standings_diff;
+pub mod task_group;
+pub mod tiebreakers;
+pub mod time;
+pub mod transport;
+pub mod write_queue;
+pub mod xml;
+
+pub use acquisitions::count_adds;
+#[cfg(feature = "arena")]
+pub use arena::{BatchPlayer, ParsedBatch};
+pub use auction::{AuctionTracker, Nomination, TeamBudget};
+pub use audit::{AuditAction, AuditEntry, AuditTrail};
+pub use batch::{BatchResult, KeyError, Keyed, MAX_KEYS_PER_REQUEST};
+pub use bootstrap::TokenHook;
+pub use browser_login::open_in_browser;
+pub use builder::ClientBuilder;
+pub use cancel::{Cancelled, CancellationToken};
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerPolicy, CircuitOpen, CircuitState};
+pub use compat::SeasonCompat;
+#[cfg(feature = "config")]
+pub use config::{CacheConfig, Config, CredentialsConfig, RateLimitConfig};
+pub use diff::{diff, diff_values, FieldChange};
+pub use draft::{DraftBoard, PickRecommendation};
+pub use eligibility::EligibilityCache;
+#[cfg(feature = "encryption")]
+pub use encrypted_store::{EncryptedFileTokenStore, TokenKey};
+pub use env::{EnvError, EnvProblem};
+pub use estimate::{CostEstimate, Workflow};
+pub use event_log::{CompactionReport, EventLog, LogEntry, LoggedEvent, RetentionPolicy};
+pub use form::{form_metrics, FormMetrics, FormThresholds};
+pub use http::{HttpError, TimeoutError};
+pub use integrity::{IntegrityChecker, IntegrityFlag, IntegrityReport, IntegrityThresholds, LineupSnapshot};
+pub use job::{Job, JobCheckpoint, JobStop};
+pub use lineup::{LineupCopy, Unplaceable, UnplaceableReason};
+pub use login::{AccessToken, InsufficientScope, ManualAuthFlow, RequestToken, Scope, TokenExpired, TokenRevoked};
+pub use pagination::{Page, Pager, QuotaPolicy};
+pub use middleware::{Middleware, Next};
+pub use player_ref::{PlayerCollection, PlayerRef};
+pub use player_stats::FreeAgentSearch;
+pub use polling::{GameTimeSchedule, LiveSignal, PollTarget, PollWatcher, PollingPlan, PollingPlanner};
+pub use progress::Progress;
+pub use projection_cache::{Persisted, ValueCache, ValueKind};
+pub use projections::{ProjectionContext, ProjectionProvider, ProjectionProviderExt};
+pub use quota_history::{JobPriority, QuotaHistory};
+#[cfg(feature = "handlebars")]
+pub use reports::HandlebarsEngine;
+pub use reports::{Placeholders, Report, ReportRenderer, TemplateEngine};
+pub use research::{Crawl, CrawlCheckpoint, CrawlReport, CrawlStop};
+pub use retro::{lineup_retro, optimal_lineup, LineupRetro, ManagerEfficiency};
+pub use retry::RetryPolicy;
+pub use roster_timeline::{RosterStint, RosterTimeline};
+pub use schedule::{ScheduleService, ScheduleSource, SeasonSchedule};
+pub use score_edits::{detect_score_edits, score_stats, ScoreEdit, StatCorrection};
+pub use scoreboard_diff::{diff_scoreboards, ScoreboardEvent};
+pub use season_sim::{SeasonPhase, SeasonSimulator, SimulatedWeek, SimulationConfig, SIMULATED_SCORING_TYPES, SIMULATED_SPORTS};
+pub use secret::Secret;
+pub use session::{FileTokenStore, MemoryTokenStore, SessionManager, StoredToken, TokenStatus, TokenStore, PROACTIVE_REFRESH_FRACTION};
+#[cfg(feature = "keyring")]
+pub use session::KeyringTokenStore;
+pub use settings_report::{SettingChange, SettingsReport};
+pub use site::{GameRecord, LeagueArchive};
+pub use standings_diff::{diff_standings, StandingsEvent, StandingsTracker};
+pub use task_group::TaskGroup;
+pub use tiebreakers::{compute_standings, verify_standings, ComputedStanding, StandingsMismatch, StandingsVerification};
+pub use time::{WeekInstants, YahooDateTime, YAHOO_TZ};
+pub use transport::{weighted_cost, BodyChunks, HttpResponse, HttpTransport, Method, NextChunk, PoolConfig, ProxyConfig, ReqwestTransport, Request, RequestOptions, StreamingResponse};
+pub use write_queue::{QueuedWrite, WriteQueue, WriteStatus};
+pub use xml::{InvalidXmlChar, RosterBuilder, RosterCoverage, TransactionBuilder, XmlBody};
+pub use yahoo_fantasy_models::points;
+pub use yahoo_fantasy_models::position;
+pub use yahoo_fantasy_models::{
+    AcquisitionLimitReached, AcquisitionUsage, CapKind, CapUsage, DailyStatLine, DraftPick, DraftPlayer,
+    DraftStatus, FreeAgent, Game, HeadToHeadMatrix, HeadToHeadRecord, League, LeagueSearchFilters,
+    LeagueSettings, LeagueStatus, LeagueVisibility, LimitPeriod, Matchup, MatchupTeam, PlayerGameLog,
+    PlayerPoints, PlayerStats, Points, Position, PublicLeagueSummary, Roster, RosterPlayer, RosterSlot,
+    Scoreboard, Standings, StatCoverage, StatGroup, StatModifier, TeamStanding, Tiebreaker, Transaction,
+    TransactionPlayer, TransactionType, WeekRange,
+};
+
+/// How often waits re-check for shutdown and cancellation
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Boxed future returned by object-safe async trait methods
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 
 /// Main SDK client
 #[derive(Debug)]
 pub struct YahooFantasyClient {
     consumer_key: String,
-    consumer_secret: String,
-    access_token: Option<String>,
-    access_token_secret: Option<String>,
+    consumer_secret: Secret,
+    /// Access token and its expiry; replaced when refreshed
+    token: Mutex<Option<StoredToken>>,
+    /// Held while the token is refreshed, so concurrent requests wait for one
+    /// refresh instead of each starting their own. A `SessionManager` shares
+    /// one between every client it hands out for the same user.
+    refresh: Arc<tokio::sync::Mutex<login::RefreshState>>,
+    /// Where refreshed tokens are saved, if anywhere
+    token_store: Option<session::StoreBinding>,
+    /// Also told about every refreshed token
+    refresh_hook: Option<TokenHook>,
     base_url: String,
+    user_agent: String,
+    default_headers: Vec<(String, String)>,
     rate_limiter: Arc<RateLimiter>,
     cache: Arc<Cache>,
+    eligibility: Arc<EligibilityCache>,
+    shutdown: CancellationToken,
+    in_flight: Arc<InFlightCount>,
+    transport: Arc<dyn HttpTransport>,
+    middleware: Vec<Arc<dyn Middleware>>,
+    request_timeout: Duration,
+    retry: RetryPolicy,
+    /// Scope requested by `get_request_token`; `None` leaves it to the app's registration
+    scope: Option<Scope>,
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Charge requests by `transport::weighted_cost` instead of one token each
+    weighted_costs: bool,
 }
 
 impl YahooFantasyClient {
     /// Create a new Yahoo Fantasy client
-    pub fn new(consumer_key: String, consumer_secret: String) -> Self {
-        Self {
-            consumer_key,
-            consumer_secret,
-            access_token: None,
-            access_token_secret: None,
-            base_url: "https://fantasysports.yahooapis.com/fantasy/v2".to_string(),
-            rate_limiter: Arc::new(RateLimiter::new()),
-            cache: Arc::new(Cache::new()),
-        }
+    pub fn new(consumer_key: String, consumer_secret: String) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        ClientBuilder::new(consumer_key, consumer_secret).build()
     }
 
-    /// Set OAuth access tokens
+    /// Start configuring a client
+    pub fn builder(consumer_key: String, consumer_secret: String) -> ClientBuilder {
+        ClientBuilder::new(consumer_key, consumer_secret)
+    }
+
+    /// Set OAuth access tokens. Without a session handle and expiry they can't
+    /// be refreshed; use `set_access_token` for that.
     pub fn set_tokens(&mut self, access_token: String, access_token_secret: String) {
-        self.access_token = Some(access_token);
-        self.access_token_secret = Some(access_token_secret);
+        let token = AccessToken {
+            token: access_token.into(),
+            secret: access_token_secret.into(),
+            session_handle: None,
+            expires_in: None,
+        };
+        *self.token.get_mut().unwrap() = Some(StoredToken { token, expires_at: None });
+    }
+
+    /// Set tokens just returned by `get_access_token`. They are refreshed
+    /// automatically shortly before they expire, or when Yahoo reports them expired.
+    pub fn set_access_token(&mut self, token: AccessToken) {
+        *self.token.get_mut().unwrap() = Some(StoredToken::new(token, chrono::Utc::now()));
+    }
+
+    /// Current access token, including any refresh since it was set; save it to resume later
+    pub fn access_token(&self) -> Option<AccessToken> {
+        self.token.lock().unwrap().as_ref().map(|stored| stored.token.clone())
     }
 
     /// Check if client is authenticated
     pub fn is_authenticated(&self) -> bool {
-        self.access_token.is_some() && self.access_token_secret.is_some()
+        self.token.lock().unwrap().is_some()
     }
 
-    /// Get all available games (mock implementation for testing)
-    pub fn get_games(&self) -> Result<Vec<Game>, Box<dyn std::error::Error + Send + Sync>> {
-        self.rate_limiter.wait_for_request();
-        
-        let games = vec![
-            Game {
-                game_key: "nfl.2024".to_string(),
-                name: "NFL Football".to_string(),
-                code: "nfl".to_string(),
-                season: 2024,
-            },
-            Game {
-                game_key: "nba.2024".to_string(),
-                name: "NBA Basketball".to_string(),
-                code: "nba".to_string(),
-                season: 2024,
-            },
-            Game {
-                game_key: "mlb.2024".to_string(),
-                name: "MLB Baseball".to_string(),
-                code: "mlb".to_string(),
-                season: 2024,
-            },
-        ];
-
-        self.rate_limiter.record_request();
-        Ok(games)
-    }
-
-    /// Get user's leagues for a specific game (mock implementation)
-    pub fn get_leagues(&self, game_key: &str) -> Result<Vec<League>, Box<dyn std::error::Error + Send + Sync>> {
-        self.rate_limiter.wait_for_request();
-        
-        let _ = game_key;
-        let leagues = vec![
-            League {
-                league_key: "423.l.12345".to_string(),
-                name: "My Test League".to_string(),
-                num_teams: 12,
-                current_week: 15,
+    /// Expiry and refreshability of the current access token; `None` when the client has none
+    pub fn token_status(&self) -> Option<TokenStatus> {
+        self.token.lock().unwrap().as_ref().map(|stored| stored.status(&chrono::Utc::now()))
+    }
+
+    /// When the current access token expires; `None` without a token or when Yahoo didn't say
+    pub fn token_expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.token.lock().unwrap().as_ref().and_then(|stored| stored.expires_at)
+    }
+
+    /// Get the consumer key this client was created with
+    pub fn consumer_key(&self) -> &str {
+        &self.consumer_key
+    }
+
+    /// Get the API base URL
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Build a request for a resource path relative to the base URL, with the default headers and User-Agent
+    pub fn request(&self, method: Method, path: &str) -> Request {
+        self.request_at(&self.base_url, method, path)
+    }
+
+    /// Like `request`, but relative to `base_url` instead of the client's
+    pub fn request_at(&self, base_url: &str, method: Method, path: &str) -> Request {
+        let url = format!("{}/{}", base_url.trim_end_matches('/'), path.trim_start_matches('/'));
+        let request = self
+            .default_headers
+            .iter()
+            .fold(Request::new(method, url), |request, (name, value)| request.header(name, value))
+            .header("User-Agent", &self.user_agent);
+        if self.weighted_costs {
+            let cost = transport::weighted_cost(request.url());
+            request.with_cost(cost)
+        } else {
+            request
+        }
+    }
+
+    /// User-Agent sent with every request
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Headers sent with every request, besides the User-Agent
+    pub fn default_headers(&self) -> &[(String, String)] {
+        &self.default_headers
+    }
+
+    /// Stop accepting requests and wait up to `timeout` for in-flight ones to finish.
+    /// Requests still waiting on the rate limiter fail with `Cancelled`.
+    /// Returns `true` if everything drained before the timeout.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.shutdown.cancel();
+
+        tokio::time::timeout(timeout, self.in_flight.drained()).await.is_ok()
+    }
+
+    /// Check if `shutdown` has been called
+    pub fn is_shut_down(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Number of requests currently being sent
+    pub fn in_flight_requests(&self) -> usize {
+        self.in_flight.count.load(Ordering::SeqCst)
+    }
+
+    /// Wait for rate limit capacity and register an in-flight request
+    async fn begin_request(&self, cancel: Option<&CancellationToken>) -> Result<InFlight, Cancelled> {
+        self.begin_weighted_request(1.0, cancel).await
+    }
+
+    /// Wait until `cost` tokens can be reserved and register the request as in
+    /// flight. The tokens are refunded unless `InFlight::sent` is called.
+    async fn begin_weighted_request(&self, cost: f64, cancel: Option<&CancellationToken>) -> Result<InFlight, Cancelled> {
+        loop {
+            self.shutdown.check()?;
+            cancel.map_or(Ok(()), |c| c.check())?;
+            if self.rate_limiter.try_acquire(cost) {
+                break;
             }
-        ];
+            tokio::time::sleep(self.rate_limiter.time_until_affordable(cost).min(POLL_INTERVAL)).await;
+        }
+
+        self.in_flight.count.fetch_add(1, Ordering::SeqCst);
+        Ok(InFlight {
+            count: self.in_flight.clone(),
+            limiter: self.rate_limiter.clone(),
+            cost,
+            sent: false,
+        })
+    }
+
+    /// Get the client's rate limiter
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Whether requests are flowing or failing fast after repeated upstream failures
+    pub fn circuit_state(&self) -> CircuitState {
+        self.circuit_breaker.state()
+    }
+
+    /// Close the circuit breaker, e.g. after confirming Yahoo is back
+    pub fn reset_circuit(&self) {
+        self.circuit_breaker.reset();
+    }
+
+    /// Position eligibility seen in fetched rosters
+    pub fn eligibility(&self) -> &EligibilityCache {
+        &self.eligibility
+    }
+
+    /// Get the client's response cache
+    pub fn cache(&self) -> &Cache {
+        &self.cache
+    }
 
-        self.rate_limiter.record_request();
+    /// Get all games currently available on Yahoo
+    pub async fn get_games(&self) -> Result<Vec<Game>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json("games;is_available=1").await?;
+        parse_games(&body)
+    }
+
+    /// Get specific games by key, batched into as many requests as needed
+    pub async fn get_games_by_keys(&self, game_keys: &[String]) -> BatchResult<Game> {
+        self.fetch_many(game_keys, |chunk| async move {
+            let body = self.get_json(&format!("games;{}", batch::key_selector("game_keys", &chunk))).await?;
+            parse_games(&body)
+        })
+        .await
+    }
+
+    /// Fetch resources for many keys, splitting them into request-sized chunks.
+    /// Chunks are fetched sequentially; `fetch` should send its request through
+    /// `execute` so it is rate limited, cached, and retried. When a chunk fails,
+    /// its keys are retried one at a time so a single bad key only fails itself.
+    pub async fn fetch_many<T, F, Fut>(&self, keys: &[String], fetch: F) -> BatchResult<T>
+    where
+        T: Keyed,
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        self.fetch_many_cancellable(keys, &CancellationToken::new(), fetch).await
+    }
+
+    /// Like `fetch_many`, but stops sending chunks once `cancel` is triggered,
+    /// abandoning the chunk in flight. Keys that were never requested are
+    /// reported as failed with `Cancelled` and consume no rate limit tokens.
+    pub async fn fetch_many_cancellable<T, F, Fut>(&self, keys: &[String], cancel: &CancellationToken, mut fetch: F) -> BatchResult<T>
+    where
+        T: Keyed,
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        let mut result = BatchResult::default();
+
+        for chunk in batch::chunk_keys(keys) {
+            if cancel.is_cancelled() || self.shutdown.is_cancelled() {
+                result.failed.extend(chunk.iter().map(|key| KeyError {
+                    key: key.clone(),
+                    error: Box::new(Cancelled),
+                }));
+                continue;
+            }
+
+            match self.fetch_chunk(chunk, cancel, &mut fetch).await {
+                Ok(items) => result.collect_chunk(chunk, items),
+                Err(error) if chunk.len() == 1 => result.failed.push(KeyError {
+                    key: chunk[0].clone(),
+                    error,
+                }),
+                Err(_) => {
+                    for key in chunk {
+                        let single = std::slice::from_ref(key);
+                        match self.fetch_chunk(single, cancel, &mut fetch).await {
+                            Ok(items) => result.collect_chunk(single, items),
+                            Err(error) => result.failed.push(KeyError {
+                                key: key.clone(),
+                                error,
+                            }),
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    async fn fetch_chunk<T, F, Fut>(&self, chunk: &[String], cancel: &CancellationToken, fetch: &mut F) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: FnMut(Vec<String>) -> Fut,
+        Fut: Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        cancel.check()?;
+        tokio::select! {
+            fetched = fetch(chunk.to_vec()) => fetched,
+            _ = cancel.cancelled() => Err(Box::new(Cancelled)),
+        }
+    }
+
+    /// Get the logged-in user's leagues for a specific game
+    pub async fn get_leagues(&self, game_key: &str) -> Result<Vec<League>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("users;use_login=1/games;game_keys={}/leagues", game_key)).await?;
+        let content = response::content(&body)?;
+
+        let mut leagues = Vec::new();
+        for user in response::collection(&content["users"], "user") {
+            let Some(games) = user.get("games") else {
+                continue;
+            };
+            for game in response::collection(games, "game") {
+                let Some(game_leagues) = game.get("leagues") else {
+                    continue;
+                };
+                for league in response::collection(game_leagues, "league") {
+                    let compat = response::season(&league).or(response::season(&game)).map_or_else(SeasonCompat::unknown, SeasonCompat::for_season);
+                    leagues.push(compat.parse_league(serde_json::Value::Object(league))?);
+                }
+            }
+        }
         Ok(leagues)
     }
+
+    /// Get the fantasy week date ranges for a game
+    pub async fn get_game_weeks(&self, game_key: &str) -> Result<Vec<WeekRange>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("game/{}/game_weeks", game_key)).await?;
+        parse_game_weeks(&body)
+    }
+
+    /// Get the join and draft state of a league for the logged-in user
+    pub async fn get_league_status(&self, league_key: &str) -> Result<LeagueStatus, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("league/{};out=settings,teams", league_key)).await?;
+        parse_league_status(&body)
+    }
+
+    /// Browse public leagues for a game, e.g. `nfl`
+    pub async fn search_public_leagues(&self, game_code: &str, filters: &LeagueSearchFilters) -> Result<Vec<PublicLeagueSummary>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("game/{}/leagues;league_type=public;out=settings", game_code)).await?;
+        let leagues = parse_public_leagues(&body)?;
+        Ok(leagues.into_iter().filter(|l| filters.matches(l)).collect())
+    }
+
+    /// Get a league's scoreboard for a week, with the points of each team's starters
+    pub async fn get_scoreboard(&self, league_key: &str, week: i32) -> Result<Scoreboard, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("league/{}/scoreboard;week={}/matchups/teams/roster;week={}/players/stats;type=week;week={}", league_key, week, week, week)).await?;
+        parse_scoreboard(&body)
+    }
+
+    /// Get a league's standings
+    pub async fn get_standings(&self, league_key: &str) -> Result<Standings, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("league/{}/standings", league_key)).await?;
+        parse_standings(&body)
+    }
+
+    /// Get a league's scoring, roster, waiver, and playoff settings
+    pub async fn get_league_settings(&self, league_key: &str) -> Result<LeagueSettings, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("league/{}/settings", league_key)).await?;
+        parse_league_settings(&body)
+    }
+
+    /// Get a league's transaction log, newest first
+    pub async fn get_transactions(&self, league_key: &str) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("league/{}/transactions", league_key)).await?;
+        parse_transactions(&body)
+    }
+}
+
+/// Decrements the client's in-flight count when the request completes
+/// Requests being sent by a client and the clients derived from it
+#[derive(Debug, Default)]
+pub(crate) struct InFlightCount {
+    count: AtomicUsize,
+    /// Woken whenever the count drops to zero
+    idle: Notify,
+}
+
+impl InFlightCount {
+    /// Resolve once no requests are in flight
+    async fn drained(&self) {
+        loop {
+            let idle = self.idle.notified();
+            tokio::pin!(idle);
+            // Register before checking the count so the last request finishing in between still wakes us
+            idle.as_mut().enable();
+            if self.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+struct InFlight {
+    count: Arc<InFlightCount>,
+    limiter: Arc<RateLimiter>,
+    /// Tokens reserved for the request
+    cost: f64,
+    sent: bool,
+}
+
+impl InFlight {
+    /// The request reached the server, so its tokens stay spent
+    fn sent(&mut self) {
+        self.sent = true;
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.count.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.count.idle.notify_waiters();
+        }
+        // Dropped before sending, e.g. cancelled: the request cost nothing
+        if !self.sent {
+            self.limiter.refund(self.cost);
+        }
+    }
+}
+
+/// Games from a `games` collection response
+fn parse_games(body: &serde_json::Value) -> Result<Vec<Game>, Box<dyn std::error::Error + Send + Sync>> {
+    response::collection(&response::content(body)?["games"], "game")
+        .into_iter()
+        .map(|game| SeasonCompat::for_resource(&game).parse_game(serde_json::Value::Object(game)))
+        .collect()
+}
+
+/// Weeks from a `game/{key}/game_weeks` response, in week order
+fn parse_game_weeks(body: &serde_json::Value) -> Result<Vec<WeekRange>, Box<dyn std::error::Error + Send + Sync>> {
+    let game = response::resource(&response::content(body)?["game"]);
+    let mut weeks = response::collection(response::member(&game, "game_weeks"), "game_week")
+        .into_iter()
+        .map(|week| {
+            Ok(WeekRange {
+                week: response::require(&week, "week", "game week")?,
+                start: time::parse_date(&response::require::<String>(&week, "start", "game week")?)?,
+                end: time::parse_date(&response::require::<String>(&week, "end", "game week")?)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>()?;
+    weeks.sort_by_key(|week| week.week);
+    Ok(weeks)
+}
+
+/// Transactions from a `league/{key}/transactions` response, newest first
+fn parse_transactions(body: &serde_json::Value) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>> {
+    let league = response::resource(&response::content(body)?["league"]);
+    let mut transactions = response::collection(response::member(&league, "transactions"), "transaction")
+        .into_iter()
+        .map(|transaction| {
+            let kind = transaction.get("type").ok_or("transaction is missing type")?;
+            let kind: TransactionType = serde_json::from_value(kind.clone()).map_err(|e| format!("unknown transaction type {}: {}", kind, e))?;
+            let players = response::collection(response::member(&transaction, "players"), "player").iter().map(parse_transaction_player).collect::<Result<_, _>>()?;
+            Ok(Transaction {
+                transaction_key: response::require(&transaction, "transaction_key", "transaction")?,
+                by_commissioner: kind == TransactionType::Commish,
+                kind,
+                status: response::require(&transaction, "status", "transaction")?,
+                timestamp: response::require(&transaction, "timestamp", "transaction")?,
+                players,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>()?;
+    transactions.sort_by_key(|t| std::cmp::Reverse(t.timestamp));
+    Ok(transactions)
+}
+
+/// A player's move; Yahoo sends `transaction_data` as an object for a single
+/// move and an array otherwise, which `resource` flattens alike
+fn parse_transaction_player(player: &serde_json::Map<String, serde_json::Value>) -> Result<TransactionPlayer, Box<dyn std::error::Error + Send + Sync>> {
+    let moved = response::resource(player.get("transaction_data").ok_or("transaction player is missing transaction_data")?);
+    Ok(TransactionPlayer {
+        player_key: response::require(player, "player_key", "transaction player")?,
+        name: player.get("name").and_then(|name| response::scalar(&name["full"])).ok_or("transaction player is missing name")?,
+        source_team_key: response::field(&moved, "source_team_key"),
+        destination_team_key: response::field(&moved, "destination_team_key"),
+    })
+}
+
+/// FAAB budget Yahoo gives each team unless the commissioner changes it
+const DEFAULT_FAAB_BUDGET: u32 = 100;
+
+/// Settings from a `league/{key}/settings` response
+fn parse_league_settings(body: &serde_json::Value) -> Result<LeagueSettings, Box<dyn std::error::Error + Send + Sync>> {
+    let league = response::resource(&response::content(body)?["league"]);
+    let settings = response::resource(league.get("settings").ok_or("league is missing its settings")?);
+    let number = |name: &str| response::field::<u32>(&settings, name);
+
+    let names: HashMap<u32, (String, String)> = stat_entries(&settings, "stat_categories")
+        .into_iter()
+        .filter_map(|stat| Some((response::field(&stat, "stat_id")?, (response::field(&stat, "name")?, response::field(&stat, "display_name")?))))
+        .collect();
+    let stat_modifiers = stat_entries(&settings, "stat_modifiers")
+        .into_iter()
+        .map(|stat| {
+            let stat_id = response::require(&stat, "stat_id", "stat modifier")?;
+            let (name, display_name) = names.get(&stat_id).cloned().ok_or_else(|| format!("stat modifier {} has no stat category", stat_id))?;
+            Ok(StatModifier {
+                stat_id,
+                name,
+                display_name,
+                value: response::require(&stat, "value", "stat modifier")?,
+            })
+        })
+        .collect::<Result<_, Box<dyn std::error::Error + Send + Sync>>>()?;
+
+    let positions: Vec<_> = settings
+        .get("roster_positions")
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("roster_position").map(response::resource))
+        .collect();
+    let roster_slots = parse_roster_slots(&positions)?;
+
+    Ok(LeagueSettings {
+        league_key: response::require(&league, "league_key", "league")?,
+        scoring_type: response::field(&league, "scoring_type").or_else(|| response::field(&settings, "scoring_type")).ok_or("league is missing scoring_type")?,
+        draft_type: response::require(&settings, "draft_type", "league settings")?,
+        stat_modifiers,
+        roster_slots,
+        waiver_type: response::field(&settings, "waiver_type").unwrap_or_default(),
+        waiver_days: number("waiver_time").unwrap_or_default(),
+        faab_budget: response::flag(&settings, "uses_faab").unwrap_or(false).then(|| number("faab_budget").unwrap_or(DEFAULT_FAAB_BUDGET)),
+        trade_end_date: response::field(&settings, "trade_end_date"),
+        playoff_start_week: number("playoff_start_week").filter(|_| response::flag(&settings, "uses_playoff") != Some(false)),
+        num_playoff_teams: number("num_playoff_teams").filter(|_| response::flag(&settings, "uses_playoff") != Some(false)),
+        has_playoff_consolation_games: response::flag(&settings, "has_playoff_consolation_games").unwrap_or(false),
+        uses_playoff_reseeding: response::flag(&settings, "uses_playoff_reseeding").unwrap_or(false),
+        // Not part of Yahoo's settings resource
+        tiebreakers: Vec::new(),
+        // Blank when the league sets no cap
+        max_innings_pitched: number("max_innings_pitched"),
+        max_games_played: number("max_games_played"),
+        max_weekly_adds: number("max_weekly_adds"),
+        max_season_adds: number("max_adds"),
+    })
+}
+
+/// The `stat` entries of a settings block like `{"stats": [{"stat": {...}}, ...]}`
+fn stat_entries(settings: &serde_json::Map<String, serde_json::Value>, block: &str) -> Vec<serde_json::Map<String, serde_json::Value>> {
+    response::member(settings, block)["stats"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("stat").map(response::resource))
+        .collect()
+}
+
+/// Roster slots from `roster_positions`. Yahoo only names each slot, so the
+/// positions a flex accepts come from its code, e.g. "W/R/T", and bench and
+/// injury slots take any position a starting slot does.
+fn parse_roster_slots(positions: &[serde_json::Map<String, serde_json::Value>]) -> Result<Vec<RosterSlot>, Box<dyn std::error::Error + Send + Sync>> {
+    const RESERVE: &[&str] = &["BN", "IR", "IL", "IL+", "NA"];
+    const ANY: &[&str] = &["BN", "IR", "IL", "IL+", "NA", "Util"];
+    let mut slots = positions
+        .iter()
+        .map(|slot| {
+            let position: String = response::require(slot, "position", "roster position")?;
+            Ok(RosterSlot {
+                is_starting: response::flag(slot, "is_starting_position").unwrap_or(!RESERVE.contains(&position.as_str())),
+                accepts: flex_positions(&position),
+                count: response::field(slot, "count").unwrap_or(1),
+                position,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>()?;
+
+    let mut starting: Vec<String> = Vec::new();
+    for accepted in slots.iter().filter(|s| !ANY.contains(&s.position.as_str())).flat_map(|s| &s.accepts) {
+        if !starting.contains(accepted) {
+            starting.push(accepted.clone());
+        }
+    }
+    for slot in slots.iter_mut().filter(|s| ANY.contains(&s.position.as_str())) {
+        slot.accepts = starting.clone();
+    }
+    Ok(slots)
+}
+
+/// Positions a slot code takes: the letters of an NFL flex like "Q/W/R/T",
+/// the members of other sports' combined slots, or just the position itself
+fn flex_positions(slot: &str) -> Vec<String> {
+    let members: &[&str] = match slot {
+        "OP" => &["QB", "WR", "RB", "TE"],
+        "G" => &["PG", "SG"],
+        "F" => &["SF", "PF"],
+        "CI" => &["1B", "3B"],
+        "MI" => &["2B", "SS"],
+        "P" => &["SP", "RP"],
+        _ if slot.contains('/') => {
+            let letters: Option<Vec<String>> = slot
+                .split('/')
+                .map(|letter| match letter {
+                    "Q" => Some("QB".to_string()),
+                    "W" => Some("WR".to_string()),
+                    "R" => Some("RB".to_string()),
+                    "T" => Some("TE".to_string()),
+                    _ => None,
+                })
+                .collect();
+            return letters.unwrap_or_else(|| vec![slot.to_string()]);
+        }
+        _ => return vec![slot.to_string()],
+    };
+    members.iter().map(|p| p.to_string()).collect()
+}
+
+/// Status from a `league/{key};out=settings,teams` response
+fn parse_league_status(body: &serde_json::Value) -> Result<LeagueStatus, Box<dyn std::error::Error + Send + Sync>> {
+    let league = response::resource(&response::content(body)?["league"]);
+    let settings = league.get("settings").map(response::resource).unwrap_or_default();
+    let my_team = response::collection(response::member(&league, "teams"), "team")
+        .into_iter()
+        .find(|team| response::field::<u8>(team, "is_owned_by_current_login") == Some(1));
+    Ok(LeagueStatus {
+        league_key: response::require(&league, "league_key", "league")?,
+        visibility: league_enum(&league, &settings, "league_type")?,
+        draft_status: league_enum(&league, &settings, "draft_status")?,
+        my_team_key: my_team.as_ref().map(|team| response::require(team, "team_key", "team")).transpose()?,
+        // Yahoo creates the team when the user joins and leaves it unnamed until they set it up
+        team_pending_setup: my_team.is_some_and(|team| response::field::<String>(&team, "name").is_none_or(|name| name.is_empty())),
+    })
+}
+
+/// Leagues from a game's `leagues;league_type=public;out=settings` response
+fn parse_public_leagues(body: &serde_json::Value) -> Result<Vec<PublicLeagueSummary>, Box<dyn std::error::Error + Send + Sync>> {
+    let game = response::resource(&response::content(body)?["game"]);
+    response::collection(response::member(&game, "leagues"), "league")
+        .into_iter()
+        .map(|league| {
+            let settings = league.get("settings").map(response::resource).unwrap_or_default();
+            let num_teams: i32 = response::require(&league, "num_teams", "league")?;
+            let max_teams = response::field(&settings, "max_teams").unwrap_or(num_teams);
+            Ok(PublicLeagueSummary {
+                league_key: response::require(&league, "league_key", "league")?,
+                name: response::require(&league, "name", "league")?,
+                scoring_type: response::require(&league, "scoring_type", "league")?,
+                draft_type: response::require(&settings, "draft_type", "league settings")?,
+                num_teams,
+                open_slots: (max_teams - num_teams).max(0),
+            })
+        })
+        .collect()
+}
+
+/// A lowercase enum field of the league or, failing that, its settings
+fn league_enum<T: serde::de::DeserializeOwned>(league: &serde_json::Map<String, serde_json::Value>, settings: &serde_json::Map<String, serde_json::Value>, name: &str) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+    let value = league.get(name).or_else(|| settings.get(name)).ok_or_else(|| format!("league is missing {}", name))?;
+    serde_json::from_value(value.clone()).map_err(|e| format!("league has an unknown {} {}: {}", name, value, e).into())
+}
+
+/// Scoreboard from a `league/{key}/scoreboard` response
+fn parse_scoreboard(body: &serde_json::Value) -> Result<Scoreboard, Box<dyn std::error::Error + Send + Sync>> {
+    let league = response::resource(&response::content(body)?["league"]);
+    let scoreboard = league.get("scoreboard").ok_or("league is missing its scoreboard")?;
+    let matchups = response::collection(&scoreboard["0"]["matchups"], "matchup")
+        .into_iter()
+        .map(|matchup| {
+            let teams = response::collection(&response::member(&matchup, "0")["teams"], "team").iter().map(parse_matchup_team).collect::<Result<_, _>>()?;
+            Ok(Matchup { teams })
+        })
+        .collect::<Result<_, Box<dyn std::error::Error + Send + Sync>>>()?;
+    Ok(Scoreboard {
+        league_key: response::require(&league, "league_key", "league")?,
+        week: scoreboard.get("week").and_then(response::scalar).or_else(|| response::field(&league, "current_week")).ok_or("scoreboard is missing week")?,
+        matchups,
+    })
 }
 
-/// Game data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Game {
-    pub game_key: String,
-    pub name: String,
-    pub code: String,
-    pub season: i32,
+fn parse_matchup_team(team: &serde_json::Map<String, serde_json::Value>) -> Result<MatchupTeam, Box<dyn std::error::Error + Send + Sync>> {
+    let total = |name: &str| team.get(name).and_then(|points| response::scalar::<Points>(&points["total"]));
+    Ok(MatchupTeam {
+        team_key: response::require(team, "team_key", "team")?,
+        name: response::require(team, "name", "team")?,
+        points: total("team_points").ok_or("team is missing team_points")?,
+        projected_points: total("team_projected_points").unwrap_or_default(),
+        players: parse_starter_points(team)?,
+    })
 }
 
-/// League data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct League {
-    pub league_key: String,
-    pub name: String,
-    pub num_teams: i32,
-    pub current_week: i32,
+/// Points of the players in a matchup team's starting slots; empty when the roster wasn't requested
+fn parse_starter_points(team: &serde_json::Map<String, serde_json::Value>) -> Result<Vec<PlayerPoints>, Box<dyn std::error::Error + Send + Sync>> {
+    let roster = response::resource(response::member(team, "roster"));
+    response::collection(&response::member(&roster, "0")["players"], "player")
+        .iter()
+        .filter(|player| {
+            let position: Option<String> = response::field(&response::resource(response::member(player, "selected_position")), "position");
+            position.is_some_and(|position| lineup::is_starting(&position))
+        })
+        .map(|player| {
+            Ok(PlayerPoints {
+                player_key: response::require(player, "player_key", "player")?,
+                points: response::field(&response::resource(response::member(player, "player_points")), "total").unwrap_or(Points::ZERO),
+            })
+        })
+        .collect()
 }
 
+/// Standings from a `league/{key}/standings` response, in rank order
+fn parse_standings(body: &serde_json::Value) -> Result<Standings, Box<dyn std::error::Error + Send + Sync>> {
+    let league = response::resource(&response::content(body)?["league"]);
+    let standings = response::resource(league.get("standings").ok_or("league is missing its standings")?);
+    let mut teams = response::collection(response::member(&standings, "teams"), "team").iter().map(parse_team_standing).collect::<Result<Vec<_>, _>>()?;
+    teams.sort_by_key(|team| team.rank);
+    Ok(Standings {
+        league_key: response::require(&league, "league_key", "league")?,
+        week: response::require(&league, "current_week", "league")?,
+        teams,
+    })
+}
+
+fn parse_team_standing(team: &serde_json::Map<String, serde_json::Value>) -> Result<TeamStanding, Box<dyn std::error::Error + Send + Sync>> {
+    let standing = team.get("team_standings").and_then(|s| s.as_object()).ok_or("team is missing team_standings")?;
+    let outcomes = standing.get("outcome_totals").and_then(|o| o.as_object()).ok_or("team standings are missing outcome_totals")?;
+    let flag = |name| response::field::<u8>(team, name).map(|flag| flag != 0);
+    Ok(TeamStanding {
+        team_key: response::require(team, "team_key", "team")?,
+        name: response::require(team, "name", "team")?,
+        // Unranked before the first week is scored
+        rank: response::field(standing, "rank").unwrap_or_default(),
+        wins: response::require(outcomes, "wins", "team standings")?,
+        losses: response::require(outcomes, "losses", "team standings")?,
+        ties: response::field(outcomes, "ties").unwrap_or_default(),
+        points_for: response::field(standing, "points_for").unwrap_or_default(),
+        waiver_priority: response::field(team, "waiver_priority"),
+        clinched_playoffs: flag("clinched_playoffs"),
+        eliminated: flag("eliminated"),
+    })
+}
+
+impl Keyed for Game {
+    fn key(&self) -> &str {
+        &self.game_key
+    }
+
+    /// A game code, e.g. `nfl`, stands for the current season's game
+    fn answers(&self, key: &str) -> bool {
+        self.game_key == key || self.code == key
+    }
+}
+
+impl Keyed for League {
+    fn key(&self) -> &str {
+        &self.league_key
+    }
+
+    /// League keys may name their game by code, e.g. `nfl.l.12345`
+    fn answers(&self, key: &str) -> bool {
+        batch::same_resource(&self.league_key, key, ".l.")
+    }
+}
+
+/// Requests a `RateLimiter` allows in a burst by default
+pub const DEFAULT_MAX_TOKENS: f64 = 100.0;
+
+/// Tokens a `RateLimiter` regains per second by default, about 3000 requests an hour
+pub const DEFAULT_REFILL_RATE: f64 = 0.83;
+
 /// Rate limiter using token bucket algorithm
 #[derive(Debug)]
 pub struct RateLimiter {
@@ -122,40 +904,126 @@ struct RateLimiterState {
     refill_rate: f64,
     last_refill: Instant,
     requests_count: u64,
+    /// Set when the server asked us to back off
+    paused_until: Option<Instant>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RateLimiter {
     pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_TOKENS, DEFAULT_REFILL_RATE)
+    }
+
+    /// Bucket of `max_tokens`, starting full and refilling `refill_rate` tokens per second
+    pub fn with_limits(max_tokens: f64, refill_rate: f64) -> Self {
         Self {
             state: Mutex::new(RateLimiterState {
-                tokens: 100.0,
-                max_tokens: 100.0,
-                refill_rate: 0.83,
+                tokens: max_tokens,
+                max_tokens,
+                refill_rate,
                 last_refill: Instant::now(),
                 requests_count: 0,
+                paused_until: None,
             }),
         }
     }
 
-    pub fn wait_for_request(&self) {
+    /// Wait for a request to be available without blocking the executor
+    pub async fn wait_for_request(&self) {
+        while !self.can_make_request() {
+            tokio::time::sleep(self.time_until_available()).await;
+        }
+    }
+
+    /// Wait for a request to be available, giving up if `cancel` is triggered.
+    /// Cancelling while waiting consumes no tokens.
+    pub async fn wait_for_request_cancellable(&self, cancel: &CancellationToken) -> Result<(), Cancelled> {
         loop {
+            cancel.check()?;
             if self.can_make_request() {
-                break;
+                return Ok(());
             }
-            std::thread::sleep(Duration::from_millis(10));
+            tokio::time::sleep(self.time_until_available().min(POLL_INTERVAL)).await;
         }
     }
 
+    /// How long until a token is available; zero if one is available now
+    pub fn time_until_available(&self) -> Duration {
+        self.time_until_affordable(1.0)
+    }
+
+    /// How long until a request costing `cost` tokens can be sent. Costs above
+    /// the bucket size are capped to it so they can always eventually run.
+    pub fn time_until_affordable(&self, cost: f64) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        self.refill_tokens(&mut state);
+        let cost = cost.min(state.max_tokens);
+        let paused = state
+            .paused_until
+            .map_or(Duration::ZERO, |until| until.saturating_duration_since(Instant::now()));
+        if state.tokens >= cost || state.refill_rate <= 0.0 {
+            return paused;
+        }
+        let refill = Duration::try_from_secs_f64((cost - state.tokens) / state.refill_rate).unwrap_or(Duration::MAX);
+        paused.max(refill)
+    }
+
     pub fn can_make_request(&self) -> bool {
+        self.can_afford(1.0)
+    }
+
+    /// Whether a request costing `cost` tokens can be sent now
+    pub fn can_afford(&self, cost: f64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.refill_tokens(&mut state);
+        state.tokens >= cost.min(state.max_tokens) && state.paused_until.is_none_or(|until| Instant::now() >= until)
+    }
+
+    /// Hold every request for `delay`, e.g. when the server sends `Retry-After`.
+    /// A shorter pause never cuts an existing one short.
+    pub fn pause_for(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        let mut state = self.state.lock().unwrap();
+        state.paused_until = Some(state.paused_until.map_or(until, |current| current.max(until)));
+    }
+
+    /// Take `cost` tokens if a request costing that much can be sent now. The
+    /// check and the spend happen under one lock, so concurrent callers can't
+    /// both claim the last tokens.
+    pub fn try_acquire(&self, cost: f64) -> bool {
         let mut state = self.state.lock().unwrap();
         self.refill_tokens(&mut state);
-        state.tokens >= 1.0
+        let cost = cost.min(state.max_tokens);
+        if state.tokens < cost || state.paused_until.is_some_and(|until| Instant::now() < until) {
+            return false;
+        }
+        state.tokens -= cost;
+        state.requests_count += 1;
+        true
+    }
+
+    /// Give back tokens from `try_acquire` for a request that was never sent
+    pub fn refund(&self, cost: f64) {
+        let mut state = self.state.lock().unwrap();
+        state.tokens = (state.tokens + cost.min(state.max_tokens)).min(state.max_tokens);
+        state.requests_count = state.requests_count.saturating_sub(1);
     }
 
     pub fn record_request(&self) {
+        self.record_cost(1.0);
+    }
+
+    /// Record a request that consumed `cost` tokens
+    pub fn record_cost(&self, cost: f64) {
         let mut state = self.state.lock().unwrap();
-        if state.tokens >= 1.0 {
-            state.tokens -= 1.0;
+        let cost = cost.min(state.max_tokens);
+        if state.tokens >= cost {
+            state.tokens -= cost;
             state.requests_count += 1;
         }
     }
@@ -166,6 +1034,16 @@ impl RateLimiter {
         state.tokens
     }
 
+    /// Bucket size (maximum burst)
+    pub fn max_tokens(&self) -> f64 {
+        self.state.lock().unwrap().max_tokens
+    }
+
+    /// Tokens added per second
+    pub fn refill_rate(&self) -> f64 {
+        self.state.lock().unwrap().refill_rate
+    }
+
     fn refill_tokens(&self, state: &mut RateLimiterState) {
         let now = Instant::now();
         let time_passed = now.duration_since(state.last_refill).as_secs_f64();
@@ -176,11 +1054,19 @@ impl RateLimiter {
     }
 }
 
+/// Responses a `Cache` holds by default before evicting
+pub const DEFAULT_CACHE_SIZE: usize = 1000;
+
+/// How long a `Cache` keeps responses by default
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(300);
+
 /// Simple in-memory cache with TTL
 #[derive(Debug)]
 pub struct Cache {
     entries: Mutex<HashMap<String, CacheEntry>>,
     max_size: usize,
+    /// Lifetime of entries stored without one
+    ttl: Duration,
 }
 
 #[derive(Debug, Clone)]
@@ -188,6 +1074,39 @@ struct CacheEntry {
     data: String,
     timestamp: SystemTime,
     ttl: Duration,
+    validators: Validators,
+}
+
+/// `ETag` and `Last-Modified` of a cached response, sent back as
+/// `If-None-Match` and `If-Modified-Since` to revalidate it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn from_response(response: &HttpResponse) -> Self {
+        Self {
+            etag: response.header("ETag").map(str::to_string),
+            last_modified: response.header("Last-Modified").map(str::to_string),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Make `request` conditional on the resource having changed
+    pub fn apply(&self, mut request: Request) -> Request {
+        if let Some(etag) = &self.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+        request
+    }
 }
 
 impl CacheEntry {
@@ -199,37 +1118,79 @@ impl CacheEntry {
     }
 }
 
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Cache {
     pub fn new() -> Self {
+        Self::with_settings(DEFAULT_CACHE_SIZE, DEFAULT_CACHE_TTL)
+    }
+
+    /// Cache holding up to `max_size` entries, kept for `ttl` unless stored with their own lifetime
+    pub fn with_settings(max_size: usize, ttl: Duration) -> Self {
         Self {
             entries: Mutex::new(HashMap::new()),
-            max_size: 1000,
+            max_size,
+            ttl,
         }
     }
 
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
     pub fn get(&self, key: &str) -> Option<String> {
         let mut entries = self.entries.lock().unwrap();
         if let Some(entry) = entries.get(key) {
             if !entry.is_expired() {
                 return Some(entry.data.clone());
-            } else {
+            } else if entry.validators.is_empty() {
                 entries.remove(key);
             }
         }
         None
     }
 
+    /// An expired entry that can be revalidated, with its validators
+    pub fn stale(&self, key: &str) -> Option<(String, Validators)> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+        (entry.is_expired() && !entry.validators.is_empty()).then(|| (entry.data.clone(), entry.validators.clone()))
+    }
+
     pub fn put(&self, key: String, data: String) {
+        self.put_with_ttl(key, data, self.ttl);
+    }
+
+    /// Store a response with the validators to revalidate it once it expires
+    pub fn put_validated(&self, key: String, data: String, validators: Validators) {
+        self.insert(key, data, self.ttl, validators);
+    }
+
+    /// Store an item with a custom lifetime; `Duration::MAX` never expires
+    pub fn put_with_ttl(&self, key: String, data: String, ttl: Duration) {
+        self.insert(key, data, ttl, Validators::default());
+    }
+
+    fn insert(&self, key: String, data: String, ttl: Duration, validators: Validators) {
         let mut entries = self.entries.lock().unwrap();
         
-        if entries.len() >= self.max_size {
+        if entries.len() >= self.max_size && !entries.contains_key(&key) {
             self.evict_oldest(&mut entries);
         }
 
         let entry = CacheEntry {
             data,
             timestamp: SystemTime::now(),
-            ttl: Duration::from_secs(300),
+            ttl,
+            validators,
         };
 
         entries.insert(key, entry);
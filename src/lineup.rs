@@ -0,0 +1,219 @@
+//! Roster reads and lineup copying
+//! Repeats one week's or day's lineup on another, reporting players that can't be placed
+
+use std::collections::{HashMap, HashSet};
+
+use crate::transport::{Method, Request};
+use crate::xml::{RosterBuilder, RosterCoverage};
+use crate::caps;
+use crate::{CapKind, CapUsage};
+use crate::player_stats::eligible_positions;
+use crate::{response, time, Position, Roster, RosterPlayer, YahooFantasyClient};
+
+/// Position for reserve players
+const BENCH: &str = "BN";
+/// Slots that don't score
+const NON_STARTING: &[&str] = &["BN", "IR", "IL", "IL+", "NA"];
+/// Statuses that keep a player out of a starting slot
+const UNAVAILABLE: &[&str] = &["O", "IR", "IL", "IL10", "IL60", "NA", "SUSP"];
+
+/// Why a player from the source lineup couldn't be started again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnplaceableReason {
+    /// Dropped or traded since the source coverage
+    NotOnRoster,
+    /// No longer eligible at the slot they started in
+    NotEligible,
+    /// Injured, suspended, or otherwise out
+    Unavailable { status: String },
+    /// Their team doesn't play in the target coverage
+    NoGame,
+    /// Starting them would break a weekly cap the team has used up
+    CapReached { kind: CapKind },
+}
+
+/// A source starter that was left out of the copied lineup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unplaceable {
+    pub player_key: String,
+    pub position: String,
+    pub reason: UnplaceableReason,
+}
+
+/// Result of copying a lineup onto another coverage
+#[derive(Debug, Clone)]
+pub struct LineupCopy {
+    pub coverage: RosterCoverage,
+    /// Position changes to apply, as (player_key, position)
+    pub moves: Vec<(String, String)>,
+    pub unplaceable: Vec<Unplaceable>,
+    /// Roster write applying `moves`; `None` when the lineup already matches
+    pub request: Option<Request>,
+}
+
+impl YahooFantasyClient {
+    /// Get a team's roster for a week or date
+    pub async fn get_roster(&self, team_key: &str, coverage: &RosterCoverage) -> Result<Roster, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("team/{}/roster;{}", team_key, coverage_param(coverage))).await?;
+        let roster = parse_roster(&body, coverage)?;
+        self.eligibility.observe(&roster);
+        Ok(roster)
+    }
+
+    /// Repeat the lineup from `from` on `to`. Starters who can't be placed are
+    /// reported and their slot keeps whoever already holds it in `to`.
+    pub async fn copy_lineup(&self, team_key: &str, from: RosterCoverage, to: RosterCoverage) -> Result<LineupCopy, Box<dyn std::error::Error + Send + Sync>> {
+        let source = self.get_roster(team_key, &from).await?;
+        let target = self.get_roster(team_key, &to).await?;
+        let (moves, unplaceable) = plan_lineup_copy(&source, &target);
+
+        let request = if moves.is_empty() {
+            None
+        } else {
+            let body = moves
+                .iter()
+                .fold(RosterBuilder::new(to.clone()), |builder, (player_key, position)| builder.player(player_key, position))
+                .build()?;
+            Some(self.request(Method::Put, &format!("team/{}/roster", team_key)).with_body(&body))
+        };
+
+        Ok(LineupCopy {
+            coverage: to,
+            moves,
+            unplaceable,
+            request,
+        })
+    }
+}
+
+/// Position changes that turn `target` into `source`'s lineup, plus the source
+/// starters that couldn't be placed
+pub fn plan_lineup_copy(source: &Roster, target: &Roster) -> (Vec<(String, String)>, Vec<Unplaceable>) {
+    plan_lineup_copy_within(source, target, &[])
+}
+
+/// `plan_lineup_copy` for a team with weekly caps, e.g. from `get_cap_usage`.
+/// Source starters are placed in order until a cap runs out; the rest are
+/// reported as `CapReached` and keep their slot's current occupant.
+pub fn plan_lineup_copy_within(source: &Roster, target: &Roster, caps: &[CapUsage]) -> (Vec<(String, String)>, Vec<Unplaceable>) {
+    let mut caps = caps.to_vec();
+    let current: HashMap<&str, &RosterPlayer> = target.players.iter().map(|p| (p.player_key.as_str(), p)).collect();
+
+    let mut placed: Vec<(&str, &str)> = Vec::new();
+    let mut unplaceable = Vec::new();
+    for starter in source.players.iter().filter(|p| is_starting(&p.selected_position)) {
+        let position = starter.selected_position.as_str();
+        let reason = match current.get(starter.player_key.as_str()) {
+            None => Some(UnplaceableReason::NotOnRoster),
+            Some(p) if !p.eligible_positions.iter().any(|e| e == position) && !flex_accepts(position, p) => {
+                Some(UnplaceableReason::NotEligible)
+            }
+            Some(p) => match &p.status {
+                Some(status) if UNAVAILABLE.contains(&status.as_str()) => Some(UnplaceableReason::Unavailable { status: status.clone() }),
+                _ if !p.has_game => Some(UnplaceableReason::NoGame),
+                _ => caps::try_start(&mut caps, p).err().map(|kind| UnplaceableReason::CapReached { kind }),
+            },
+        };
+        match reason {
+            Some(reason) => unplaceable.push(Unplaceable {
+                player_key: starter.player_key.clone(),
+                position: position.to_string(),
+                reason,
+            }),
+            None => placed.push((&starter.player_key, position)),
+        }
+    }
+
+    // Starting slots per position; those not taken by placed players keep their current occupant
+    let mut open: HashMap<&str, usize> = HashMap::new();
+    for roster in [source, target] {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for player in roster.players.iter().filter(|p| is_starting(&p.selected_position)) {
+            *counts.entry(&player.selected_position).or_default() += 1;
+        }
+        for (position, count) in counts {
+            let slots = open.entry(position).or_default();
+            *slots = (*slots).max(count);
+        }
+    }
+    for (_, position) in &placed {
+        if let Some(n) = open.get_mut(position) {
+            *n = n.saturating_sub(1);
+        }
+    }
+
+    let placed_keys: HashSet<&str> = placed.iter().map(|(key, _)| *key).collect();
+    let mut lineup: Vec<(&str, &str)> = placed.clone();
+    for player in &target.players {
+        if placed_keys.contains(player.player_key.as_str()) || !is_starting(&player.selected_position) {
+            continue;
+        }
+        match open.get_mut(player.selected_position.as_str()) {
+            Some(n) if *n > 0 => *n -= 1,
+            _ => lineup.push((&player.player_key, BENCH)),
+        }
+    }
+
+    let moves = lineup
+        .into_iter()
+        .filter(|(key, position)| current.get(key).is_some_and(|p| p.selected_position != *position))
+        .map(|(key, position)| (key.to_string(), position.to_string()))
+        .collect();
+    (moves, unplaceable)
+}
+
+pub(crate) fn is_starting(position: &str) -> bool {
+    !NON_STARTING.contains(&position)
+}
+
+/// Flex slots are named after the positions they accept, e.g. "W/R/T", "Util",
+/// or the IDP slots "DL", "DB", and "D". Team defenses only ever fill their own slot.
+fn flex_accepts(slot: &str, player: &RosterPlayer) -> bool {
+    if player.is_team_defense() {
+        return false;
+    }
+    let slot_position: Position = slot.parse().unwrap_or_else(|never| match never {});
+    if slot_position.is_idp() {
+        return player.positions().iter().any(|p| p.fills(&slot_position));
+    }
+    if slot == "Util" {
+        return true;
+    }
+    let initials: Vec<&str> = slot.split('/').collect();
+    initials.len() > 1
+        && player
+            .eligible_positions
+            .iter()
+            .any(|p| initials.iter().any(|i| p.starts_with(i)))
+}
+
+fn coverage_param(coverage: &RosterCoverage) -> String {
+    match coverage {
+        RosterCoverage::Week(week) => format!("week={}", week),
+        RosterCoverage::Date(date) => format!("date={}", time::format_date(*date)),
+    }
+}
+
+/// Roster from a `team/{key}/roster` response. Yahoo doesn't say whether a
+/// player's team plays, so `has_game` is only false for an NFL bye week.
+fn parse_roster(body: &serde_json::Value, coverage: &RosterCoverage) -> Result<Roster, Box<dyn std::error::Error + Send + Sync>> {
+    let team = response::resource(&response::content(body)?["team"]);
+    let roster = response::resource(response::member(&team, "roster"));
+    let mut players = Vec::new();
+    // The players collection sits under the roster's "0" entry, beside its coverage fields
+    for player in response::collection(&response::member(&roster, "0")["players"], "player") {
+        let bye: Option<u32> = response::field(&response::resource(response::member(&player, "bye_weeks")), "week");
+        players.push(RosterPlayer {
+            player_key: response::require(&player, "player_key", "player")?,
+            name: response::member(&player, "name")["full"].as_str().unwrap_or_default().to_string(),
+            eligible_positions: eligible_positions(&player),
+            selected_position: response::field(&response::resource(response::member(&player, "selected_position")), "position").unwrap_or_else(|| BENCH.to_string()),
+            status: response::field(&player, "status").filter(|s: &String| !s.is_empty()),
+            has_game: !matches!(coverage, RosterCoverage::Week(week) if bye == Some(*week)),
+        });
+    }
+    Ok(Roster {
+        team_key: response::require(&team, "team_key", "team")?,
+        players,
+    })
+}
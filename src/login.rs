@@ -0,0 +1,429 @@
+//! Three-legged OAuth login
+//! Request token, user authorization, and access token exchange for acting on a user's behalf
+
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::Utc;
+use percent_encoding::percent_decode_str;
+use serde::{Deserialize, Serialize};
+
+use crate::oauth;
+use crate::session::DEFAULT_REFRESH_MARGIN;
+use crate::{Cache, HttpError, Request, Secret, StoredToken, YahooFantasyClient};
+
+/// Yahoo endpoint issuing request tokens
+pub const REQUEST_TOKEN_URL: &str = "https://api.login.yahoo.com/oauth/v2/get_request_token";
+
+/// Yahoo endpoint exchanging an authorized request token for an access token
+pub const ACCESS_TOKEN_URL: &str = "https://api.login.yahoo.com/oauth/v2/get_token";
+
+/// Callback asking Yahoo to show the verifier to the user instead of redirecting
+pub const OOB_CALLBACK: &str = "oob";
+
+/// Yahoo's page where users approve a request token
+pub const REQUEST_AUTH_URL: &str = "https://api.login.yahoo.com/oauth/v2/request_auth";
+
+/// Fantasy Sports permission requested at login
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Scope {
+    /// Read leagues, teams, and players
+    Read,
+    /// Read, plus edit rosters, lineups, and transactions
+    Write,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "fspt-r",
+            Scope::Write => "fspt-w",
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Yahoo rejected a call because the user's token lacks `scope`. Start a new
+/// login with `YahooFantasyClient::reauthorize` to request it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsufficientScope {
+    pub scope: Scope,
+    /// Page where the user approves the new login's request token
+    pub reauthorize_url: String,
+    pub status: u16,
+    pub body: String,
+}
+
+impl fmt::Display for InsufficientScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the access token lacks the {} scope; re-authorize at {}", self.scope, self.reauthorize_url)
+    }
+}
+
+impl std::error::Error for InsufficientScope {}
+
+/// Yahoo rejected a call because the access token expired, and the client
+/// couldn't refresh it to retry. Log in again to get a new token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenExpired {
+    /// Why the token couldn't be refreshed
+    pub reason: String,
+}
+
+impl fmt::Display for TokenExpired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the access token expired and could not be refreshed: {}", self.reason)
+    }
+}
+
+impl std::error::Error for TokenExpired {}
+
+/// Yahoo revoked the user's authorization, or refused to refresh the token
+/// because its session handle is no longer valid. The token can't be used or
+/// refreshed again; the user has to log in again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenRevoked {
+    pub status: u16,
+    pub body: String,
+}
+
+impl fmt::Display for TokenRevoked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the user's authorization was revoked; log in again (HTTP {}: {})", self.status, self.body.trim())
+    }
+}
+
+impl std::error::Error for TokenRevoked {}
+
+/// Outcome of the last refresh, for the requests that waited on it
+#[derive(Debug, Default)]
+pub(crate) struct RefreshState {
+    failed: Option<FailedRefresh>,
+    /// The token last refreshed and what it was replaced with, so another
+    /// client for the same user can pick up the new token instead of
+    /// refreshing with a session handle that may have been rotated away
+    succeeded: Option<(AccessToken, StoredToken)>,
+}
+
+/// A refresh that failed, reported to the requests that waited on it
+#[derive(Debug)]
+struct FailedRefresh {
+    token: AccessToken,
+    error: String,
+    revoked: Option<TokenRevoked>,
+    at: Instant,
+}
+
+/// A temporary token the user authorizes at `authorize_url`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RequestToken {
+    pub token: String,
+    pub secret: Secret,
+    pub authorize_url: String,
+}
+
+/// Yahoo's out-of-band login for SSH sessions and other machines without a
+/// browser: the user approves at `authorize_url` on any device, and Yahoo shows
+/// them a verifier code to paste back in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManualAuthFlow {
+    request_token: RequestToken,
+}
+
+impl ManualAuthFlow {
+    pub fn authorize_url(&self) -> &str {
+        &self.request_token.authorize_url
+    }
+
+    pub fn request_token(&self) -> &RequestToken {
+        &self.request_token
+    }
+}
+
+/// Tokens for signing requests on a user's behalf
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AccessToken {
+    pub token: Secret,
+    pub secret: Secret,
+    /// Handle for refreshing the token once it expires
+    pub session_handle: Option<Secret>,
+    /// Seconds the token is valid for
+    pub expires_in: Option<u64>,
+}
+
+impl AccessToken {
+    /// Token and secret, as used for signing
+    pub(crate) fn pair(&self) -> (&str, &str) {
+        (self.token.expose(), self.secret.expose())
+    }
+}
+
+impl YahooFantasyClient {
+    /// Start a login: Yahoo redirects the user to `callback` with `oauth_token`
+    /// and `oauth_verifier` after they approve at `RequestToken::authorize_url`.
+    /// Asks for the scope set with `ClientBuilder::scope`, if any.
+    pub async fn get_request_token(&self, callback: &str) -> Result<RequestToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.request_token(callback, self.scope).await
+    }
+
+    /// Start a login asking for `scope`, e.g. after an `InsufficientScope` error
+    pub async fn reauthorize(&self, scope: Scope, callback: &str) -> Result<RequestToken, Box<dyn std::error::Error + Send + Sync>> {
+        self.request_token(callback, Some(scope)).await
+    }
+
+    async fn request_token(&self, callback: &str, scope: Option<Scope>) -> Result<RequestToken, Box<dyn std::error::Error + Send + Sync>> {
+        let mut url = format!("{}?oauth_callback={}", REQUEST_TOKEN_URL, oauth::encode(callback));
+        if let Some(scope) = scope {
+            url.push_str(&format!("&scope={}", scope));
+        }
+        let fields = self.token_request(&url, None).await?;
+        Ok(RequestToken {
+            token: field(&fields, "oauth_token")?,
+            secret: secret_field(&fields, "oauth_token_secret")?,
+            authorize_url: field(&fields, "xoauth_request_auth_url")?,
+        })
+    }
+
+    /// Finish a login with the verifier Yahoo passed to the callback
+    pub async fn get_access_token(&self, request_token: &RequestToken, verifier: &str) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}?oauth_verifier={}", ACCESS_TOKEN_URL, oauth::encode(verifier));
+        let fields = self
+            .token_request(&url, Some((&request_token.token, request_token.secret.expose())))
+            .await?;
+        Ok(AccessToken {
+            token: secret_field(&fields, "oauth_token")?,
+            secret: secret_field(&fields, "oauth_token_secret")?,
+            session_handle: secret_field(&fields, "oauth_session_handle").ok(),
+            expires_in: field(&fields, "oauth_expires_in").ok().and_then(|v| v.parse().ok()),
+        })
+    }
+
+    /// Start an out-of-band login asking for `scope`, or the scope set with
+    /// `ClientBuilder::scope` if `None`
+    pub async fn start_manual_auth(&self, scope: Option<Scope>) -> Result<ManualAuthFlow, Box<dyn std::error::Error + Send + Sync>> {
+        let request_token = self.request_token(OOB_CALLBACK, scope.or(self.scope)).await?;
+        Ok(ManualAuthFlow { request_token })
+    }
+
+    /// Finish an out-of-band login with the code the user pasted; surrounding whitespace is ignored
+    pub async fn finish_manual_auth(&self, flow: &ManualAuthFlow, verifier: &str) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        let verifier = verifier.trim();
+        if verifier.is_empty() {
+            return Err("no verifier code was entered".into());
+        }
+        self.get_access_token(&flow.request_token, verifier).await
+    }
+
+    /// Run an out-of-band login at a terminal: write the approval URL to
+    /// `output`, read the pasted code from `input`, and finish the exchange
+    pub async fn login_manually(
+        &self,
+        scope: Option<Scope>,
+        mut input: impl BufRead,
+        mut output: impl Write,
+    ) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        let flow = self.start_manual_auth(scope).await?;
+        writeln!(output, "Approve access at {}", flow.authorize_url())?;
+        write!(output, "Then enter the code Yahoo shows: ")?;
+        output.flush()?;
+        let mut verifier = String::new();
+        input.read_line(&mut verifier)?;
+        self.finish_manual_auth(&flow, &verifier).await
+    }
+
+    /// Exchange an expired or expiring token for a new one using its session handle
+    pub async fn refresh_access_token(&self, token: &AccessToken) -> Result<AccessToken, Box<dyn std::error::Error + Send + Sync>> {
+        let session_handle = token
+            .session_handle
+            .as_ref()
+            .map(Secret::expose)
+            .ok_or("access token has no session handle to refresh with")?;
+        let url = format!("{}?oauth_session_handle={}", ACCESS_TOKEN_URL, oauth::encode(session_handle));
+        let fields = self.token_request(&url, Some(token.pair())).await.map_err(session_rejected)?;
+        Ok(AccessToken {
+            token: secret_field(&fields, "oauth_token")?,
+            secret: secret_field(&fields, "oauth_token_secret")?,
+            session_handle: secret_field(&fields, "oauth_session_handle").ok().or_else(|| token.session_handle.clone()),
+            expires_in: field(&fields, "oauth_expires_in").ok().and_then(|v| v.parse().ok()),
+        })
+    }
+
+    /// Client acting as the user who granted `token`. It shares this client's
+    /// rate limiter, transport, eligibility cache, and shutdown, but has its own
+    /// response cache so one user's data is never served to another.
+    pub fn for_user(&self, token: &AccessToken) -> YahooFantasyClient {
+        self.for_stored_token(StoredToken::new(token.clone(), Utc::now()))
+    }
+
+    /// `for_user`, keeping the expiry recorded when the token was obtained
+    pub(crate) fn for_stored_token(&self, token: StoredToken) -> YahooFantasyClient {
+        YahooFantasyClient {
+            consumer_key: self.consumer_key.clone(),
+            consumer_secret: self.consumer_secret.clone(),
+            token: Mutex::new(Some(token)),
+            refresh: Default::default(),
+            token_store: None,
+            refresh_hook: None,
+            base_url: self.base_url.clone(),
+            user_agent: self.user_agent.clone(),
+            default_headers: self.default_headers.clone(),
+            rate_limiter: self.rate_limiter.clone(),
+            cache: Arc::new(Cache::with_settings(self.cache.max_size(), self.cache.ttl())),
+            eligibility: self.eligibility.clone(),
+            shutdown: self.shutdown.clone(),
+            in_flight: self.in_flight.clone(),
+            transport: self.transport.clone(),
+            middleware: self.middleware.clone(),
+            request_timeout: self.request_timeout,
+            retry: self.retry,
+            scope: self.scope,
+            circuit_breaker: self.circuit_breaker.clone(),
+            weighted_costs: self.weighted_costs,
+        }
+    }
+
+    /// The client's token and expiry, as last set or refreshed
+    pub(crate) fn stored_token(&self) -> Option<StoredToken> {
+        self.token.lock().unwrap().clone()
+    }
+
+    /// Refresh the client's token if it can be refreshed and expires within
+    /// `DEFAULT_REFRESH_MARGIN`. A failed refresh is only an error once the
+    /// token has expired, or if the authorization was revoked.
+    pub(crate) async fn refresh_token_if_due(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(current) = self.stored_token() else {
+            return Ok(());
+        };
+        if !current.needs_refresh(DEFAULT_REFRESH_MARGIN, &Utc::now()) {
+            return Ok(());
+        }
+        match self.refresh_stored_token(&current).await {
+            Err(e) if !current.is_expired(&Utc::now()) && !e.is::<TokenRevoked>() => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Refresh the client's token after Yahoo rejected it as expired, so the
+    /// request can be retried, failing with `TokenRevoked` if the authorization
+    /// was revoked and `TokenExpired` if the token otherwise can't be refreshed
+    pub(crate) async fn refresh_rejected_token(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let current = match self.stored_token() {
+            Some(current) if current.token.session_handle.is_some() => current,
+            Some(_) => return Err(Box::new(TokenExpired { reason: "it has no session handle to refresh with".to_string() })),
+            None => return Err(Box::new(TokenExpired { reason: "the client has no access token".to_string() })),
+        };
+        self.refresh_stored_token(&current).await.map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+            if e.is::<TokenRevoked>() {
+                e
+            } else {
+                Box::new(TokenExpired { reason: e.to_string() })
+            }
+        })
+    }
+
+    /// Replace `current` with a refreshed token, saving it to the token store
+    /// and passing it to the refresh hook, if there are any, before the old token is dropped: Yahoo may rotate the
+    /// session handle, leaving only the new token refreshable. Only one refresh runs at a time: requests that find one
+    /// running wait for it, then succeed with its token or fail with its error
+    /// rather than refreshing again.
+    pub(crate) async fn refresh_stored_token(&self, current: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let waiting_since = Instant::now();
+        let mut state = self.refresh.lock().await;
+        if self.stored_token().is_some_and(|t| t.token != current.token) {
+            return Ok(());
+        }
+        if let Some((_, refreshed)) = state.succeeded.as_ref().filter(|(from, _)| *from == current.token) {
+            *self.token.lock().unwrap() = Some(refreshed.clone());
+            return Ok(());
+        }
+        if let Some(failure) = state.failed.as_ref().filter(|f| f.at >= waiting_since && f.token == current.token) {
+            return Err(match &failure.revoked {
+                Some(revoked) => Box::new(revoked.clone()),
+                None => failure.error.clone().into(),
+            });
+        }
+        match self.refresh_access_token(&current.token).await {
+            Ok(token) => {
+                let refreshed = StoredToken::new(token, Utc::now());
+                state.failed = None;
+                state.succeeded = Some((current.token.clone(), refreshed.clone()));
+                let mut saved = match &self.token_store {
+                    Some(binding) => binding.store.save(&binding.user, &refreshed),
+                    None => Ok(()),
+                };
+                if let Some(hook) = &self.refresh_hook {
+                    saved = saved.and(hook.call(&refreshed));
+                }
+                // The old session handle may no longer work, so keep the new token even if saving failed
+                *self.token.lock().unwrap() = Some(refreshed);
+                saved
+            }
+            Err(e) => {
+                state.failed = Some(FailedRefresh {
+                    token: current.token.clone(),
+                    error: e.to_string(),
+                    revoked: e.downcast_ref::<TokenRevoked>().cloned(),
+                    at: Instant::now(),
+                });
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a token endpoint request, bypassing the response cache, and parse the form-encoded reply
+    async fn token_request(&self, url: &str, token: Option<(&str, &str)>) -> Result<Vec<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
+        let request = Request::get(url).header("User-Agent", &self.user_agent);
+        let body = {
+            let mut in_flight = self.begin_request(None).await?;
+            let body = self.dispatch_with_token(&request, token).await;
+            in_flight.sent();
+            body?
+        };
+        body.trim()
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                Ok((decode(name)?, decode(value)?))
+            })
+            .collect()
+    }
+}
+
+/// A refresh Yahoo refused because the session handle was revoked or is no
+/// longer valid, e.g. after a rotated handle was lost, as `TokenRevoked`
+fn session_rejected(error: Box<dyn std::error::Error + Send + Sync>) -> Box<dyn std::error::Error + Send + Sync> {
+    match error.downcast_ref::<HttpError>() {
+        Some(e) if e.status == 401 && (e.body.contains("token_rejected") || e.body.contains("token_revoked")) => Box::new(TokenRevoked {
+            status: e.status,
+            body: e.body.clone(),
+        }),
+        _ => error,
+    }
+}
+
+fn decode(value: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(percent_decode_str(&value.replace('+', " ")).decode_utf8()?.into_owned())
+}
+
+fn secret_field(fields: &[(String, String)], name: &str) -> Result<Secret, Box<dyn std::error::Error + Send + Sync>> {
+    field(fields, name).map(Secret::from)
+}
+
+fn field(fields: &[(String, String)], name: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    fields
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.clone())
+        .ok_or_else(|| format!("token response is missing {}", name).into())
+}
@@ -1,310 +1,204 @@
 //! Yahoo Fantasy Sports SDK - Rust Implementation  
 //! Core API client with authentication, rate limiting, and caching
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
-use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
-/// Main SDK client
-#[derive(Debug)]
-pub struct YahooFantasyClient {
-    consumer_key: String,
-    consumer_secret: String,
-    access_token: Option<String>,
-    access_token_secret: Option<String>,
-    base_url: String,
-    rate_limiter: Arc<RateLimiter>,
-    cache: Arc<Cache>,
-    // HTTP client would be used for real API calls
-}
-
-impl YahooFantasyClient {
-    /// Create a new Yahoo Fantasy client
-    pub fn new(consumer_key: String, consumer_secret: String) -> Self {
-        Self {
-            consumer_key,
-            consumer_secret,
-            access_token: None,
-            access_token_secret: None,
-            base_url: "https://fantasysports.yahooapis.com/fantasy/v2".to_string(),
-            rate_limiter: Arc::new(RateLimiter::new()),
-            cache: Arc::new(Cache::new()),
-            // HTTP client initialization would go here
-        }
-    }
+use chrono::Utc;
+use yahoo_fantasy_sdk::{FileTokenStore, HttpError, InsufficientScope, StoredToken, TokenExpired, TokenRevoked, TokenStore, YahooFantasyClient};
 
-    /// Set OAuth access tokens
-    pub fn set_tokens(&mut self, access_token: String, access_token_secret: String) {
-        self.access_token = Some(access_token);
-        self.access_token_secret = Some(access_token_secret);
-    }
+/// User the CLI's tokens are stored under in the token store
+const TOKEN_USER: &str = "cli";
 
-    /// Check if client is authenticated
-    pub fn is_authenticated(&self) -> bool {
-        self.access_token.is_some() && self.access_token_secret.is_some()
-    }
+/// Demo function
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let options = options()?;
 
-    /// Get all available games (mock implementation for testing)
-    pub fn get_games(&self) -> Result<Vec<Game>, Box<dyn std::error::Error + Send + Sync>> {
-        // Wait for rate limiter
-        self.rate_limiter.wait_for_request();
-        
-        // Mock data for testing - same as other implementations
-        let games = vec![
-            Game {
-                game_key: "nfl.2024".to_string(),
-                name: "NFL Football".to_string(),
-                code: "nfl".to_string(),
-                season: 2024,
-            },
-            Game {
-                game_key: "nba.2024".to_string(),
-                name: "NBA Basketball".to_string(),
-                code: "nba".to_string(),
-                season: 2024,
-            },
-            Game {
-                game_key: "mlb.2024".to_string(),
-                name: "MLB Baseball".to_string(),
-                code: "mlb".to_string(),
-                season: 2024,
-            },
-        ];
+    println!("Yahoo Fantasy Sports SDK - Rust Implementation");
+    println!("==============================================");
+    println!();
 
-        self.rate_limiter.record_request();
-        Ok(games)
+    // Initialize client from the environment; games only need app credentials
+    let consumer_key = std::env::var("YAHOO_CONSUMER_KEY").unwrap_or_default();
+    let consumer_secret = std::env::var("YAHOO_CONSUMER_SECRET").unwrap_or_default();
+    // Tokens saved in the store are loaded now, and refreshed tokens saved back
+    let store: Option<Arc<dyn TokenStore>> = match std::env::var("YAHOO_TOKEN_STORE") {
+        Ok(path) => Some(open_token_store(&path)?),
+        Err(_) => None,
+    };
+    let mut builder = YahooFantasyClient::builder(consumer_key.clone(), consumer_secret);
+    if let Some(store) = &store {
+        builder = builder.token_store(store.clone(), TOKEN_USER);
     }
+    let mut client = builder.build()?;
 
-    /// Get user's leagues for a specific game (mock implementation)
-    pub fn get_leagues(&self, game_key: &str) -> Result<Vec<League>, Box<dyn std::error::Error + Send + Sync>> {
-        // Wait for rate limiter
-        self.rate_limiter.wait_for_request();
-        
-        let _ = game_key; // Mock implementation
-        let leagues = vec![
-            League {
-                league_key: "423.l.12345".to_string(),
-                name: "My Test League".to_string(),
-                num_teams: 12,
-                current_week: 15,
-            }
-        ];
-
-        self.rate_limiter.record_request();
-        Ok(leagues)
+    println!("✓ SDK Client initialized");
+    println!("  Authenticated: {}", client.is_authenticated());
+    if store.is_some() && client.is_authenticated() {
+        println!("✓ Tokens loaded from the token store");
     }
-}
-
-/// Game data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Game {
-    pub game_key: String,
-    pub name: String,
-    pub code: String,
-    pub season: i32,
-}
-
-/// League data structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct League {
-    pub league_key: String,
-    pub name: String,
-    pub num_teams: i32,
-    pub current_week: i32,
-}
-
-/// Rate limiter using token bucket algorithm
-#[derive(Debug)]
-pub struct RateLimiter {
-    state: Mutex<RateLimiterState>,
-}
-
-#[derive(Debug)]
-struct RateLimiterState {
-    tokens: f64,
-    max_tokens: f64,
-    refill_rate: f64, // tokens per second
-    last_refill: Instant,
-    requests_count: u64,
-}
 
-impl RateLimiter {
-    /// Create a new rate limiter
-    pub fn new() -> Self {
-        Self {
-            state: Mutex::new(RateLimiterState {
-                tokens: 100.0, // Start with full bucket
-                max_tokens: 100.0, // 100 requests burst
-                refill_rate: 0.83, // ~3000 requests/hour
-                last_refill: Instant::now(),
-                requests_count: 0,
-            }),
-        }
+    if let (Ok(token), Ok(token_secret)) = (std::env::var("YAHOO_ACCESS_TOKEN"), std::env::var("YAHOO_ACCESS_TOKEN_SECRET")) {
+        client.set_tokens(token, token_secret);
+        println!("✓ Tokens set, authenticated: {}", client.is_authenticated());
     }
 
-    /// Wait for a request to be available
-    pub fn wait_for_request(&self) {
-        loop {
-            if self.can_make_request() {
-                break;
+    if consumer_key.is_empty() {
+        println!();
+        println!("Set YAHOO_CONSUMER_KEY and YAHOO_CONSUMER_SECRET to fetch games");
+    } else {
+        let games = match client.get_games().await {
+            Err(e) if options.auto_reauth && needs_reauth(e.as_ref()) => {
+                reauth(&mut client, store.as_deref(), e.as_ref()).await?;
+                client.get_games().await?
+            }
+            result => result?,
+        };
+
+        println!();
+        println!("✓ Retrieved {} games:", games.len());
+        match options.format {
+            Format::Text => {
+                for game in &games {
+                    println!("  - {} ({}): {}", game.season, game.code, game.name);
+                }
+            }
+            Format::Markdown => {
+                println!("| Season | Code | Game |");
+                println!("| --- | --- | --- |");
+                for game in &games {
+                    println!("| {} | {} | {} |", game.season, game.code, game.name.replace('|', "\\|"));
+                }
             }
-            // Wait a small amount before checking again
-            std::thread::sleep(Duration::from_millis(10));
         }
     }
 
-    /// Check if a request can be made
-    pub fn can_make_request(&self) -> bool {
-        let mut state = self.state.lock().unwrap();
-        self.refill_tokens(&mut state);
-        state.tokens >= 1.0
-    }
+    // Demo rate limiter
+    println!();
+    println!("--- Rate Limiter Demo ---");
+    for i in 0..5 {
+        let can_request = client.rate_limiter().can_make_request();
+        let tokens = client.rate_limiter().get_remaining_tokens();
+        println!(
+            "Request {}: Can make request: {}, Tokens remaining: {:.1}",
+            i + 1,
+            can_request,
+            tokens
+        );
 
-    /// Record that a request was made
-    pub fn record_request(&self) {
-        let mut state = self.state.lock().unwrap();
-        if state.tokens >= 1.0 {
-            state.tokens -= 1.0;
-            state.requests_count += 1;
+        if can_request {
+            client.rate_limiter().record_request();
         }
     }
 
-    /// Get remaining tokens
-    pub fn get_remaining_tokens(&self) -> f64 {
-        let mut state = self.state.lock().unwrap();
-        self.refill_tokens(&mut state);
-        state.tokens
-    }
-
-    fn refill_tokens(&self, state: &mut RateLimiterState) {
-        let now = Instant::now();
-        let time_passed = now.duration_since(state.last_refill).as_secs_f64();
-        
-        let tokens_to_add = time_passed * state.refill_rate;
-        state.tokens = (state.max_tokens).min(state.tokens + tokens_to_add);
-        state.last_refill = now;
-    }
-}
+    println!();
+    println!("✓ Rust SDK demo completed successfully");
 
-/// Simple in-memory cache with TTL
-#[derive(Debug)]
-pub struct Cache {
-    entries: Mutex<HashMap<String, CacheEntry>>,
-    max_size: usize,
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct CacheEntry {
-    data: String,
-    timestamp: SystemTime,
-    ttl: Duration,
+/// How listings are printed
+#[derive(Clone, Copy)]
+enum Format {
+    Text,
+    /// Tables ready to paste into Discord, Reddit, or a group chat
+    Markdown,
 }
 
-impl CacheEntry {
-    /// Check if the cache entry has expired
-    pub fn is_expired(&self) -> bool {
-        SystemTime::now()
-            .duration_since(self.timestamp)
-            .map(|duration| duration > self.ttl)
-            .unwrap_or(true)
-    }
+/// Command-line options
+struct Options {
+    format: Format,
+    /// Run the login flow inline when tokens are expired or lack a scope, instead of exiting
+    auto_reauth: bool,
 }
 
-impl Cache {
-    /// Create a new cache instance
-    pub fn new() -> Self {
-        Self {
-            entries: Mutex::new(HashMap::new()),
-            max_size: 1000,
+/// Parse `--format text|markdown` and `--auto-reauth` from the command line.
+/// `YAHOO_AUTO_REAUTH=1` turns auto re-auth on by default.
+fn options() -> Result<Options, Box<dyn std::error::Error + Send + Sync>> {
+    let mut args = std::env::args().skip(1);
+    let mut options = Options {
+        format: Format::Text,
+        auto_reauth: matches!(std::env::var("YAHOO_AUTO_REAUTH").as_deref(), Ok("1" | "true" | "yes")),
+    };
+    while let Some(arg) = args.next() {
+        if arg == "--auto-reauth" {
+            options.auto_reauth = true;
+            continue;
         }
-    }
-
-    /// Get an item from the cache
-    pub fn get(&self, key: &str) -> Option<String> {
-        let mut entries = self.entries.lock().unwrap();
-        if let Some(entry) = entries.get(key) {
-            if !entry.is_expired() {
-                return Some(entry.data.clone());
-            } else {
-                // Remove expired entry
-                entries.remove(key);
-            }
-        }
-        None
-    }
-
-    /// Store an item in the cache
-    pub fn put(&self, key: String, data: String) {
-        let mut entries = self.entries.lock().unwrap();
-        
-        // Simple eviction if cache is full
-        if entries.len() >= self.max_size {
-            self.evict_oldest(&mut entries);
-        }
-
-        let entry = CacheEntry {
-            data,
-            timestamp: SystemTime::now(),
-            ttl: Duration::from_secs(300), // 5 minutes
+        let value = match arg.strip_prefix("--format") {
+            Some("") => args.next().ok_or("--format needs a value")?,
+            Some(rest) if rest.starts_with('=') => rest[1..].to_string(),
+            _ => return Err(format!("unknown argument `{}`", arg).into()),
+        };
+        options.format = match value.as_str() {
+            "text" => Format::Text,
+            "markdown" | "md" => Format::Markdown,
+            other => return Err(format!("unknown format `{}`; expected text or markdown", other).into()),
         };
-
-        entries.insert(key, entry);
     }
+    Ok(options)
+}
 
-    fn evict_oldest(&self, entries: &mut HashMap<String, CacheEntry>) {
-        // Simple implementation - remove first entry found
-        // In production, would use LRU
-        if let Some(key) = entries.keys().next().cloned() {
-            entries.remove(&key);
+/// The token store at `path`, encrypted with `YAHOO_TOKEN_PASSPHRASE` or the
+/// key in `YAHOO_TOKEN_KEY_FILE` when either is set
+fn open_token_store(path: &str) -> Result<Arc<dyn TokenStore>, Box<dyn std::error::Error + Send + Sync>> {
+    #[cfg(feature = "encryption")]
+    {
+        use yahoo_fantasy_sdk::{EncryptedFileTokenStore, TokenKey};
+        let key = match (std::env::var("YAHOO_TOKEN_PASSPHRASE"), std::env::var("YAHOO_TOKEN_KEY_FILE")) {
+            (Ok(passphrase), _) => Some(TokenKey::passphrase(&passphrase)),
+            (_, Ok(key_file)) => Some(TokenKey::from_key_file(key_file)?),
+            _ => None,
+        };
+        if let Some(key) = key {
+            return Ok(Arc::new(EncryptedFileTokenStore::open(path, &key)?));
         }
     }
+    Ok(Arc::new(FileTokenStore::open(path)?))
 }
 
-/// Demo function
-fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("Yahoo Fantasy Sports SDK - Rust Implementation");
-    println!("==============================================");
-    println!();
-
-    // Initialize client
-    let mut client = YahooFantasyClient::new("test_key".to_string(), "test_secret".to_string());
-
-    println!("✓ SDK Client initialized");
-    println!("  Authenticated: {}", client.is_authenticated());
-
-    // Demo with mock data
-    client.set_tokens("mock_token".to_string(), "mock_secret".to_string());
-    println!("✓ Tokens set, authenticated: {}", client.is_authenticated());
-
-    let games = client.get_games()?;
-    
-    println!();
-    println!("✓ Retrieved {} games:", games.len());
-    for game in &games {
-        println!("  - {} ({}): {}", game.season, game.code, game.name);
+/// Whether logging in again could fix `error`: a missing scope or an expired or rejected token
+fn needs_reauth(error: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    if error.is::<InsufficientScope>() || error.is::<TokenExpired>() || error.is::<TokenRevoked>() {
+        return true;
     }
+    error
+        .downcast_ref::<HttpError>()
+        .is_some_and(|e| e.status == 401 && (e.body.contains("token_expired") || e.body.contains("token_rejected")))
+}
 
-    // Demo rate limiter
+/// Refresh the stored token, or failing that walk the user through logging in
+/// again, saving the new token to the store
+async fn reauth(
+    client: &mut YahooFantasyClient,
+    store: Option<&dyn TokenStore>,
+    error: &(dyn std::error::Error + Send + Sync + 'static),
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!();
-    println!("--- Rate Limiter Demo ---");
-    for i in 0..5 {
-        let can_request = client.rate_limiter.can_make_request();
-        let tokens = client.rate_limiter.get_remaining_tokens();
-        println!(
-            "Request {}: Can make request: {}, Tokens remaining: {:.1}",
-            i + 1,
-            can_request,
-            tokens
-        );
-
-        if can_request {
-            client.rate_limiter.record_request();
+    println!("! {}", error);
+    let missing_scope = error.downcast_ref::<InsufficientScope>().map(|e| e.scope);
+
+    let stored = match store {
+        Some(store) => store.load(TOKEN_USER)?,
+        None => None,
+    };
+    // A revoked authorization can't be refreshed
+    let revoked = error.is::<TokenRevoked>();
+    let refreshed = match (&stored, missing_scope) {
+        (Some(stored), None) if stored.token.session_handle.is_some() && !revoked => client.refresh_access_token(&stored.token).await.ok(),
+        _ => None,
+    };
+    let token = match refreshed {
+        Some(token) => {
+            println!("✓ Refreshed the access token");
+            token
         }
-    }
-
-    println!();
-    println!("✓ Rust SDK demo completed successfully");
+        None => client.login_manually(missing_scope, std::io::stdin().lock(), std::io::stdout()).await?,
+    };
 
+    if let Some(store) = store {
+        store.save(TOKEN_USER, &StoredToken::new(token.clone(), Utc::now()))?;
+    }
+    client.set_access_token(token);
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,63 @@
+//! Request/response middleware
+//! Ordered hooks around the transport for adding headers, logging, or wrapping requests
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{BoxFuture, HttpResponse, HttpTransport, Request, YahooFantasyClient};
+
+/// Sees every request on its way to the transport and every response on its
+/// way back. Requests arrive already signed, so changing the method or URL
+/// invalidates the OAuth signature; adding headers is safe.
+pub trait Middleware: Send + Sync {
+    /// Handle `request`, usually by passing it on with `next.run` and
+    /// inspecting or changing the response it returns
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>>;
+}
+
+impl fmt::Debug for dyn Middleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Middleware")
+    }
+}
+
+/// The rest of the pipeline: later middleware, then the transport
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    transport: &'a dyn HttpTransport,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl fmt::Debug for Next<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Next").field("remaining", &self.middleware.len()).finish()
+    }
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(transport: &'a dyn HttpTransport, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self { transport, middleware }
+    }
+
+    /// Pass `request` down the pipeline
+    pub fn run(self, request: Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        match self.middleware.split_first() {
+            Some((first, rest)) => first.handle(
+                request,
+                Next {
+                    transport: self.transport,
+                    middleware: rest,
+                },
+            ),
+            None => Box::pin(async move { self.transport.send(&request).await }),
+        }
+    }
+}
+
+impl YahooFantasyClient {
+    /// Add middleware after any already registered; the first registered sees requests first and responses last
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+}
@@ -0,0 +1,139 @@
+//! Quota-aware pagination
+//! Walks `start`/`count` paged collections, pausing or slowing down when the rate limiter runs low
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use crate::YahooFantasyClient;
+
+/// Items Yahoo returns per page of a paged collection
+pub const DEFAULT_PAGE_SIZE: u32 = 25;
+
+/// What a pager does when the rate limiter is running low
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuotaPolicy {
+    /// Wait for tokens like any other request
+    Wait,
+    /// Return `Page::QuotaPaused` instead of fetching while fewer than `reserve` tokens remain
+    Yield { reserve: f64 },
+    /// While fewer than `reserve` tokens remain, wait `interval` before each page
+    Throttle { reserve: f64, interval: Duration },
+}
+
+/// One step of a paginated walk
+#[derive(Debug, Clone, PartialEq)]
+pub enum Page<T> {
+    Items(Vec<T>),
+    /// Quota is low; call `next_page` again after `retry_after` to continue where this left off
+    QuotaPaused { retry_after: Duration },
+    /// The last page has been returned
+    Done,
+}
+
+/// Paged walk over a collection. Each page is one rate-limited request; a page
+/// shorter than the page size ends the walk.
+#[derive(Debug)]
+pub struct Pager<'c, T, F> {
+    client: &'c YahooFantasyClient,
+    fetch: F,
+    page_size: u32,
+    start: u32,
+    done: bool,
+    quota: QuotaPolicy,
+    _items: PhantomData<fn() -> T>,
+}
+
+impl<'c, T, F, Fut> Pager<'c, T, F>
+where
+    F: FnMut(u32, u32) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+{
+    /// Pager calling `fetch(start, count)` for each page
+    pub fn new(client: &'c YahooFantasyClient, fetch: F) -> Self {
+        Self {
+            client,
+            fetch,
+            page_size: DEFAULT_PAGE_SIZE,
+            start: 0,
+            done: false,
+            quota: QuotaPolicy::Wait,
+            _items: PhantomData,
+        }
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    pub fn quota(mut self, quota: QuotaPolicy) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    /// Offset of the next page
+    pub fn position(&self) -> u32 {
+        self.start
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Fetch the next page. A failed page is not skipped: calling again retries it.
+    pub async fn next_page(&mut self) -> Result<Page<T>, Box<dyn std::error::Error + Send + Sync>> {
+        if self.done {
+            return Ok(Page::Done);
+        }
+
+        let limiter = self.client.rate_limiter();
+        match self.quota {
+            QuotaPolicy::Wait => {}
+            QuotaPolicy::Yield { reserve } => {
+                let shortfall = reserve - limiter.get_remaining_tokens();
+                if shortfall > 0.0 || !limiter.can_make_request() {
+                    let refill = Duration::try_from_secs_f64(shortfall.max(0.0) / limiter.refill_rate()).unwrap_or(Duration::MAX);
+                    return Ok(Page::QuotaPaused {
+                        retry_after: refill.max(limiter.time_until_available()),
+                    });
+                }
+            }
+            QuotaPolicy::Throttle { reserve, interval } => {
+                if limiter.get_remaining_tokens() < reserve {
+                    tokio::time::sleep(interval).await;
+                }
+            }
+        }
+
+        // `fetch` sends its request through the client, which waits for and records the tokens
+        let items = (self.fetch)(self.start, self.page_size).await?;
+
+        self.start += items.len() as u32;
+        self.done = (items.len() as u32) < self.page_size;
+        Ok(if items.is_empty() { Page::Done } else { Page::Items(items) })
+    }
+
+    /// Fetch every remaining page, waiting out any quota pauses
+    pub async fn collect(mut self) -> Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut all = Vec::new();
+        loop {
+            match self.next_page().await? {
+                Page::Items(items) => all.extend(items),
+                Page::QuotaPaused { retry_after } => tokio::time::sleep(retry_after).await,
+                Page::Done => return Ok(all),
+            }
+        }
+    }
+}
+
+impl YahooFantasyClient {
+    /// Walk a paged collection, calling `fetch(start, count)` for each page
+    pub fn paginate<T, F, Fut>(&self, fetch: F) -> Pager<'_, T, F>
+    where
+        F: FnMut(u32, u32) -> Fut,
+        Fut: Future<Output = Result<Vec<T>, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        Pager::new(self, fetch)
+    }
+}
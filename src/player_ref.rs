@@ -0,0 +1,301 @@
+//! Borrowed player views
+//! Reads players collections straight out of the response buffer, for bulk analytics over thousands of players
+
+use std::borrow::Cow;
+use std::fmt;
+
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+
+use crate::{PlayerPoints, Points, RosterPlayer, YahooFantasyClient};
+
+/// A players collection response kept as one buffer. `players` borrows from it
+/// instead of allocating a `String` per field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerCollection {
+    body: String,
+}
+
+impl PlayerCollection {
+    /// Wrap a `format=json` response body
+    pub fn new(body: String) -> Self {
+        Self { body }
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Every player in the response's `players` collections, in order. Text is
+    /// borrowed from the buffer unless it has escapes to decode.
+    pub fn players(&self) -> Result<Vec<PlayerRef<'_>>, serde_json::Error> {
+        let mut players = Vec::new();
+        let mut deserializer = serde_json::Deserializer::from_str(&self.body);
+        Fragments(&mut Root(&mut players)).deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(players)
+    }
+}
+
+/// A player as it appears in a players collection, borrowing its text from the response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerRef<'a> {
+    pub player_key: Cow<'a, str>,
+    pub name: Cow<'a, str>,
+    /// NFL, NBA, etc. team abbreviation
+    pub editorial_team_abbr: Option<Cow<'a, str>>,
+    pub display_position: Option<Cow<'a, str>>,
+    pub eligible_positions: Vec<Cow<'a, str>>,
+    /// Slot the player is in, for roster collections
+    pub selected_position: Option<Cow<'a, str>>,
+    /// Injury or availability designation; `None` when healthy
+    pub status: Option<Cow<'a, str>>,
+    /// Fantasy points, when the request asked for them
+    pub points: Option<Points>,
+}
+
+impl PlayerRef<'_> {
+    /// Copy the borrowed text so the player outlives the response
+    pub fn into_owned(self) -> PlayerRef<'static> {
+        let own = |text: Cow<'_, str>| Cow::Owned(text.into_owned());
+        PlayerRef {
+            player_key: own(self.player_key),
+            name: own(self.name),
+            editorial_team_abbr: self.editorial_team_abbr.map(own),
+            display_position: self.display_position.map(own),
+            eligible_positions: self.eligible_positions.into_iter().map(own).collect(),
+            selected_position: self.selected_position.map(own),
+            status: self.status.map(own),
+            points: self.points,
+        }
+    }
+
+    /// The standard roster model. Players collections don't say whether the
+    /// player's team has a game, so `has_game` is assumed.
+    pub fn to_roster_player(&self) -> RosterPlayer {
+        RosterPlayer {
+            player_key: self.player_key.to_string(),
+            name: self.name.to_string(),
+            eligible_positions: self.eligible_positions.iter().map(|p| p.to_string()).collect(),
+            selected_position: self.selected_position.as_deref().unwrap_or("BN").to_string(),
+            status: self.status.as_ref().map(|s| s.to_string()),
+            has_game: true,
+        }
+    }
+
+    /// The player's points, zero if the response had none
+    pub fn to_player_points(&self) -> PlayerPoints {
+        PlayerPoints {
+            player_key: self.player_key.to_string(),
+            points: self.points.unwrap_or(Points::ZERO),
+        }
+    }
+}
+
+impl YahooFantasyClient {
+    /// GET a resource holding a players collection, e.g.
+    /// `league/{league_key}/players;status=FA/stats`, keeping the response for `PlayerCollection::players`
+    pub async fn get_player_collection(&self, path: &str) -> Result<PlayerCollection, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(PlayerCollection::new(self.execute(&self.json_request(path)).await?))
+    }
+}
+
+/// String that borrows from the input when it has no escapes
+#[derive(Deserialize)]
+pub(crate) struct Text<'a>(#[serde(borrow)] pub(crate) Cow<'a, str>);
+
+#[derive(Deserialize)]
+pub(crate) struct Name<'a> {
+    #[serde(borrow)]
+    pub(crate) full: Cow<'a, str>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EligiblePosition<'a> {
+    #[serde(borrow)]
+    pub(crate) position: Cow<'a, str>,
+}
+
+/// Yahoo's `player_points` block, whose total is a string
+#[derive(Deserialize)]
+pub(crate) struct PlayerPointsBlock {
+    total: Total,
+}
+
+impl PlayerPointsBlock {
+    pub(crate) fn points(&self) -> Points {
+        self.total.0
+    }
+}
+
+/// A number Yahoo may send as a string
+struct Total(Points);
+
+impl<'de> Deserialize<'de> for Total {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TotalVisitor;
+
+        impl Visitor<'_> for TotalVisitor {
+            type Value = Total;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a number or numeric string")
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Total, E> {
+                Points::from_f64(v).map(Total).ok_or_else(|| E::custom(format!("points total {} is out of range", v)))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Total, E> {
+                v.to_string().parse().map(Total).map_err(E::custom)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Total, E> {
+                v.to_string().parse().map(Total).map_err(E::custom)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Total, E> {
+                v.trim().parse().map(Total).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(TotalVisitor)
+    }
+}
+
+/// Handles the fields of a resource, which Yahoo splits across nested arrays of small objects
+pub(crate) trait Fields<'de> {
+    fn field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error>;
+}
+
+/// Walks a resource's fragments, passing every field to a `Fields`
+pub(crate) struct Fragments<'s, F>(pub(crate) &'s mut F);
+
+impl<'de, F: Fields<'de>> DeserializeSeed<'de> for Fragments<'_, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, F: Fields<'de>> Visitor<'de> for Fragments<'_, F> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a Yahoo resource")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq.next_element_seed(Fragments(&mut *self.0))?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(Text(key)) = map.next_key()? {
+            self.0.field(&key, &mut map)?;
+        }
+        Ok(())
+    }
+
+    // Scalars between fragments carry no fields
+    fn visit_bool<E: serde::de::Error>(self, _: bool) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, _: i64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, _: u64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_f64<E: serde::de::Error>(self, _: f64) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_str<E: serde::de::Error>(self, _: &str) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: serde::de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+/// Receives each `player` resource found in a response
+pub(crate) trait PlayerSink<'de> {
+    fn player<A: MapAccess<'de>>(&mut self, map: &mut A) -> Result<(), A::Error>;
+}
+
+impl<'de> PlayerSink<'de> for Vec<PlayerRef<'de>> {
+    fn player<A: MapAccess<'de>>(&mut self, map: &mut A) -> Result<(), A::Error> {
+        let mut player = PlayerRef::default();
+        map.next_value_seed(Fragments(&mut player))?;
+        self.push(player);
+        Ok(())
+    }
+}
+
+/// Searches the whole response for `players` collections
+pub(crate) struct Root<'s, S>(pub(crate) &'s mut S);
+
+impl<'de, S: PlayerSink<'de>> Fields<'de> for Root<'_, S> {
+    fn field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error> {
+        match key {
+            "players" => map.next_value_seed(Fragments(&mut Collection(&mut *self.0))),
+            _ => map.next_value_seed(Fragments(self)),
+        }
+    }
+}
+
+/// A players collection: `{"0": {"player": [...]}, "1": ..., "count": 2}`
+struct Collection<'s, S>(&'s mut S);
+
+impl<'de, S: PlayerSink<'de>> Fields<'de> for Collection<'_, S> {
+    fn field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error> {
+        match key {
+            "player" => self.0.player(map),
+            "count" => map.next_value::<IgnoredAny>().map(drop),
+            // Index keys wrap each player
+            _ => map.next_value_seed(Fragments(self)),
+        }
+    }
+}
+
+impl<'de> Fields<'de> for PlayerRef<'de> {
+    fn field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error> {
+        match key {
+            "player_key" => self.player_key = map.next_value::<Text>()?.0,
+            "name" => self.name = map.next_value::<Name>()?.full,
+            "editorial_team_abbr" => self.editorial_team_abbr = Some(map.next_value::<Text>()?.0),
+            "display_position" => self.display_position = Some(map.next_value::<Text>()?.0),
+            "eligible_positions" => {
+                self.eligible_positions = map.next_value::<Vec<EligiblePosition>>()?.into_iter().map(|p| p.position).collect();
+            }
+            "status" => self.status = Some(map.next_value::<Text>()?.0).filter(|s| !s.is_empty()),
+            "selected_position" => map.next_value_seed(Fragments(&mut SelectedPosition(&mut self.selected_position)))?,
+            "player_points" => self.points = Some(map.next_value::<PlayerPointsBlock>()?.points()),
+            _ => {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `selected_position` fragments: coverage, then the slot
+pub(crate) struct SelectedPosition<'p, 'de>(pub(crate) &'p mut Option<Cow<'de, str>>);
+
+impl<'de> Fields<'de> for SelectedPosition<'_, 'de> {
+    fn field<A: MapAccess<'de>>(&mut self, key: &str, map: &mut A) -> Result<(), A::Error> {
+        match key {
+            "position" => *self.0 = Some(map.next_value::<Text>()?.0),
+            _ => {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
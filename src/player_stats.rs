@@ -0,0 +1,182 @@
+//! Player stats by coverage window
+//! Season, week, date, and rolling last-week/last-month stats, and free agents ranked by any of them.
+//! NFL kickers, team defenses, and individual defensive players get their own stat sets.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+
+use crate::batch::{self, BatchResult, Keyed};
+use crate::{response, FreeAgent, PlayerStats, Points, Position, StatCoverage, YahooFantasyClient};
+
+/// Which free agents to list and how to rank them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FreeAgentSearch {
+    /// Only players eligible at this position, e.g. "C", "K", "DEF" (or "D/ST"), or "DL"
+    pub position: Option<String>,
+    /// Window whose fantasy points order the results, best first
+    pub rank_by: StatCoverage,
+    pub count: u32,
+}
+
+impl Default for FreeAgentSearch {
+    fn default() -> Self {
+        Self {
+            position: None,
+            rank_by: StatCoverage::Season,
+            count: 25,
+        }
+    }
+}
+
+impl YahooFantasyClient {
+    /// Get a player's stats over a coverage window
+    pub async fn get_player_stats(&self, player_key: &str, coverage: &StatCoverage) -> Result<PlayerStats, Box<dyn std::error::Error + Send + Sync>> {
+        let names = self.stat_names(game_key(player_key)).await?;
+        let body = self.get_json(&format!("player/{}/stats;{}", player_key, coverage.param())).await?;
+        let player = response::resource(&response::content(&body)?["player"]);
+        let (stats, points) = stat_values(&player, &names);
+        Ok(PlayerStats {
+            player_key: response::require(&player, "player_key", "player")?,
+            coverage: *coverage,
+            stats,
+            points,
+        })
+    }
+
+    /// Get many players' stats over a coverage window, batched into as many requests as needed
+    pub async fn get_players_by_keys(&self, player_keys: &[String], coverage: &StatCoverage) -> BatchResult<PlayerStats> {
+        self.fetch_many(player_keys, |chunk| async move {
+            let mut names = HashMap::new();
+            for game in chunk.iter().map(|key| game_key(key)) {
+                if !names.contains_key(game) {
+                    names.insert(game, self.stat_names(game).await?);
+                }
+            }
+            let body = self.get_json(&format!("players;{}/stats;{}", batch::key_selector("player_keys", &chunk), coverage.param())).await?;
+            response::collection(response::member(&response::resource(response::content(&body)?), "players"), "player")
+                .iter()
+                .map(|player| {
+                    let player_key: String = response::require(player, "player_key", "player")?;
+                    // Stat names come from the requested key's game, which may be a code
+                    let game = chunk.iter().find(|key| batch::same_resource(&player_key, key, ".p.")).map_or(game_key(&player_key), |key| game_key(key));
+                    let (stats, points) = stat_values(player, names.get(game).unwrap_or(&HashMap::new()));
+                    Ok(PlayerStats {
+                        player_key,
+                        coverage: *coverage,
+                        stats,
+                        points,
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    /// Free agents in a league ranked by fantasy points over `search.rank_by`,
+    /// with their stats for that window, in one request
+    pub async fn search_free_agents(&self, league_key: &str, search: &FreeAgentSearch) -> Result<Vec<FreeAgent>, Box<dyn std::error::Error + Send + Sync>> {
+        // Compare parsed positions so aliases like "D/ST" find team defenses and
+        // covering positions like "DL" or "D" find the defensive players they cover
+        let wanted = search.position.as_deref().and_then(|p| p.parse::<Position>().ok());
+        let position = wanted.as_ref().map(|p| format!(";position={}", p)).unwrap_or_default();
+        let names = self.stat_names(game_key(league_key)).await?;
+        let body = self
+            .get_json(&format!(
+                "league/{}/players;status=FA{};sort=PTS;{};count={}/stats;{}",
+                league_key,
+                position,
+                search.rank_by.sort_param(),
+                search.count,
+                search.rank_by.param()
+            ))
+            .await?;
+        let league = response::resource(&response::content(&body)?["league"]);
+
+        let mut players = Vec::new();
+        for player in response::collection(response::member(&league, "players"), "player") {
+            let (stats, points) = stat_values(&player, &names);
+            let player_key: String = response::require(&player, "player_key", "player")?;
+            players.push(FreeAgent {
+                name: response::member(&player, "name")["full"].as_str().unwrap_or_default().to_string(),
+                eligible_positions: eligible_positions(&player),
+                stats: PlayerStats {
+                    player_key: player_key.clone(),
+                    coverage: search.rank_by,
+                    stats,
+                    points,
+                },
+                player_key,
+            });
+        }
+        players.retain(|p| wanted.as_ref().is_none_or(|pos| p.eligible_positions.iter().any(|e| e.parse::<Position>().is_ok_and(|e| e.fills(pos)))));
+        players.sort_by_key(|p| Reverse(p.stats.points));
+        players.truncate(search.count as usize);
+        Ok(players)
+    }
+
+    /// Stat abbreviations by stat ID for a game, e.g. 4 => "Pass Yds".
+    /// Cached for a day; a game's categories don't change mid-season.
+    pub(crate) async fn stat_names(&self, game_key: &str) -> Result<HashMap<u32, String>, Box<dyn std::error::Error + Send + Sync>> {
+        let cache_key = format!("stat_names:{}", game_key);
+        if let Some(cached) = self.cache().get(&cache_key) {
+            return Ok(serde_json::from_str(&cached)?);
+        }
+        let body = self.get_json(&format!("game/{}/stat_categories", game_key)).await?;
+        let game = response::resource(&response::content(&body)?["game"]);
+        let names: HashMap<u32, String> = crate::stat_entries(&game, "stat_categories")
+            .into_iter()
+            .filter_map(|stat| Some((response::field(&stat, "stat_id")?, response::field(&stat, "display_name")?)))
+            .collect();
+        self.cache().put_with_ttl(cache_key, serde_json::to_string(&names)?, Duration::from_secs(24 * 60 * 60));
+        Ok(names)
+    }
+}
+
+impl Keyed for PlayerStats {
+    fn key(&self) -> &str {
+        &self.player_key
+    }
+
+    /// Player keys may name their game by code, e.g. `nfl.p.30977`
+    fn answers(&self, key: &str) -> bool {
+        batch::same_resource(&self.player_key, key, ".p.")
+    }
+}
+
+/// Game part of a player or league key, e.g. "nfl" for "nfl.p.30977" or "449" for "449.l.1234"
+pub(crate) fn game_key(key: &str) -> &str {
+    key.split('.').next().unwrap_or(key)
+}
+
+/// Positions from a player's `eligible_positions`, `[{"position": "PG"}, ...]`
+pub(crate) fn eligible_positions(player: &Map<String, Value>) -> Vec<String> {
+    response::member(player, "eligible_positions")
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry["position"].as_str().map(str::to_string))
+        .collect()
+}
+
+/// A player resource's `player_stats`, keyed by abbreviation (or the stat ID
+/// when the game has no such category), and its `player_points` total, zero
+/// when absent. Stats Yahoo reports as "-" are left out.
+pub(crate) fn stat_values(player: &Map<String, Value>, names: &HashMap<u32, String>) -> (BTreeMap<String, f64>, Points) {
+    let stats = response::resource(response::member(player, "player_stats"))
+        .get("stats")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.get("stat").map(response::resource))
+        .filter_map(|stat| {
+            let id: u32 = response::field(&stat, "stat_id")?;
+            let value = response::field(&stat, "value")?;
+            Some((names.get(&id).cloned().unwrap_or_else(|| id.to_string()), value))
+        })
+        .collect();
+    let points = response::field(&response::resource(response::member(player, "player_points")), "total").unwrap_or(Points::ZERO);
+    (stats, points)
+}
@@ -0,0 +1,356 @@
+//! Rate-limit-aware and game-time-aware polling
+//! Spreads the request budget across registered pollers so their combined load stays within quota
+
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{CancellationToken, RateLimiter, YahooFantasyClient};
+
+/// Fraction of the refill rate kept free for ad-hoc requests
+const DEFAULT_HEADROOM: f64 = 0.2;
+
+/// Below this fraction of the bucket, polling slows down proportionally
+const LOW_QUOTA_THRESHOLD: f64 = 0.25;
+
+/// Shortest wait between re-plans while a watch waits for its next poll
+const MIN_REPLAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Something that polls the API on an interval (scoreboard, transactions, draft results)
+#[derive(Debug, Clone)]
+pub struct PollTarget {
+    pub name: String,
+    /// Interval the poller would like when quota is plentiful
+    pub desired: Duration,
+    /// Never poll faster than this
+    pub min_interval: Duration,
+    /// Never poll slower than this, even if it exceeds the budget
+    pub max_interval: Duration,
+    /// Requests sent per poll
+    pub requests_per_poll: u32,
+}
+
+impl PollTarget {
+    pub fn new(name: &str, desired: Duration) -> Self {
+        Self {
+            name: name.to_string(),
+            desired,
+            min_interval: desired,
+            max_interval: desired * 20,
+            requests_per_poll: 1,
+        }
+    }
+
+    pub fn bounds(mut self, min_interval: Duration, max_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self.max_interval = max_interval;
+        self
+    }
+
+    pub fn requests_per_poll(mut self, requests: u32) -> Self {
+        self.requests_per_poll = requests.max(1);
+        self
+    }
+
+    fn desired_rate(&self) -> f64 {
+        self.requests_per_poll as f64 / self.desired.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Planned interval for one poll target
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedInterval {
+    pub name: String,
+    pub interval: Duration,
+}
+
+/// Result of a planning pass
+#[derive(Debug, Clone)]
+pub struct PollingPlan {
+    pub intervals: Vec<PlannedInterval>,
+    /// Requests per second the plan will generate
+    pub planned_rate: f64,
+    /// Requests per second available to pollers
+    pub budget_rate: f64,
+}
+
+impl PollingPlan {
+    pub fn interval_for(&self, name: &str) -> Option<Duration> {
+        self.intervals.iter().find(|p| p.name == name).map(|p| p.interval)
+    }
+
+    /// True if `max_interval` bounds forced the plan above budget
+    pub fn is_over_budget(&self) -> bool {
+        self.planned_rate > self.budget_rate + f64::EPSILON
+    }
+}
+
+/// Computes per-target polling intervals from the remaining quota
+#[derive(Debug, Clone)]
+pub struct PollingPlanner {
+    targets: Vec<PollTarget>,
+    headroom: f64,
+}
+
+impl Default for PollingPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PollingPlanner {
+    pub fn new() -> Self {
+        Self {
+            targets: Vec::new(),
+            headroom: DEFAULT_HEADROOM,
+        }
+    }
+
+    /// Fraction (0.0-1.0) of the refill rate reserved for non-polling requests
+    pub fn with_headroom(mut self, headroom: f64) -> Self {
+        self.headroom = headroom.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Register a poll target, replacing any existing one with the same name
+    pub fn register(&mut self, target: PollTarget) {
+        self.unregister(&target.name);
+        self.targets.push(target);
+    }
+
+    pub fn unregister(&mut self, name: &str) {
+        self.targets.retain(|t| t.name != name);
+    }
+
+    pub fn targets(&self) -> &[PollTarget] {
+        &self.targets
+    }
+
+    /// Plan against the current state of `limiter`
+    pub fn plan_for(&self, limiter: &RateLimiter) -> PollingPlan {
+        self.plan(limiter.get_remaining_tokens(), limiter.max_tokens(), limiter.refill_rate())
+    }
+
+    /// Plan given the remaining tokens, bucket size, and refill rate (tokens/sec).
+    /// Re-run whenever quota or the registered targets change.
+    pub fn plan(&self, remaining: f64, max_tokens: f64, refill_rate: f64) -> PollingPlan {
+        let mut budget_rate = refill_rate * (1.0 - self.headroom);
+        let fill = if max_tokens > 0.0 { remaining / max_tokens } else { 0.0 };
+        if fill < LOW_QUOTA_THRESHOLD {
+            budget_rate *= (fill / LOW_QUOTA_THRESHOLD).max(0.1);
+        }
+
+        let desired_rate: f64 = self.targets.iter().map(PollTarget::desired_rate).sum();
+        let scale = if desired_rate > budget_rate && desired_rate > 0.0 {
+            desired_rate / budget_rate.max(f64::EPSILON)
+        } else {
+            1.0
+        };
+
+        let mut planned_rate = 0.0;
+        let intervals = self
+            .targets
+            .iter()
+            .map(|target| {
+                let scaled = Duration::try_from_secs_f64(target.desired.as_secs_f64() * scale).unwrap_or(Duration::MAX);
+                let interval = scaled.clamp(target.min_interval, target.max_interval.max(target.min_interval));
+                planned_rate += target.requests_per_poll as f64 / interval.as_secs_f64().max(f64::EPSILON);
+                PlannedInterval {
+                    name: target.name.clone(),
+                    interval,
+                }
+            })
+            .collect();
+
+        PollingPlan {
+            intervals,
+            planned_rate,
+            budget_rate,
+        }
+    }
+}
+
+/// Source of the "are games in progress?" signal
+pub enum LiveSignal {
+    /// Known game windows as (start, end) times, e.g. from schedule metadata
+    Windows(Vec<(SystemTime, SystemTime)>),
+    /// User-provided callback
+    Callback(Box<dyn Fn(SystemTime) -> bool + Send + Sync>),
+}
+
+impl std::fmt::Debug for LiveSignal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LiveSignal::Windows(windows) => f.debug_tuple("Windows").field(windows).finish(),
+            LiveSignal::Callback(_) => f.write_str("Callback(..)"),
+        }
+    }
+}
+
+/// Polls quickly while games are live and drops to a heartbeat otherwise
+#[derive(Debug)]
+pub struct GameTimeSchedule {
+    live_interval: Duration,
+    idle_interval: Duration,
+    signal: LiveSignal,
+}
+
+impl GameTimeSchedule {
+    pub fn new(live_interval: Duration, idle_interval: Duration, signal: LiveSignal) -> Self {
+        Self {
+            live_interval,
+            idle_interval,
+            signal,
+        }
+    }
+
+    /// Schedule driven by known game windows
+    pub fn from_windows(live_interval: Duration, idle_interval: Duration, windows: Vec<(SystemTime, SystemTime)>) -> Self {
+        Self::new(live_interval, idle_interval, LiveSignal::Windows(windows))
+    }
+
+    /// Schedule driven by a callback returning whether games are live at the given time
+    pub fn from_callback<F>(live_interval: Duration, idle_interval: Duration, callback: F) -> Self
+    where
+        F: Fn(SystemTime) -> bool + Send + Sync + 'static,
+    {
+        Self::new(live_interval, idle_interval, LiveSignal::Callback(Box::new(callback)))
+    }
+
+    pub fn is_live(&self, now: SystemTime) -> bool {
+        match &self.signal {
+            LiveSignal::Windows(windows) => windows.iter().any(|(start, end)| *start <= now && now < *end),
+            LiveSignal::Callback(callback) => callback(now),
+        }
+    }
+
+    /// Delay before the next poll. While idle, the heartbeat is shortened so the
+    /// first poll lands when the next known game window opens.
+    pub fn next_interval(&self, now: SystemTime) -> Duration {
+        if self.is_live(now) {
+            return self.live_interval;
+        }
+
+        let until_next_game = match &self.signal {
+            LiveSignal::Windows(windows) => windows
+                .iter()
+                .filter_map(|(start, _)| start.duration_since(now).ok())
+                .min(),
+            LiveSignal::Callback(_) => None,
+        };
+
+        match until_next_game {
+            Some(wait) => wait.clamp(self.live_interval, self.idle_interval.max(self.live_interval)),
+            None => self.idle_interval,
+        }
+    }
+
+    /// Poll target for the planner reflecting the current live state
+    pub fn poll_target(&self, name: &str, now: SystemTime) -> PollTarget {
+        let desired = self.next_interval(now);
+        PollTarget::new(name, desired).bounds(desired, self.idle_interval.max(desired))
+    }
+}
+
+/// One poller for `YahooFantasyClient::watch`. Its target is rebuilt from the
+/// schedule and re-planned against the rate limiter before every poll, so the
+/// interval tightens when games go live, relaxes when they end, and stretches
+/// as quota runs low. Watchers sharing a planner split the budget between them.
+#[derive(Debug, Clone)]
+pub struct PollWatcher {
+    name: String,
+    schedule: Arc<GameTimeSchedule>,
+    requests_per_poll: u32,
+    planner: Arc<Mutex<PollingPlanner>>,
+}
+
+impl PollWatcher {
+    /// Watcher with a planner of its own
+    pub fn new(name: &str, schedule: GameTimeSchedule) -> Self {
+        Self {
+            name: name.to_string(),
+            schedule: Arc::new(schedule),
+            requests_per_poll: 1,
+            planner: Arc::new(Mutex::new(PollingPlanner::new())),
+        }
+    }
+
+    /// Plan alongside the other watchers sharing `planner`
+    pub fn planner(mut self, planner: Arc<Mutex<PollingPlanner>>) -> Self {
+        self.planner = planner;
+        self
+    }
+
+    pub fn requests_per_poll(mut self, requests: u32) -> Self {
+        self.requests_per_poll = requests.max(1);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Register the target for `now` with the planner and plan against `limiter`
+    pub fn next_interval(&self, limiter: &RateLimiter, now: SystemTime) -> Duration {
+        let target = self.schedule.poll_target(&self.name, now).requests_per_poll(self.requests_per_poll);
+        let mut planner = self.planner.lock().unwrap();
+        planner.register(target);
+        planner
+            .plan_for(limiter)
+            .interval_for(&self.name)
+            .unwrap_or_else(|| self.schedule.next_interval(now))
+    }
+
+    /// How often a wait for the next poll is re-planned: the live interval, so
+    /// a game starting mid-wait brings the poll forward
+    fn replan_interval(&self) -> Duration {
+        self.schedule.live_interval.max(MIN_REPLAN_INTERVAL)
+    }
+}
+
+/// Removes a watcher's target from its planner when the watch ends
+struct Registration<'a>(&'a PollWatcher);
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.0.planner.lock().unwrap().unregister(&self.0.name);
+    }
+}
+
+impl YahooFantasyClient {
+    /// Call `poll` on `watcher`'s planned interval until `cancel` fires or the
+    /// client shuts down, passing each result to `on_result`; failed polls
+    /// don't end the watch. A poll in flight when the watch is cancelled is abandoned.
+    pub async fn watch<T, F, Fut>(
+        &self,
+        watcher: &PollWatcher,
+        cancel: &CancellationToken,
+        mut poll: F,
+        mut on_result: impl FnMut(Result<T, Box<dyn std::error::Error + Send + Sync>>),
+    ) where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        let _registration = Registration(watcher);
+        while !cancel.is_cancelled() && !self.is_shut_down() {
+            let polled_at = Instant::now();
+            tokio::select! {
+                result = poll() => on_result(result),
+                _ = cancel.cancelled() => return,
+                _ = self.shutdown.cancelled() => return,
+            }
+            loop {
+                let interval = watcher.next_interval(&self.rate_limiter, SystemTime::now());
+                let wait = (polled_at + interval).saturating_duration_since(Instant::now());
+                if wait.is_zero() {
+                    break;
+                }
+                tokio::select! {
+                    _ = tokio::time::sleep(wait.min(watcher.replan_interval())) => {}
+                    _ = cancel.cancelled() => return,
+                    _ = self.shutdown.cancelled() => return,
+                }
+            }
+        }
+    }
+}
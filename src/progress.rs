@@ -0,0 +1,91 @@
+//! Progress reporting
+//! Snapshots of how far a long multi-request operation has got, for progress bars and job status
+
+use std::time::{Duration, Instant};
+
+/// Where a long operation is, passed to its progress callback before each
+/// request and once more when it finishes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// Resources fetched so far
+    pub completed: u32,
+    /// Resources the operation expects to fetch; revised if it learns more along the way
+    pub total: u32,
+    /// Resource being fetched; `None` once the operation has finished
+    pub current: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    pub fn remaining(&self) -> u32 {
+        self.total.saturating_sub(self.completed)
+    }
+
+    /// Completed share of the work, from 0.0 to 1.0
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.completed as f64 / self.total as f64).min(1.0)
+        }
+    }
+
+    /// Time left at the average pace so far; `None` until something has completed
+    pub fn eta(&self) -> Option<Duration> {
+        if self.completed == 0 {
+            return None;
+        }
+        Some(self.elapsed.mul_f64(self.remaining() as f64 / self.completed as f64))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current.is_none()
+    }
+}
+
+/// Counts completed resources and feeds snapshots to a callback
+pub(crate) struct ProgressTracker<P> {
+    completed: u32,
+    total: u32,
+    started: Instant,
+    on_progress: P,
+}
+
+impl<P: FnMut(&Progress)> ProgressTracker<P> {
+    pub(crate) fn new(total: u32, on_progress: P) -> Self {
+        Self {
+            completed: 0,
+            total,
+            started: Instant::now(),
+            on_progress,
+        }
+    }
+
+    pub(crate) fn set_total(&mut self, total: u32) {
+        self.total = total;
+    }
+
+    /// Report that `item` is about to be fetched
+    pub(crate) fn start(&mut self, item: impl Into<String>) {
+        self.report(Some(item.into()));
+    }
+
+    /// Count the current item as done
+    pub(crate) fn complete(&mut self) {
+        self.completed += 1;
+    }
+
+    pub(crate) fn finish(mut self) {
+        self.total = self.completed;
+        self.report(None);
+    }
+
+    fn report(&mut self, current: Option<String>) {
+        (self.on_progress)(&Progress {
+            completed: self.completed,
+            total: self.total,
+            current,
+            elapsed: self.started.elapsed(),
+        });
+    }
+}
@@ -0,0 +1,166 @@
+//! Persistent projection and valuation cache
+//! File-backed store keyed by (player, coverage, provider version), shared across consumers
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::projections::{ProjectionContext, ProjectionProvider};
+use crate::Points;
+
+/// What kind of value an entry holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    Projection,
+    Valuation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+struct EntryKey {
+    kind: ValueKind,
+    provider: String,
+    version: String,
+    player_key: String,
+    coverage: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    key: EntryKey,
+    value: Points,
+}
+
+/// Projection/valuation cache, optionally persisted to a JSON file
+#[derive(Debug, Default)]
+pub struct ValueCache {
+    path: Option<PathBuf>,
+    entries: Mutex<HashMap<EntryKey, Points>>,
+}
+
+impl ValueCache {
+    /// Cache that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the cache from `path` if it exists; `flush` writes it back
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str::<Vec<Entry>>(&json)?
+                .into_iter()
+                .map(|e| (e.key, e.value))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            entries: Mutex::new(entries),
+        })
+    }
+
+    pub fn get(&self, kind: ValueKind, provider: &dyn ProjectionProvider, context: &ProjectionContext) -> Option<Points> {
+        self.entries.lock().unwrap().get(&key(kind, provider, context)).copied()
+    }
+
+    pub fn put(&self, kind: ValueKind, provider: &dyn ProjectionProvider, context: &ProjectionContext, value: Points) {
+        self.entries.lock().unwrap().insert(key(kind, provider, context), value);
+    }
+
+    /// Drop entries from `provider` produced by any version other than its current one.
+    /// Returns the number of entries removed.
+    pub fn invalidate_stale(&self, provider: &dyn ProjectionProvider) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let before = entries.len();
+        entries.retain(|k, _| k.provider != provider.name() || k.version == provider.version());
+        before - entries.len()
+    }
+
+    /// Drop every entry from a provider, regardless of version
+    pub fn invalidate_provider(&self, provider_name: &str) {
+        self.entries.lock().unwrap().retain(|k, _| k.provider != provider_name);
+    }
+
+    /// Drop all cached values for a player (e.g. after injury news)
+    pub fn invalidate_player(&self, player_key: &str) {
+        self.entries.lock().unwrap().retain(|k, _| k.player_key != player_key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Write the cache to its file, atomically replacing the previous contents
+    pub fn flush(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let entries: Vec<Entry> = self
+            .entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| Entry {
+                key: key.clone(),
+                value: *value,
+            })
+            .collect();
+
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&entries)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+fn key(kind: ValueKind, provider: &dyn ProjectionProvider, context: &ProjectionContext) -> EntryKey {
+    EntryKey {
+        kind,
+        provider: provider.name().to_string(),
+        version: provider.version().to_string(),
+        player_key: context.player_key.clone(),
+        coverage: context.coverage.clone(),
+    }
+}
+
+/// Serves a provider's projections from a shared `ValueCache`
+pub struct Persisted<P> {
+    inner: P,
+    cache: Arc<ValueCache>,
+}
+
+impl<P: ProjectionProvider> Persisted<P> {
+    /// Wrap `inner`, discarding anything cached by older versions of it
+    pub fn new(inner: P, cache: Arc<ValueCache>) -> Self {
+        cache.invalidate_stale(&inner);
+        Self { inner, cache }
+    }
+}
+
+impl<P: ProjectionProvider> ProjectionProvider for Persisted<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn version(&self) -> &str {
+        self.inner.version()
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        if let Some(points) = self.cache.get(ValueKind::Projection, &self.inner, context) {
+            return Some(points);
+        }
+        let points = self.inner.project(context)?;
+        self.cache.put(ValueKind::Projection, &self.inner, context, points);
+        Some(points)
+    }
+}
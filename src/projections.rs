@@ -0,0 +1,381 @@
+//! Projection providers
+//! The `ProjectionProvider` chain and combinators that wrap it
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Points;
+
+/// What a projection is being asked for
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ProjectionContext {
+    pub player_key: String,
+    /// Primary position, e.g. "WR"
+    pub position: String,
+    /// Opposing editorial team for this coverage, if known
+    pub opponent: Option<String>,
+    /// Week or date coverage, e.g. "week:12" or "date:2024-05-01"
+    pub coverage: String,
+}
+
+/// Source of projected fantasy points
+pub trait ProjectionProvider: Send + Sync {
+    /// Identifies the provider
+    fn name(&self) -> &str;
+
+    /// Model version; bump it to invalidate persisted projections
+    fn version(&self) -> &str {
+        "1"
+    }
+
+    /// Projected points, or `None` if this provider has no opinion
+    fn project(&self, context: &ProjectionContext) -> Option<Points>;
+}
+
+impl<P: ProjectionProvider + ?Sized> ProjectionProvider for Box<P> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn version(&self) -> &str {
+        (**self).version()
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        (**self).project(context)
+    }
+}
+
+/// Fixed per-player projections, e.g. loaded from Yahoo or a spreadsheet
+#[derive(Debug, Clone, Default)]
+pub struct StaticProjections {
+    name: String,
+    points: HashMap<String, Points>,
+}
+
+impl StaticProjections {
+    pub fn new(name: &str, points: HashMap<String, Points>) -> Self {
+        Self {
+            name: name.to_string(),
+            points,
+        }
+    }
+}
+
+impl ProjectionProvider for StaticProjections {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        self.points.get(&context.player_key).copied()
+    }
+}
+
+/// Points a position scored against an opponent in one game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct OpponentGame {
+    pub opponent: String,
+    pub position: String,
+    pub points: f64,
+}
+
+/// Defense-vs-position strength: how much each opponent inflates or suppresses each position
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct DefenseVsPosition {
+    factors: HashMap<String, HashMap<String, f64>>,
+}
+
+impl DefenseVsPosition {
+    /// Opponent/position pairs with fewer games than this get no adjustment
+    pub const MIN_SAMPLES: usize = 3;
+    /// Adjustments are capped at ±30%
+    pub const MAX_ADJUSTMENT: f64 = 0.3;
+
+    /// Derive factors from aggregated game logs: points allowed to a position by an
+    /// opponent, relative to the league-wide average for that position
+    pub fn from_games(games: &[OpponentGame]) -> Self {
+        let mut by_position: HashMap<&str, (f64, usize)> = HashMap::new();
+        let mut by_pair: HashMap<(&str, &str), (f64, usize)> = HashMap::new();
+        for game in games {
+            let position = by_position.entry(game.position.as_str()).or_default();
+            position.0 += game.points;
+            position.1 += 1;
+            let pair = by_pair.entry((game.opponent.as_str(), game.position.as_str())).or_default();
+            pair.0 += game.points;
+            pair.1 += 1;
+        }
+
+        let mut factors: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        for ((opponent, position), (total, count)) in by_pair {
+            let (league_total, league_count) = by_position[position];
+            let league_average = league_total / league_count as f64;
+            if count < Self::MIN_SAMPLES || league_average <= 0.0 {
+                continue;
+            }
+            let factor = (total / count as f64 / league_average)
+                .clamp(1.0 - Self::MAX_ADJUSTMENT, 1.0 + Self::MAX_ADJUSTMENT);
+            factors
+                .entry(opponent.to_string())
+                .or_default()
+                .insert(position.to_string(), factor);
+        }
+
+        Self { factors }
+    }
+
+    /// Multiplier for a position facing `opponent`; 1.0 when unknown
+    pub fn factor(&self, opponent: &str, position: &str) -> f64 {
+        self.factors
+            .get(opponent)
+            .and_then(|positions| positions.get(position))
+            .copied()
+            .unwrap_or(1.0)
+    }
+}
+
+/// Scales another provider's projections by opponent strength
+pub struct OpponentAdjusted<P> {
+    inner: P,
+    defense: DefenseVsPosition,
+    name: String,
+}
+
+impl<P: ProjectionProvider> OpponentAdjusted<P> {
+    pub fn new(inner: P, defense: DefenseVsPosition) -> Self {
+        let name = format!("{}+opponent", inner.name());
+        Self { inner, defense, name }
+    }
+}
+
+impl<P: ProjectionProvider> ProjectionProvider for OpponentAdjusted<P> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        self.inner.version()
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        let baseline = self.inner.project(context)?;
+        let factor = context
+            .opponent
+            .as_deref()
+            .map_or(1.0, |opponent| self.defense.factor(opponent, &context.position));
+        baseline.scale(factor)
+    }
+}
+
+/// Weighted average of several providers; providers without a projection are
+/// skipped and the remaining weights renormalized
+pub struct Weighted {
+    providers: Vec<(Box<dyn ProjectionProvider>, f64)>,
+    name: String,
+    version: String,
+}
+
+impl Weighted {
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+            name: String::from("weighted()"),
+            version: String::new(),
+        }
+    }
+
+    pub fn with(mut self, provider: impl ProjectionProvider + 'static, weight: f64) -> Self {
+        self.providers.push((Box::new(provider), weight.max(0.0)));
+        let parts: Vec<String> = self
+            .providers
+            .iter()
+            .map(|(p, w)| format!("{}*{}", p.name(), w))
+            .collect();
+        self.name = format!("weighted({})", parts.join(","));
+        self.version = combined_version(self.providers.iter().map(|(p, _)| p));
+        self
+    }
+}
+
+impl Default for Weighted {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProjectionProvider for Weighted {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        let mut total = Points::ZERO;
+        let mut total_weight = 0.0;
+        for (provider, weight) in &self.providers {
+            if let Some(points) = provider.project(context) {
+                total += points.scale(*weight)?;
+                total_weight += weight;
+            }
+        }
+        (total_weight > 0.0).then(|| total.scale(1.0 / total_weight)).flatten()
+    }
+}
+
+/// First provider with a projection wins
+pub struct Fallback {
+    providers: Vec<Box<dyn ProjectionProvider>>,
+    name: String,
+    version: String,
+}
+
+impl Fallback {
+    pub fn new() -> Self {
+        Self {
+            providers: Vec::new(),
+            name: String::from("fallback()"),
+            version: String::new(),
+        }
+    }
+
+    pub fn then(mut self, provider: impl ProjectionProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        let parts: Vec<&str> = self.providers.iter().map(|p| p.name()).collect();
+        self.name = format!("fallback({})", parts.join(","));
+        self.version = combined_version(self.providers.iter());
+        self
+    }
+}
+
+impl Default for Fallback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProjectionProvider for Fallback {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        &self.version
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        self.providers.iter().find_map(|p| p.project(context))
+    }
+}
+
+/// Memoizes another provider's answers per context
+pub struct Cached<P> {
+    inner: P,
+    entries: Mutex<HashMap<ProjectionContext, Option<Points>>>,
+}
+
+impl<P: ProjectionProvider> Cached<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Forget all memoized projections
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+impl<P: ProjectionProvider> ProjectionProvider for Cached<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn version(&self) -> &str {
+        self.inner.version()
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        if let Some(cached) = self.entries.lock().unwrap().get(context) {
+            return *cached;
+        }
+        let projected = self.inner.project(context);
+        self.entries.lock().unwrap().insert(context.clone(), projected);
+        projected
+    }
+}
+
+/// Hand-entered projections that take precedence over another provider
+pub struct Overrides<P> {
+    inner: P,
+    overrides: HashMap<String, Points>,
+    name: String,
+}
+
+impl<P: ProjectionProvider> Overrides<P> {
+    pub fn new(inner: P) -> Self {
+        let name = format!("{}+overrides", inner.name());
+        Self {
+            inner,
+            overrides: HashMap::new(),
+            name,
+        }
+    }
+
+    /// Override a player's projection for every coverage
+    pub fn set(&mut self, player_key: &str, points: Points) {
+        self.overrides.insert(player_key.to_string(), points);
+    }
+
+    pub fn remove(&mut self, player_key: &str) {
+        self.overrides.remove(player_key);
+    }
+}
+
+impl<P: ProjectionProvider> ProjectionProvider for Overrides<P> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn version(&self) -> &str {
+        self.inner.version()
+    }
+
+    fn project(&self, context: &ProjectionContext) -> Option<Points> {
+        self.overrides
+            .get(&context.player_key)
+            .copied()
+            .or_else(|| self.inner.project(context))
+    }
+}
+
+/// Version of a composite provider: changes whenever any member's version does
+fn combined_version<'a>(providers: impl Iterator<Item = &'a Box<dyn ProjectionProvider>>) -> String {
+    providers.map(|p| p.version()).collect::<Vec<_>>().join("+")
+}
+
+/// Fluent constructors for the combinators
+pub trait ProjectionProviderExt: ProjectionProvider + Sized {
+    fn opponent_adjusted(self, defense: DefenseVsPosition) -> OpponentAdjusted<Self> {
+        OpponentAdjusted::new(self, defense)
+    }
+
+    fn cached(self) -> Cached<Self> {
+        Cached::new(self)
+    }
+
+    fn with_overrides(self) -> Overrides<Self> {
+        Overrides::new(self)
+    }
+}
+
+impl<P: ProjectionProvider> ProjectionProviderExt for P {}
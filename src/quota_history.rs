@@ -0,0 +1,129 @@
+//! Quota usage history
+//! Hourly request counts, persisted across runs, used to defer low-priority jobs to historically quiet hours
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Timelike, Utc};
+
+use crate::{BoxFuture, HttpResponse, Middleware, Next, Request};
+
+/// Days of hourly counts kept
+const RETENTION_DAYS: i64 = 28;
+
+const SECONDS_PER_HOUR: i64 = 60 * 60;
+
+/// How eagerly a job should run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobPriority {
+    /// Run as soon as possible
+    High,
+    /// Wait for the quietest window in the history, e.g. archival syncs and analytics refreshes
+    Low,
+}
+
+/// Requests sent per hour over the last few weeks, optionally persisted to a
+/// JSON file. Register it as middleware to count every request the client sends.
+#[derive(Debug, Default)]
+pub struct QuotaHistory {
+    path: Option<PathBuf>,
+    /// Requests per hour, keyed by Unix hour (seconds / 3600)
+    hours: Mutex<BTreeMap<i64, u32>>,
+}
+
+impl QuotaHistory {
+    /// History that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the history from `path` if it exists; `flush` writes it back
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let hours = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            hours: Mutex::new(hours),
+        })
+    }
+
+    /// Count `requests` sent at `at`, dropping hours older than the retention period
+    pub fn record(&self, at: &DateTime<impl TimeZone>, requests: u32) {
+        let hour = at.timestamp().div_euclid(SECONDS_PER_HOUR);
+        let mut hours = self.hours.lock().unwrap();
+        *hours.entry(hour).or_insert(0) += requests;
+        let oldest = hour - RETENTION_DAYS * 24;
+        hours.retain(|h, _| *h >= oldest);
+    }
+
+    /// Average requests in each UTC hour of the day, over the days in the history
+    pub fn hourly_profile(&self) -> [f64; 24] {
+        let hours = self.hours.lock().unwrap();
+        let mut profile = [0.0; 24];
+        let (Some(first), Some(last)) = (hours.keys().next(), hours.keys().next_back()) else {
+            return profile;
+        };
+        let days = ((last - first) / 24 + 1) as f64;
+        for (hour, requests) in hours.iter() {
+            profile[hour.rem_euclid(24) as usize] += *requests as f64 / days;
+        }
+        profile
+    }
+
+    /// UTC hour starting the `length`-hour window with the fewest requests on
+    /// average; ties go to the earliest hour. Windows wrap past midnight.
+    pub fn quietest_window(&self, length: u32) -> u32 {
+        let profile = self.hourly_profile();
+        let length = length.clamp(1, 24) as usize;
+        let load = |start: usize| (0..length).map(|i| profile[(start + i) % 24]).sum::<f64>();
+        (0..24).min_by(|a, b| load(*a).total_cmp(&load(*b))).unwrap_or(0) as u32
+    }
+
+    /// When a job of `priority` expected to take `length` hours should start, as of `now`.
+    /// Low-priority jobs start now if already inside the quietest window.
+    pub fn next_run(&self, priority: JobPriority, length: u32, now: &DateTime<Utc>) -> DateTime<Utc> {
+        if priority == JobPriority::High || self.hours.lock().unwrap().is_empty() {
+            return *now;
+        }
+        let start = self.quietest_window(length);
+        let current = now.hour();
+        if current == start {
+            return *now;
+        }
+        let hours_until = (start + 24 - current) % 24;
+        let top_of_hour = now.with_minute(0).and_then(|t| t.with_second(0)).and_then(|t| t.with_nanosecond(0)).unwrap_or(*now);
+        top_of_hour + ChronoDuration::hours(hours_until as i64)
+    }
+
+    /// Sleep until `next_run` for a job of `priority`
+    pub async fn wait_for_window(&self, priority: JobPriority, length: u32) {
+        let now = Utc::now();
+        let wait = (self.next_run(priority, length, &now) - now).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+
+    /// Write the history to its file, atomically replacing the previous contents
+    pub fn flush(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec(&*self.hours.lock().unwrap())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// Counts each request as it is sent
+impl Middleware for std::sync::Arc<QuotaHistory> {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        self.record(&Utc::now(), 1);
+        next.run(request)
+    }
+}
@@ -0,0 +1,290 @@
+//! Report rendering
+//! Renders report structures as Markdown, with user templates replacing the built-in prose per report
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::DateTime;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{AuditAction, AuditTrail, LineupRetro, Scoreboard, SettingsReport, Standings, StandingsMismatch, StandingsVerification};
+
+/// A report that can be rendered as Markdown. Templates see the report's serialized form.
+pub trait Report: Serialize {
+    /// Name a custom template for this report is registered under
+    const TEMPLATE: &'static str;
+
+    /// Built-in rendering, used when no template is registered
+    fn to_markdown(&self) -> String;
+}
+
+/// Renders a template source against report data
+pub trait TemplateEngine: Send + Sync {
+    fn render(&self, source: &str, data: &Value) -> Result<String, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Built-in engine: replaces `{{ path.to.field }}` with the value at that path
+/// (list elements by index, e.g. `{{ computed.0.team_key }}`). No loops or
+/// conditionals; enable the `handlebars` feature for those.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Placeholders;
+
+impl TemplateEngine for Placeholders {
+    fn render(&self, source: &str, data: &Value) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut out = String::with_capacity(source.len());
+        let mut rest = source;
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let end = rest[start..].find("}}").ok_or("unclosed `{{` in template")? + start;
+            let path = rest[start + 2..end].trim();
+            let value = path
+                .split('.')
+                .filter(|segment| !segment.is_empty())
+                .try_fold(data, |value, segment| match value {
+                    Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+                    _ => value.get(segment),
+                })
+                .ok_or_else(|| format!("no value at `{}`", path))?;
+            match value {
+                Value::String(s) => out.push_str(s),
+                other => out.push_str(&other.to_string()),
+            }
+            rest = &rest[end + 2..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+/// Handlebars engine, without HTML escaping since reports are Markdown
+#[cfg(feature = "handlebars")]
+#[derive(Debug, Clone)]
+pub struct HandlebarsEngine(handlebars::Handlebars<'static>);
+
+#[cfg(feature = "handlebars")]
+impl Default for HandlebarsEngine {
+    fn default() -> Self {
+        let mut registry = handlebars::Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        Self(registry)
+    }
+}
+
+#[cfg(feature = "handlebars")]
+impl HandlebarsEngine {
+    /// Registry for adding helpers and partials
+    pub fn registry_mut(&mut self) -> &mut handlebars::Handlebars<'static> {
+        &mut self.0
+    }
+}
+
+#[cfg(feature = "handlebars")]
+impl TemplateEngine for HandlebarsEngine {
+    fn render(&self, source: &str, data: &Value) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.0.render_template(source, data)?)
+    }
+}
+
+/// Renders reports with the built-in Markdown unless a template is registered for them
+pub struct ReportRenderer {
+    engine: Box<dyn TemplateEngine>,
+    templates: HashMap<String, String>,
+}
+
+impl Default for ReportRenderer {
+    fn default() -> Self {
+        Self::with_engine(Placeholders)
+    }
+}
+
+impl std::fmt::Debug for ReportRenderer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReportRenderer").field("templates", &self.templates).finish_non_exhaustive()
+    }
+}
+
+impl ReportRenderer {
+    /// Renderer using the built-in placeholder engine
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_engine(engine: impl TemplateEngine + 'static) -> Self {
+        Self {
+            engine: Box::new(engine),
+            templates: HashMap::new(),
+        }
+    }
+
+    /// Use `source` for reports whose `Report::TEMPLATE` is `name`
+    pub fn template(mut self, name: &str, source: impl Into<String>) -> Self {
+        self.templates.insert(name.to_string(), source.into());
+        self
+    }
+
+    pub fn render<R: Report>(&self, report: &R) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        match self.templates.get(R::TEMPLATE) {
+            Some(source) => self.engine.render(source, &serde_json::to_value(report)?),
+            None => Ok(report.to_markdown()),
+        }
+    }
+}
+
+impl Report for AuditTrail {
+    const TEMPLATE: &'static str = "audit";
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# Commissioner audit: {}\n\n", self.league_key);
+        if self.entries().is_empty() {
+            out.push_str("No commissioner actions recorded.\n");
+        }
+        for entry in self.entries() {
+            let when = DateTime::from_timestamp(entry.timestamp, 0).map_or_else(|| entry.timestamp.to_string(), |t| t.format("%Y-%m-%d %H:%M").to_string());
+            let what = match &entry.action {
+                AuditAction::RosterMove {
+                    transaction_key,
+                    added,
+                    dropped,
+                } => {
+                    let players = |moves: &[(String, String)]| moves.iter().map(|(player, _)| player.as_str()).collect::<Vec<_>>().join(", ");
+                    format!("Roster move `{}`: added [{}], dropped [{}]", transaction_key, players(added), players(dropped))
+                }
+                AuditAction::SettingChange { change } => format!("Setting changed: {}", change),
+                AuditAction::ScoreEdit { edit } => format!(
+                    "Score edit for {} in week {}: reported {}, recomputed {} ({:+})",
+                    edit.team_key,
+                    edit.week,
+                    edit.reported,
+                    edit.recomputed,
+                    edit.adjustment.to_f64()
+                ),
+            };
+            let _ = writeln!(out, "- {} — {}", when, what);
+        }
+        out
+    }
+}
+
+impl Report for SettingsReport {
+    const TEMPLATE: &'static str = "settings";
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# League settings: {}\n", self.league_key);
+        for section in &self.sections {
+            let _ = writeln!(out, "\n## {}\n\n| Setting | Value |\n| --- | --- |", section.title);
+            for entry in &section.entries {
+                let _ = writeln!(out, "| {} | {} |", cell(&entry.label), cell(&entry.value));
+            }
+        }
+        out
+    }
+}
+
+impl Report for Standings {
+    const TEMPLATE: &'static str = "standings";
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Standings: {} (week {})\n\n| Rank | Team | W-L-T | PF |\n| --- | --- | --- | --- |\n",
+            self.league_key, self.week
+        );
+        for team in &self.teams {
+            let status = match (team.clinched_playoffs, team.eliminated) {
+                (Some(true), _) => " (x)",
+                (_, Some(true)) => " (e)",
+                _ => "",
+            };
+            let _ = writeln!(
+                out,
+                "| {} | {}{} | {}-{}-{} | {} |",
+                team.rank,
+                cell(&team.name),
+                status,
+                team.wins,
+                team.losses,
+                team.ties,
+                team.points_for
+            );
+        }
+        out
+    }
+}
+
+/// Matchup preview: projections, plus the score once games have started
+impl Report for Scoreboard {
+    const TEMPLATE: &'static str = "scoreboard";
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# Week {} matchups: {}\n\n", self.week, self.league_key);
+        for matchup in &self.matchups {
+            let sides: Vec<String> = matchup
+                .teams
+                .iter()
+                .map(|team| format!("**{}** {} (proj. {})", team.name, team.points, team.projected_points))
+                .collect();
+            let _ = writeln!(out, "- {}", sides.join(" vs "));
+        }
+        out
+    }
+}
+
+impl Report for LineupRetro {
+    const TEMPLATE: &'static str = "lineup_retro";
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "# Week {} lineup review: {}\n\nScored {} of a possible {} ({:.0}% efficiency), leaving {} on the bench.\n\n## Best lineup\n\n",
+            self.week,
+            self.team_key,
+            self.actual,
+            self.optimal,
+            self.efficiency() * 100.0,
+            self.points_left_on_bench()
+        );
+        for (player_key, position) in &self.optimal_lineup {
+            let _ = writeln!(out, "- {}: {}", position, player_key);
+        }
+        out
+    }
+}
+
+impl Report for StandingsVerification {
+    const TEMPLATE: &'static str = "standings_verification";
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# Standings check: {}\n\n| Rank | Team | W-L-T | PF |\n| --- | --- | --- | --- |\n", self.league_key);
+        for standing in &self.computed {
+            let record = &standing.record;
+            let tie = if standing.unresolved_tie { " (tied)" } else { "" };
+            let _ = writeln!(
+                out,
+                "| {}{} | {} | {}-{}-{} | {} |",
+                standing.rank, tie, cell(&standing.team_key), record.wins, record.losses, record.ties, record.points_for
+            );
+        }
+        if self.is_consistent() {
+            out.push_str("\nYahoo's standings match.\n");
+            return out;
+        }
+        out.push_str("\n## Mismatches\n\n");
+        for mismatch in &self.mismatches {
+            let line = match mismatch {
+                StandingsMismatch::MissingTeam { team_key } => format!("{} appears in only one set of standings", team_key),
+                StandingsMismatch::Record { team_key, reported, computed } => format!(
+                    "{} record: Yahoo {}-{}-{}, computed {}-{}-{}",
+                    team_key, reported.0, reported.1, reported.2, computed.0, computed.1, computed.2
+                ),
+                StandingsMismatch::PointsFor { team_key, reported, computed } => format!("{} points for: Yahoo {}, computed {}", team_key, reported, computed),
+                StandingsMismatch::Rank { team_key, reported, computed } => format!("{} rank: Yahoo {}, computed {}", team_key, reported, computed),
+            };
+            let _ = writeln!(out, "- {}", line);
+        }
+        out
+    }
+}
+
+/// Table cell text, with pipes escaped so they don't split the cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
@@ -0,0 +1,162 @@
+//! Bulk multi-league research
+//! Fans a query out across many leagues under a strict request budget, with resumable checkpoints
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{CancellationToken, YahooFantasyClient};
+
+/// Progress of a crawl; serialize it to resume an interrupted crawl later
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct CrawlCheckpoint {
+    /// Leagues whose query completed; skipped on resume
+    pub completed: BTreeSet<String>,
+    /// Leagues whose query failed, with the error; retried on resume
+    pub failed: BTreeMap<String, String>,
+    /// Requests charged to this crawl so far, across resumes
+    pub requests_used: u32,
+}
+
+impl CrawlCheckpoint {
+    pub fn to_json(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Why a crawl stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrawlStop {
+    /// Every league was attempted
+    Finished,
+    /// The next league would exceed the request budget
+    BudgetExhausted,
+    /// The rate limiter fell below the reserved token floor
+    QuotaLow,
+    Cancelled,
+}
+
+/// Results of one `run`
+#[derive(Debug)]
+pub struct CrawlReport<T> {
+    pub results: Vec<(String, T)>,
+    pub stop: CrawlStop,
+    /// Leagues not yet completed (including failures)
+    pub remaining: usize,
+}
+
+/// A resumable fan-out of one query over a list of league keys
+#[derive(Debug, Clone)]
+pub struct Crawl {
+    league_keys: Vec<String>,
+    request_budget: Option<u32>,
+    requests_per_league: u32,
+    reserve_tokens: f64,
+    checkpoint: CrawlCheckpoint,
+}
+
+impl Crawl {
+    pub fn new(league_keys: Vec<String>) -> Self {
+        Self::resume(league_keys, CrawlCheckpoint::default())
+    }
+
+    /// Continue from a saved checkpoint
+    pub fn resume(league_keys: Vec<String>, checkpoint: CrawlCheckpoint) -> Self {
+        Self {
+            league_keys,
+            request_budget: None,
+            requests_per_league: 1,
+            reserve_tokens: 0.0,
+            checkpoint,
+        }
+    }
+
+    /// Total requests the crawl may use, across resumes
+    pub fn budget(mut self, max_requests: u32) -> Self {
+        self.request_budget = Some(max_requests);
+        self
+    }
+
+    /// Requests the query makes per league (e.g. 2 for settings + draft results)
+    pub fn requests_per_league(mut self, requests: u32) -> Self {
+        self.requests_per_league = requests.max(1);
+        self
+    }
+
+    /// Stop rather than wait when the rate limiter has fewer tokens than this
+    pub fn reserve_tokens(mut self, tokens: f64) -> Self {
+        self.reserve_tokens = tokens;
+        self
+    }
+
+    pub fn checkpoint(&self) -> &CrawlCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Run `query` for each league not yet completed. The checkpoint is updated
+    /// after every league, so it can be persisted from `on_progress`.
+    pub async fn run<'c, T, F, Fut, P>(&mut self, client: &'c YahooFantasyClient, cancel: &CancellationToken, mut query: F, mut on_progress: P) -> CrawlReport<T>
+    where
+        F: FnMut(&'c YahooFantasyClient, String) -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>>,
+        P: FnMut(&CrawlCheckpoint),
+    {
+        let mut results = Vec::new();
+        let mut stop = CrawlStop::Finished;
+
+        let pending: Vec<String> = self
+            .league_keys
+            .iter()
+            .filter(|key| !self.checkpoint.completed.contains(*key))
+            .cloned()
+            .collect();
+
+        for league_key in &pending {
+            if cancel.is_cancelled() {
+                stop = CrawlStop::Cancelled;
+                break;
+            }
+            if let Some(budget) = self.request_budget {
+                if self.checkpoint.requests_used + self.requests_per_league > budget {
+                    stop = CrawlStop::BudgetExhausted;
+                    break;
+                }
+            }
+            if client.rate_limiter().get_remaining_tokens() < self.reserve_tokens + self.requests_per_league as f64 {
+                stop = CrawlStop::QuotaLow;
+                break;
+            }
+
+            self.checkpoint.requests_used += self.requests_per_league;
+            match query(client, league_key.clone()).await {
+                Ok(result) => {
+                    self.checkpoint.failed.remove(league_key);
+                    self.checkpoint.completed.insert(league_key.clone());
+                    results.push((league_key.clone(), result));
+                }
+                Err(error) => {
+                    self.checkpoint.failed.insert(league_key.clone(), error.to_string());
+                }
+            }
+            on_progress(&self.checkpoint);
+        }
+
+        let remaining = self
+            .league_keys
+            .iter()
+            .filter(|key| !self.checkpoint.completed.contains(*key))
+            .count();
+
+        CrawlReport {
+            results,
+            stop,
+            remaining,
+        }
+    }
+}
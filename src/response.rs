@@ -0,0 +1,88 @@
+//! Yahoo JSON response helpers
+//! Flattens the `format=json` encoding of resources and collections into plain objects
+
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+/// Merge a resource's fragments into one object. Yahoo encodes a resource as an
+/// array of objects (and nested arrays of objects) that each hold a few fields.
+pub fn resource(value: &Value) -> Map<String, Value> {
+    let mut fields = Map::new();
+    merge(value, &mut fields);
+    fields
+}
+
+/// Resources in a collection such as `{"0": {"game": [...]}, "1": ..., "count": 2}`,
+/// in index order, each flattened with `resource`
+pub fn collection(value: &Value, singular: &str) -> Vec<Map<String, Value>> {
+    let Some(entries) = value.as_object() else {
+        return Vec::new();
+    };
+    let mut indexed: Vec<(usize, &Value)> = entries
+        .iter()
+        .filter_map(|(k, v)| Some((k.parse().ok()?, v.get(singular)?)))
+        .collect();
+    indexed.sort_by_key(|(i, _)| *i);
+    indexed.into_iter().map(|(_, v)| resource(v)).collect()
+}
+
+/// Root of a response body
+pub fn content(body: &Value) -> Result<&Value, Box<dyn std::error::Error + Send + Sync>> {
+    body.get("fantasy_content")
+        .ok_or_else(|| "response is missing fantasy_content".into())
+}
+
+/// Season of a game or league resource, if present. Payloads from before
+/// 2010 carry it as `year`.
+pub fn season(fields: &Map<String, Value>) -> Option<i32> {
+    match fields.get("season").or_else(|| fields.get("year"))? {
+        Value::String(raw) => raw.trim().parse().ok(),
+        Value::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+        _ => None,
+    }
+}
+
+/// A field of a flattened resource, or `null` when it's missing
+pub fn member<'a>(fields: &'a Map<String, Value>, name: &str) -> &'a Value {
+    fields.get(name).unwrap_or(&Value::Null)
+}
+
+/// A scalar parsed from text or a number; Yahoo sends most numbers as strings
+pub fn scalar<T: FromStr>(value: &Value) -> Option<T> {
+    match value {
+        Value::String(raw) => raw.trim().parse().ok(),
+        Value::Number(n) => n.to_string().parse().ok(),
+        _ => None,
+    }
+}
+
+/// A scalar field of a flattened resource, see `scalar`
+pub fn field<T: FromStr>(fields: &Map<String, Value>, name: &str) -> Option<T> {
+    fields.get(name).and_then(scalar)
+}
+
+/// Like `field`, failing with an error naming the resource when it's missing or malformed
+pub fn require<T: FromStr>(fields: &Map<String, Value>, name: &str, resource: &str) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+    field(fields, name).ok_or_else(|| format!("{} is missing {}", resource, name).into())
+}
+
+/// A flag Yahoo may send as `"1"`, `1`, or `true`
+pub fn flag(fields: &Map<String, Value>, name: &str) -> Option<bool> {
+    match fields.get(name)? {
+        Value::Bool(flag) => Some(*flag),
+        value => scalar::<u8>(value).map(|flag| flag != 0),
+    }
+}
+
+fn merge(value: &Value, fields: &mut Map<String, Value>) {
+    match value {
+        Value::Array(parts) => parts.iter().for_each(|part| merge(part, fields)),
+        Value::Object(object) => {
+            for (k, v) in object {
+                fields.insert(k.clone(), v.clone());
+            }
+        }
+        _ => {}
+    }
+}
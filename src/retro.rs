@@ -0,0 +1,197 @@
+//! Lineup retrospectives
+//! Compares started lineups to the best lineup in hindsight, per week and season-long
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::progress::ProgressTracker;
+use crate::xml::RosterCoverage;
+use crate::{response, PlayerPoints, Points, Progress, Roster, RosterSlot, YahooFantasyClient};
+
+/// One week's started lineup against the best one available in hindsight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LineupRetro {
+    pub team_key: String,
+    pub week: u32,
+    /// Points scored by the players actually started
+    pub actual: Points,
+    /// Points the best possible lineup would have scored
+    pub optimal: Points,
+    /// Best possible lineup, as (player_key, position)
+    pub optimal_lineup: Vec<(String, String)>,
+}
+
+impl LineupRetro {
+    /// Points the manager left on the bench
+    pub fn points_left_on_bench(&self) -> Points {
+        self.optimal - self.actual
+    }
+
+    /// `actual / optimal`; 1.0 when nothing could have scored
+    pub fn efficiency(&self) -> f64 {
+        efficiency(self.actual, self.optimal)
+    }
+}
+
+/// A team's lineup decisions aggregated over many weeks
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ManagerEfficiency {
+    pub team_key: String,
+    pub weeks: usize,
+    pub actual: Points,
+    pub optimal: Points,
+}
+
+impl ManagerEfficiency {
+    /// Aggregate weekly retrospectives per team, sorted by team key
+    pub fn from_retros(retros: &[LineupRetro]) -> Vec<Self> {
+        let mut by_team: BTreeMap<&str, Self> = BTreeMap::new();
+        for retro in retros {
+            let entry = by_team.entry(&retro.team_key).or_insert_with(|| Self {
+                team_key: retro.team_key.clone(),
+                weeks: 0,
+                actual: Points::ZERO,
+                optimal: Points::ZERO,
+            });
+            entry.weeks += 1;
+            entry.actual += retro.actual;
+            entry.optimal += retro.optimal;
+        }
+        by_team.into_values().collect()
+    }
+
+    pub fn points_left_on_bench(&self) -> Points {
+        self.optimal - self.actual
+    }
+
+    /// Season-long `actual / optimal`, so high-scoring weeks weigh more
+    pub fn efficiency(&self) -> f64 {
+        efficiency(self.actual, self.optimal)
+    }
+}
+
+fn efficiency(actual: Points, optimal: Points) -> f64 {
+    if optimal <= Points::ZERO {
+        1.0
+    } else {
+        actual.to_f64() / optimal.to_f64()
+    }
+}
+
+/// Best lineup in hindsight and its points. Slots are filled most restrictive
+/// first (flex slots last), each with the highest-scoring eligible player left,
+/// which is optimal when every flex slot accepts a superset of the positions it
+/// overlaps. Players missing from `points` score zero.
+pub fn optimal_lineup(slots: &[RosterSlot], roster: &Roster, points: &HashMap<String, Points>) -> (Vec<(String, String)>, Points) {
+    let score = |player_key: &str| points.get(player_key).copied().unwrap_or(Points::ZERO);
+
+    let mut starting: Vec<&RosterSlot> = slots.iter().filter(|s| s.is_starting).collect();
+    starting.sort_by_key(|s| s.accepts.len());
+
+    let mut used: HashSet<&str> = HashSet::new();
+    let mut lineup = Vec::new();
+    let mut total = Points::ZERO;
+    for slot in starting {
+        for _ in 0..slot.count {
+            let best = roster
+                .players
+                .iter()
+                .filter(|p| !used.contains(p.player_key.as_str()))
+                .filter(|p| p.eligible_positions.iter().any(|pos| slot.accepts(pos)))
+                .max_by_key(|p| score(&p.player_key));
+            let Some(player) = best else {
+                break;
+            };
+            used.insert(&player.player_key);
+            total += score(&player.player_key);
+            lineup.push((player.player_key.clone(), slot.position.clone()));
+        }
+    }
+    (lineup, total)
+}
+
+/// Compare the lineup in `roster` to the best one in hindsight
+pub fn lineup_retro(week: u32, slots: &[RosterSlot], roster: &Roster, points: &HashMap<String, Points>) -> LineupRetro {
+    let starting: HashSet<&str> = slots.iter().filter(|s| s.is_starting).map(|s| s.position.as_str()).collect();
+    let actual = roster
+        .players
+        .iter()
+        .filter(|p| starting.contains(p.selected_position.as_str()))
+        .filter_map(|p| points.get(&p.player_key).copied())
+        .sum();
+    let (optimal_lineup, optimal) = optimal_lineup(slots, roster, points);
+    LineupRetro {
+        team_key: roster.team_key.clone(),
+        week,
+        actual,
+        optimal,
+        optimal_lineup,
+    }
+}
+
+impl YahooFantasyClient {
+    /// Actual fantasy points for each player on a team's roster in a week
+    pub async fn get_roster_points(&self, team_key: &str, week: u32) -> Result<Vec<PlayerPoints>, Box<dyn std::error::Error + Send + Sync>> {
+        let body = self.get_json(&format!("team/{}/roster;week={}/players/stats;type=week;week={}", team_key, week, week)).await?;
+        let team = response::resource(&response::content(&body)?["team"]);
+        let roster = response::resource(response::member(&team, "roster"));
+        response::collection(&response::member(&roster, "0")["players"], "player")
+            .iter()
+            .map(|player| {
+                Ok(PlayerPoints {
+                    player_key: response::require(player, "player_key", "player")?,
+                    points: response::field(&response::resource(response::member(player, "player_points")), "total").unwrap_or(Points::ZERO),
+                })
+            })
+            .collect()
+    }
+
+    /// Best possible lineup for a team's week in hindsight, compared to what was started
+    pub async fn optimal_lineup_retro(&self, team_key: &str, week: u32) -> Result<LineupRetro, Box<dyn std::error::Error + Send + Sync>> {
+        let league_key = team_key
+            .rsplit_once(".t.")
+            .map(|(league, _)| league)
+            .ok_or_else(|| format!("invalid team key {}", team_key))?;
+
+        let settings = self.get_league_settings(league_key).await?;
+        let roster = self.get_roster(team_key, &RosterCoverage::Week(week)).await?;
+        let points = self
+            .get_roster_points(team_key, week)
+            .await?
+            .into_iter()
+            .map(|p| (p.player_key, p.points))
+            .collect();
+        Ok(lineup_retro(week, &settings.roster_slots, &roster, &points))
+    }
+
+    /// Manager efficiency for every team in a league over weeks `1..=through_week`
+    pub async fn manager_efficiency(&self, league_key: &str, through_week: u32) -> Result<Vec<ManagerEfficiency>, Box<dyn std::error::Error + Send + Sync>> {
+        self.manager_efficiency_with_progress(league_key, through_week, |_| {}).await
+    }
+
+    /// Like `manager_efficiency`, calling `on_progress` before each team-week and when done.
+    /// Progress counts team-weeks, not requests.
+    pub async fn manager_efficiency_with_progress(
+        &self,
+        league_key: &str,
+        through_week: u32,
+        on_progress: impl FnMut(&Progress),
+    ) -> Result<Vec<ManagerEfficiency>, Box<dyn std::error::Error + Send + Sync>> {
+        let mut progress = ProgressTracker::new(0, on_progress);
+        let standings = self.get_standings(league_key).await?;
+        progress.set_total(standings.teams.len() as u32 * through_week);
+        let mut retros = Vec::new();
+        for team in &standings.teams {
+            for week in 1..=through_week {
+                progress.start(format!("{} week {}", team.team_key, week));
+                retros.push(self.optimal_lineup_retro(&team.team_key, week).await?);
+                progress.complete();
+            }
+        }
+        progress.finish();
+        Ok(ManagerEfficiency::from_retros(&retros))
+    }
+}
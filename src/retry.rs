@@ -0,0 +1,78 @@
+//! Automatic retries
+//! Exponential backoff with jitter for network failures and 5xx responses
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+
+use crate::{HttpError, TimeoutError};
+
+/// How the client retries transient failures. Every attempt goes through the
+/// rate limiter, so retries consume tokens like any other request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubles with each further retry
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Delay after `attempt` (1-based) failed: half the capped exponential
+    /// backoff, plus a random amount up to the other half
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .checked_mul(1 << attempt.saturating_sub(1).min(16))
+            .map_or(self.max_delay, |d| d.min(self.max_delay));
+        let half = backoff / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+}
+
+/// Whether an error is worth retrying: timeouts, 429 and 5xx responses, and
+/// reqwest connection failures. Errors from custom transports aren't retried.
+pub fn is_transient(error: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    if let Some(http) = error.downcast_ref::<HttpError>() {
+        return http.status == 429 || http.status >= 500;
+    }
+    if let Some(network) = error.downcast_ref::<reqwest::Error>() {
+        return network.is_connect() || network.is_timeout() || network.is_request() || network.is_body();
+    }
+    matches!(error.downcast_ref::<TimeoutError>(), Some(TimeoutError::Connect(_) | TimeoutError::Request(_)))
+}
+
+/// Delay requested by the server, if `error` is a response carrying `Retry-After`
+pub fn retry_after(error: &(dyn std::error::Error + Send + Sync + 'static)) -> Option<Duration> {
+    error.downcast_ref::<HttpError>()?.retry_after
+}
+
+/// Parse a `Retry-After` value: delay-seconds or an HTTP date. Dates in the past yield zero.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
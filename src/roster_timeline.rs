@@ -0,0 +1,131 @@
+//! Roster timeline reconstruction
+//! Rewinds a team's current roster through the transaction log to recover historical roster state
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+use crate::time::{self, WeekInstants, WeekRange};
+use crate::xml::RosterCoverage;
+use crate::{Roster, Transaction, YahooFantasyClient};
+
+/// One continuous period a player spent on the roster
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RosterStint {
+    pub player_key: String,
+    pub name: String,
+    /// Unix seconds; `None` if the player was on the roster before the first known transaction
+    pub acquired: Option<i64>,
+    /// Unix seconds; `None` if the player is still on the roster
+    pub released: Option<i64>,
+}
+
+impl RosterStint {
+    /// Whether the player was on the roster at `timestamp`
+    pub fn contains(&self, timestamp: i64) -> bool {
+        self.acquired.is_none_or(|a| a <= timestamp) && self.released.is_none_or(|r| timestamp < r)
+    }
+
+    /// Whether the stint overlaps `start..end`
+    pub fn overlaps(&self, start: i64, end: i64) -> bool {
+        self.acquired.is_none_or(|a| a < end) && self.released.is_none_or(|r| start < r)
+    }
+}
+
+/// Every player a team has rostered, with when they joined and left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RosterTimeline {
+    pub team_key: String,
+    pub stints: Vec<RosterStint>,
+}
+
+impl RosterTimeline {
+    /// Rewind `current` (the roster as of `as_of`) through the team's transactions.
+    /// Transactions after `as_of` or not involving the team are ignored.
+    pub fn reconstruct(current: &Roster, as_of: i64, transactions: &[Transaction]) -> Self {
+        let team_key = current.team_key.as_str();
+        let mut open: HashMap<String, RosterStint> = current
+            .players
+            .iter()
+            .map(|p| {
+                let stint = RosterStint {
+                    player_key: p.player_key.clone(),
+                    name: p.name.clone(),
+                    acquired: None,
+                    released: None,
+                };
+                (p.player_key.clone(), stint)
+            })
+            .collect();
+        let mut closed = Vec::new();
+
+        let mut history: Vec<&Transaction> = transactions.iter().filter(|t| t.timestamp <= as_of).collect();
+        history.sort_by_key(|t| std::cmp::Reverse(t.timestamp));
+
+        for transaction in history {
+            for player in &transaction.players {
+                // Undo an acquisition: the stint started here
+                if player.destination_team_key.as_deref() == Some(team_key) {
+                    if let Some(mut stint) = open.remove(&player.player_key) {
+                        stint.acquired = Some(transaction.timestamp);
+                        closed.push(stint);
+                    }
+                }
+                // Undo a departure: the player was on the roster until here
+                if player.source_team_key.as_deref() == Some(team_key) {
+                    open.insert(
+                        player.player_key.clone(),
+                        RosterStint {
+                            player_key: player.player_key.clone(),
+                            name: player.name.clone(),
+                            acquired: None,
+                            released: Some(transaction.timestamp),
+                        },
+                    );
+                }
+            }
+        }
+
+        let mut stints: Vec<RosterStint> = closed.into_iter().chain(open.into_values()).collect();
+        stints.sort_by(|a, b| a.acquired.cmp(&b.acquired).then_with(|| a.player_key.cmp(&b.player_key)));
+        Self {
+            team_key: team_key.to_string(),
+            stints,
+        }
+    }
+
+    /// Players on the roster at an instant
+    pub fn at(&self, instant: &DateTime<impl TimeZone>) -> Vec<&RosterStint> {
+        let timestamp = instant.timestamp();
+        self.stints.iter().filter(|s| s.contains(timestamp)).collect()
+    }
+
+    /// Players on the roster at the start of a coverage date (when daily lineups lock)
+    pub fn on_date(&self, date: NaiveDate) -> Vec<&RosterStint> {
+        self.at(&time::start_of_day(date))
+    }
+
+    /// Players on the roster at any point during a fantasy week
+    pub fn during_week(&self, week: &WeekRange) -> Vec<&RosterStint> {
+        let (start, end) = (week.starts_at().timestamp(), week.ends_at().timestamp());
+        self.stints.iter().filter(|s| s.overlaps(start, end)).collect()
+    }
+}
+
+impl YahooFantasyClient {
+    /// Reconstruct a team's roster history from its current roster and the league transaction log
+    pub async fn roster_timeline(&self, team_key: &str) -> Result<RosterTimeline, Box<dyn std::error::Error + Send + Sync>> {
+        let league_key = team_key
+            .rsplit_once(".t.")
+            .map(|(league, _)| league)
+            .ok_or_else(|| format!("invalid team key {}", team_key))?;
+
+        let now = time::now();
+        let current = self.get_roster(team_key, &RosterCoverage::Date(time::coverage_date(&now))).await?;
+        let transactions = self.get_transactions(league_key).await?;
+        Ok(RosterTimeline::reconstruct(&current, now.timestamp(), &transactions))
+    }
+}
@@ -0,0 +1,226 @@
+//! Real-world team schedules and bye weeks
+//! One place for game times, byes, and games-per-week, loaded from game metadata with pluggable fallbacks
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::polling::GameTimeSchedule;
+use crate::time::{WeekInstants, WeekRange};
+use crate::{BoxFuture, YahooFantasyClient};
+
+/// How long a game is assumed to be live when no end time is known
+pub const DEFAULT_GAME_LENGTH: Duration = Duration::from_secs(4 * 60 * 60);
+
+/// A single real-world game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ScheduledGame {
+    /// Editorial team abbreviations, e.g. "KC"
+    pub home: String,
+    pub away: String,
+    pub starts_at: DateTime<Utc>,
+}
+
+impl ScheduledGame {
+    pub fn involves(&self, team: &str) -> bool {
+        self.home.eq_ignore_ascii_case(team) || self.away.eq_ignore_ascii_case(team)
+    }
+
+    /// Estimated live window
+    pub fn window(&self) -> (SystemTime, SystemTime) {
+        let start = SystemTime::from(self.starts_at);
+        (start, start + DEFAULT_GAME_LENGTH)
+    }
+}
+
+/// Schedule data for one game code and season
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SeasonSchedule {
+    pub game_code: String,
+    pub season: i32,
+    /// Fantasy weeks from game metadata
+    pub weeks: Vec<WeekRange>,
+    pub games: Vec<ScheduledGame>,
+    /// Bye weeks per team abbreviation (NFL)
+    pub bye_weeks: HashMap<String, Vec<u32>>,
+}
+
+impl SeasonSchedule {
+    pub fn week(&self, week: u32) -> Option<&WeekRange> {
+        self.weeks.iter().find(|w| w.week == week)
+    }
+
+    pub fn is_on_bye(&self, team: &str, week: u32) -> bool {
+        self.bye_weeks
+            .iter()
+            .any(|(t, byes)| t.eq_ignore_ascii_case(team) && byes.contains(&week))
+    }
+
+    /// Games a team plays during a fantasy week
+    pub fn games_for_team(&self, team: &str, week: u32) -> Vec<&ScheduledGame> {
+        let Some(range) = self.week(week) else {
+            return Vec::new();
+        };
+        self.games
+            .iter()
+            .filter(|g| g.involves(team) && range.contains_instant(&g.starts_at))
+            .collect()
+    }
+
+    pub fn games_per_week(&self, team: &str, week: u32) -> usize {
+        self.games_for_team(team, week).len()
+    }
+
+    /// Teams from `teams` that share the given bye week
+    pub fn bye_conflicts<'a>(&self, teams: &[&'a str], week: u32) -> Vec<&'a str> {
+        teams.iter().copied().filter(|t| self.is_on_bye(t, week)).collect()
+    }
+
+    /// Live windows for every game, for game-time-aware polling
+    pub fn game_windows(&self) -> Vec<(SystemTime, SystemTime)> {
+        self.games.iter().map(ScheduledGame::window).collect()
+    }
+
+    /// Polling schedule that is fast while any game is live
+    pub fn polling_schedule(&self, live_interval: Duration, idle_interval: Duration) -> GameTimeSchedule {
+        GameTimeSchedule::from_windows(live_interval, idle_interval, self.game_windows())
+    }
+
+    /// Fill in anything missing here from `other`
+    fn merge_missing(&mut self, other: SeasonSchedule) {
+        if self.weeks.is_empty() {
+            self.weeks = other.weeks;
+        }
+        if self.games.is_empty() {
+            self.games = other.games;
+        }
+        for (team, byes) in other.bye_weeks {
+            self.bye_weeks.entry(team).or_insert(byes);
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        !self.weeks.is_empty() && !self.games.is_empty()
+    }
+}
+
+/// Somewhere schedule data can be loaded from
+pub trait ScheduleSource: Send + Sync {
+    fn load<'a>(&'a self, game_code: &'a str, season: i32) -> BoxFuture<'a, Result<SeasonSchedule, Box<dyn std::error::Error + Send + Sync>>>;
+}
+
+/// Loads fantasy weeks from Yahoo game metadata
+pub struct GameMetadataSource<'a> {
+    client: &'a YahooFantasyClient,
+}
+
+impl<'a> GameMetadataSource<'a> {
+    pub fn new(client: &'a YahooFantasyClient) -> Self {
+        Self { client }
+    }
+}
+
+impl ScheduleSource for GameMetadataSource<'_> {
+    fn load<'a>(&'a self, game_code: &'a str, season: i32) -> BoxFuture<'a, Result<SeasonSchedule, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            let game_key = format!("{}.{}", game_code, season);
+            Ok(SeasonSchedule {
+                game_code: game_code.to_string(),
+                season,
+                weeks: self.client.get_game_weeks(&game_key).await?,
+                ..SeasonSchedule::default()
+            })
+        })
+    }
+}
+
+/// Schedule data supplied up front, e.g. deserialized from an external feed
+pub struct StaticScheduleSource {
+    schedules: Vec<SeasonSchedule>,
+}
+
+impl StaticScheduleSource {
+    pub fn new(schedules: Vec<SeasonSchedule>) -> Self {
+        Self { schedules }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+}
+
+impl ScheduleSource for StaticScheduleSource {
+    fn load<'a>(&'a self, game_code: &'a str, season: i32) -> BoxFuture<'a, Result<SeasonSchedule, Box<dyn std::error::Error + Send + Sync>>> {
+        let schedule = self
+            .schedules
+            .iter()
+            .find(|s| s.game_code == game_code && s.season == season)
+            .cloned()
+            .ok_or_else(|| format!("no schedule for {} {}", game_code, season).into());
+        Box::pin(async move { schedule })
+    }
+}
+
+/// Consults sources in order, filling gaps from later ones, and caches the result
+pub struct ScheduleService<'a> {
+    sources: Vec<Box<dyn ScheduleSource + 'a>>,
+    loaded: Mutex<HashMap<(String, i32), SeasonSchedule>>,
+}
+
+impl<'a> ScheduleService<'a> {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            loaded: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Add a source; earlier sources take precedence
+    pub fn with_source(mut self, source: impl ScheduleSource + 'a) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Load (or return the cached) schedule for a game code and season
+    pub async fn load(&self, game_code: &str, season: i32) -> Result<SeasonSchedule, Box<dyn std::error::Error + Send + Sync>> {
+        let cache_key = (game_code.to_string(), season);
+        let cached = self.loaded.lock().unwrap().get(&cache_key).cloned();
+        if let Some(schedule) = cached {
+            return Ok(schedule);
+        }
+
+        let mut schedule: Option<SeasonSchedule> = None;
+        let mut last_error = None;
+        for source in &self.sources {
+            match source.load(game_code, season).await {
+                Ok(loaded) => match schedule.as_mut() {
+                    Some(existing) => existing.merge_missing(loaded),
+                    None => schedule = Some(loaded),
+                },
+                Err(error) => last_error = Some(error),
+            }
+            if schedule.as_ref().is_some_and(SeasonSchedule::is_complete) {
+                break;
+            }
+        }
+
+        let schedule = match (schedule, last_error) {
+            (Some(schedule), _) => schedule,
+            (None, Some(error)) => return Err(error),
+            (None, None) => return Err("no schedule sources configured".into()),
+        };
+        self.loaded.lock().unwrap().insert(cache_key, schedule.clone());
+        Ok(schedule)
+    }
+}
+
+impl Default for ScheduleService<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
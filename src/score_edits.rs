@@ -0,0 +1,99 @@
+//! Score-edit detection
+//! Recomputes team totals from raw stats and flags gaps that stat corrections don't explain
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Points, Scoreboard, StatModifier};
+
+/// Decimal places Yahoo reports team totals to
+const REPORTED_PLACES: u32 = 2;
+
+/// A stat value Yahoo revised after the fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StatCorrection {
+    pub player_key: String,
+    /// Stat abbreviation, matching `StatModifier::display_name`
+    pub stat: String,
+    pub previous: f64,
+    pub corrected: f64,
+}
+
+/// A reported team total that doesn't match the recomputed one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ScoreEdit {
+    pub team_key: String,
+    pub week: i32,
+    pub reported: Points,
+    pub recomputed: Points,
+    /// `reported - recomputed`: the likely manual adjustment
+    pub adjustment: Points,
+}
+
+/// Fantasy points for a raw stat line; stats without a modifier score nothing.
+/// `None` if a stat value isn't finite or the total overflows.
+pub fn score_stats(stats: &BTreeMap<String, f64>, modifiers: &[StatModifier]) -> Option<Points> {
+    modifiers
+        .iter()
+        .filter_map(|m| stats.get(&m.display_name).map(|value| m.value.scale(*value)))
+        .sum()
+}
+
+/// Compare each team's reported total to its starters' recomputed points.
+/// `stats` holds each player's current (post-correction) stats for the week. A gap
+/// that equals the points from `corrections` means Yahoo hasn't applied them yet and
+/// isn't flagged; teams with a starter missing from `stats`, or whose stats
+/// can't be scored, are skipped.
+pub fn detect_score_edits(
+    scoreboard: &Scoreboard,
+    stats: &HashMap<String, BTreeMap<String, f64>>,
+    modifiers: &[StatModifier],
+    corrections: &[StatCorrection],
+) -> Vec<ScoreEdit> {
+    let correction_points = |player_key: &str| -> Option<Points> {
+        corrections
+            .iter()
+            .filter(|c| c.player_key == player_key)
+            .filter_map(|c| {
+                let modifier = modifiers.iter().find(|m| m.display_name == c.stat)?;
+                Some(modifier.value.scale(c.corrected - c.previous))
+            })
+            .sum()
+    };
+
+    let mut edits = Vec::new();
+    for team in scoreboard.matchups.iter().flat_map(|m| &m.teams) {
+        let lines: Option<Vec<&BTreeMap<String, f64>>> = team.players.iter().map(|p| stats.get(&p.player_key)).collect();
+        let Some(lines) = lines else {
+            continue;
+        };
+
+        let Some(recomputed) = lines.into_iter().map(|line| score_stats(line, modifiers)).sum::<Option<Points>>() else {
+            continue;
+        };
+        let recomputed = recomputed.round_to(REPORTED_PLACES);
+        let gap = team.points - recomputed;
+        if gap.is_negligible() {
+            continue;
+        }
+
+        let Some(pending) = team.players.iter().map(|p| correction_points(&p.player_key)).sum::<Option<Points>>() else {
+            continue;
+        };
+        if (gap + pending).round_to(REPORTED_PLACES).is_negligible() {
+            continue;
+        }
+
+        edits.push(ScoreEdit {
+            team_key: team.team_key.clone(),
+            week: scoreboard.week,
+            reported: team.points,
+            recomputed,
+            adjustment: gap,
+        });
+    }
+    edits
+}
@@ -0,0 +1,130 @@
+//! Scoreboard diff engine
+//! Turns consecutive scoreboard snapshots into semantic events
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Matchup, MatchupTeam, Points, Scoreboard};
+
+/// Something meaningful that happened between two scoreboard snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScoreboardEvent {
+    /// A team took the lead in its matchup
+    LeadChange {
+        week: i32,
+        leader_team_key: String,
+        /// Team that led in the previous snapshot, if the matchup wasn't tied
+        previous_leader_team_key: Option<String>,
+        margin: Points,
+    },
+    /// A player's points changed; negative deltas are stat corrections
+    ScoringPlay {
+        week: i32,
+        team_key: String,
+        player_key: String,
+        points_delta: Points,
+    },
+    /// The projected winner of a matchup changed
+    ProjectedResultFlip {
+        week: i32,
+        projected_winner_team_key: String,
+        projected_loser_team_key: String,
+    },
+}
+
+/// Compare two snapshots of the same league week and return the events between them
+pub fn diff_scoreboards(previous: &Scoreboard, current: &Scoreboard) -> Vec<ScoreboardEvent> {
+    let mut events = Vec::new();
+    if previous.league_key != current.league_key || previous.week != current.week {
+        return events;
+    }
+
+    let previous_matchups: HashMap<Vec<&str>, &Matchup> =
+        previous.matchups.iter().map(|m| (matchup_id(m), m)).collect();
+
+    for matchup in &current.matchups {
+        let Some(before) = previous_matchups.get(&matchup_id(matchup)) else {
+            continue;
+        };
+
+        for team in &matchup.teams {
+            if let Some(old_team) = before.teams.iter().find(|t| t.team_key == team.team_key) {
+                scoring_plays(current.week, old_team, team, &mut events);
+            }
+        }
+
+        let old_leader = leader(&before.teams, |t| t.points);
+        let new_leader = leader(&matchup.teams, |t| t.points);
+        if let Some((leader_team, margin)) = new_leader {
+            let previous_leader_team_key = old_leader.map(|(t, _)| t.team_key.clone());
+            if previous_leader_team_key.as_deref() != Some(leader_team.team_key.as_str()) {
+                events.push(ScoreboardEvent::LeadChange {
+                    week: current.week,
+                    leader_team_key: leader_team.team_key.clone(),
+                    previous_leader_team_key,
+                    margin,
+                });
+            }
+        }
+
+        let old_projected = leader(&before.teams, |t| t.projected_points);
+        let new_projected = leader(&matchup.teams, |t| t.projected_points);
+        if let (Some((old_winner, _)), Some((new_winner, _))) = (old_projected, new_projected) {
+            if old_winner.team_key != new_winner.team_key {
+                events.push(ScoreboardEvent::ProjectedResultFlip {
+                    week: current.week,
+                    projected_winner_team_key: new_winner.team_key.clone(),
+                    projected_loser_team_key: old_winner.team_key.clone(),
+                });
+            }
+        }
+    }
+
+    events
+}
+
+/// Matchups are identified by their sorted team keys
+fn matchup_id(matchup: &Matchup) -> Vec<&str> {
+    let mut keys: Vec<&str> = matchup.teams.iter().map(|t| t.team_key.as_str()).collect();
+    keys.sort_unstable();
+    keys
+}
+
+/// Leading team and margin over the runner-up, or `None` when tied
+fn leader<F>(teams: &[MatchupTeam], score: F) -> Option<(&MatchupTeam, Points)>
+where
+    F: Fn(&MatchupTeam) -> Points,
+{
+    let mut sorted: Vec<&MatchupTeam> = teams.iter().collect();
+    sorted.sort_by_key(|t| std::cmp::Reverse(score(t)));
+    let first = *sorted.first()?;
+    let margin = sorted.get(1).map_or(score(first), |second| score(first) - score(second));
+    if margin.is_negligible() {
+        None
+    } else {
+        Some((first, margin))
+    }
+}
+
+/// Player point deltas between snapshots, inferred as scoring plays
+fn scoring_plays(week: i32, before: &MatchupTeam, after: &MatchupTeam, events: &mut Vec<ScoreboardEvent>) {
+    for player in &after.players {
+        let old_points = before
+            .players
+            .iter()
+            .find(|p| p.player_key == player.player_key)
+            .map_or(Points::ZERO, |p| p.points);
+        let delta = player.points - old_points;
+        if !delta.is_negligible() {
+            events.push(ScoreboardEvent::ScoringPlay {
+                week,
+                team_key: after.team_key.clone(),
+                player_key: player.player_key.clone(),
+                points_delta: delta,
+            });
+        }
+    }
+}
@@ -0,0 +1,447 @@
+//! Simulated league seasons
+//! A deterministic league clock that steps through the draft, regular season, and playoffs, generating
+//! scores, standings, and transactions so apps can test full-season workflows offline. Leagues are seedable
+//! and vary by sport, size, and scoring type, so property tests can run against many reproducible seasons.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::time::{self, WeekRange};
+use crate::{Matchup, MatchupTeam, Points, Scoreboard, Standings, TeamStanding, Transaction, TransactionPlayer, TransactionType};
+
+/// Where a simulated league is in its season
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SeasonPhase {
+    Predraft,
+    Drafting,
+    RegularSeason,
+    Playoffs,
+    Complete,
+}
+
+/// Sports the simulator can play, by Yahoo game code
+pub const SIMULATED_SPORTS: &[&str] = &["nfl", "nba", "mlb", "nhl"];
+
+/// Yahoo scoring types the simulator can play
+pub const SIMULATED_SCORING_TYPES: &[&str] = &["head", "headone", "point"];
+
+/// Shape of a simulated league
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationConfig {
+    /// The game code prefix picks the sport, e.g. "nba.l.1"
+    pub league_key: String,
+    pub num_teams: u32,
+    pub regular_season_weeks: u32,
+    pub num_playoff_teams: u32,
+    /// Yahoo scoring type: "head" and "headone" play weekly matchups and a
+    /// playoff bracket, "point" ranks teams on total points with no playoffs
+    pub scoring_type: String,
+    /// Monday of week 1
+    pub season_start: NaiveDate,
+    /// Same seed, same season
+    pub seed: u64,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            league_key: "nfl.l.1".to_string(),
+            num_teams: 10,
+            regular_season_weeks: 14,
+            num_playoff_teams: 4,
+            scoring_type: "head".to_string(),
+            season_start: season_start("nfl"),
+            seed: 0,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// A default league playing `sport`, e.g. "nba"
+    pub fn for_sport(sport: &str) -> Self {
+        Self {
+            league_key: format!("{}.l.1", sport),
+            regular_season_weeks: season_weeks(sport).1,
+            season_start: season_start(sport),
+            ..Self::default()
+        }
+    }
+
+    /// A league drawn from `seed`, varying the sport, number of teams, season
+    /// length, playoff field, and scoring type. The same seed always gives the
+    /// same league, and the same season when simulated.
+    pub fn generate(seed: u64) -> Self {
+        let mut hasher = DefaultHasher::new();
+        ("config", seed).hash(&mut hasher);
+        let roll = hasher.finish();
+        let pick = |options: &[&str], shift: u32| options[((roll >> shift) % options.len() as u64) as usize].to_string();
+
+        let sport = pick(SIMULATED_SPORTS, 0);
+        let num_teams = 4 + ((roll >> 8) % 13) as u32;
+        let (min_weeks, max_weeks) = season_weeks(&sport);
+        let playoff_fields: Vec<u32> = [2, 4, 6, 8].into_iter().filter(|&n| n <= num_teams).collect();
+        Self {
+            league_key: format!("{}.l.{}", sport, seed % 1_000_000),
+            num_teams,
+            regular_season_weeks: min_weeks + ((roll >> 16) % (max_weeks - min_weeks + 1) as u64) as u32,
+            num_playoff_teams: playoff_fields[((roll >> 24) % playoff_fields.len() as u64) as usize],
+            scoring_type: pick(SIMULATED_SCORING_TYPES, 32),
+            season_start: season_start(&sport),
+            seed,
+        }
+    }
+
+    /// Yahoo game code of the league's sport
+    pub fn sport(&self) -> &str {
+        self.league_key.split('.').next().unwrap_or("nfl")
+    }
+
+    /// Whether teams play weekly head-to-head matchups and a playoff bracket
+    pub fn is_head_to_head(&self) -> bool {
+        self.scoring_type != "point"
+    }
+}
+
+/// Shortest and longest regular seasons, in weeks, a simulated `sport` league plays
+fn season_weeks(sport: &str) -> (u32, u32) {
+    match sport {
+        "nba" | "nhl" => (16, 21),
+        "mlb" => (18, 23),
+        _ => (11, 15),
+    }
+}
+
+/// Monday of week 1 for a simulated `sport` league
+fn season_start(sport: &str) -> NaiveDate {
+    let (month, day) = match sport {
+        "nba" => (10, 21),
+        "nhl" => (10, 7),
+        "mlb" => (3, 25),
+        _ => (9, 2),
+    };
+    NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+}
+
+/// Lowest weekly team score and the spread above it, in tenths of a point
+fn score_range(sport: &str) -> (f64, u64) {
+    match sport {
+        "nba" => (850.0, 4000),
+        "nhl" => (90.0, 1100),
+        "mlb" => (80.0, 1200),
+        _ => (70.0, 900),
+    }
+}
+
+/// What happened in one step of the league clock
+#[derive(Debug, Clone)]
+pub struct SimulatedWeek {
+    /// Week just played; 0 before the regular season
+    pub week: u32,
+    /// Phase after the step
+    pub phase: SeasonPhase,
+    /// Matchups played this week; `None` outside the season and in points leagues
+    pub scoreboard: Option<Scoreboard>,
+    /// Transactions made this week, newest first
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Record {
+    wins: u32,
+    losses: u32,
+    ties: u32,
+    points_for: Points,
+}
+
+/// A league whose season advances one step at a time: the draft opens, the
+/// draft completes, then each call plays a week of the regular season or a
+/// playoff round until a champion is decided
+#[derive(Debug, Clone)]
+pub struct SeasonSimulator {
+    config: SimulationConfig,
+    phase: SeasonPhase,
+    week: u32,
+    records: Vec<Record>,
+    /// Teams still alive in the playoffs, best seed first
+    bracket: Vec<usize>,
+    champion: Option<usize>,
+    /// Every transaction so far, newest first
+    transactions: Vec<Transaction>,
+}
+
+impl SeasonSimulator {
+    pub fn new(config: SimulationConfig) -> Self {
+        let num_teams = config.num_teams.max(2) as usize;
+        Self {
+            records: vec![Record::default(); num_teams],
+            config: SimulationConfig {
+                num_teams: num_teams as u32,
+                num_playoff_teams: config.num_playoff_teams.clamp(2, num_teams as u32),
+                ..config
+            },
+            phase: SeasonPhase::Predraft,
+            week: 0,
+            bracket: Vec::new(),
+            champion: None,
+            transactions: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &SimulationConfig {
+        &self.config
+    }
+
+    pub fn phase(&self) -> SeasonPhase {
+        self.phase
+    }
+
+    /// Last week played; 0 before the regular season
+    pub fn week(&self) -> u32 {
+        self.week
+    }
+
+    /// Dates of `week`, counting from `SimulationConfig::season_start`
+    pub fn week_range(&self, week: u32) -> WeekRange {
+        let start = self.config.season_start + Duration::weeks(week.saturating_sub(1) as i64);
+        WeekRange {
+            week,
+            start,
+            end: start + Duration::days(6),
+        }
+    }
+
+    pub fn team_key(&self, team: usize) -> String {
+        format!("{}.t.{}", self.config.league_key, team + 1)
+    }
+
+    /// The playoff winner, once the season is complete
+    pub fn champion(&self) -> Option<String> {
+        self.champion.map(|team| self.team_key(team))
+    }
+
+    /// Every transaction so far, newest first
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// Standings as of the last week played, ranked by wins then points for
+    pub fn standings(&self) -> Standings {
+        let seeded = self.playoff_seeds();
+        let in_playoffs = self.config.is_head_to_head() && matches!(self.phase, SeasonPhase::Playoffs | SeasonPhase::Complete);
+        let teams = self
+            .ranked()
+            .into_iter()
+            .enumerate()
+            .map(|(rank, team)| {
+                let record = &self.records[team];
+                TeamStanding {
+                    team_key: self.team_key(team),
+                    name: format!("Team {}", team + 1),
+                    rank: rank as u32 + 1,
+                    wins: record.wins,
+                    losses: record.losses,
+                    ties: record.ties,
+                    points_for: record.points_for,
+                    waiver_priority: Some(self.config.num_teams - rank as u32),
+                    clinched_playoffs: in_playoffs.then(|| seeded.contains(&team)),
+                    eliminated: in_playoffs.then(|| !self.bracket.contains(&team) && self.champion != Some(team)),
+                }
+            })
+            .collect();
+        Standings {
+            league_key: self.config.league_key.clone(),
+            week: self.week as i32,
+            teams,
+        }
+    }
+
+    /// Move the league clock forward one step
+    pub fn advance(&mut self) -> SimulatedWeek {
+        let (scoreboard, transactions) = match self.phase {
+            SeasonPhase::Predraft => {
+                self.phase = SeasonPhase::Drafting;
+                (None, Vec::new())
+            }
+            SeasonPhase::Drafting => {
+                self.phase = SeasonPhase::RegularSeason;
+                (None, Vec::new())
+            }
+            SeasonPhase::RegularSeason => {
+                self.week += 1;
+                let scoreboard = if self.config.is_head_to_head() {
+                    let pairs = self.round_robin(self.week);
+                    Some(self.play(&pairs))
+                } else {
+                    self.score_week();
+                    None
+                };
+                let transactions = self.waiver_moves();
+                if self.week >= self.config.regular_season_weeks {
+                    if self.config.is_head_to_head() {
+                        self.phase = SeasonPhase::Playoffs;
+                        self.bracket = self.playoff_seeds();
+                    } else {
+                        self.phase = SeasonPhase::Complete;
+                        self.champion = self.ranked().first().copied();
+                    }
+                }
+                (scoreboard, transactions)
+            }
+            SeasonPhase::Playoffs => {
+                self.week += 1;
+                // The top seed takes a bye when an odd number of teams is left
+                let bye = (self.bracket.len() % 2 == 1).then(|| self.bracket[0]);
+                let playing: Vec<usize> = self.bracket.iter().copied().filter(|t| Some(*t) != bye).collect();
+                let pairs: Vec<(usize, usize)> = (0..playing.len() / 2).map(|i| (playing[i], playing[playing.len() - 1 - i])).collect();
+                let scoreboard = self.play(&pairs);
+                let winners: Vec<usize> = pairs.iter().map(|&(high, low)| if self.score(low) > self.score(high) { low } else { high }).collect();
+                self.bracket.retain(|t| Some(*t) == bye || winners.contains(t));
+                if self.bracket.len() == 1 {
+                    self.champion = self.bracket.pop();
+                    self.phase = SeasonPhase::Complete;
+                }
+                (Some(scoreboard), Vec::new())
+            }
+            SeasonPhase::Complete => (None, Vec::new()),
+        };
+        SimulatedWeek {
+            week: self.week,
+            phase: self.phase,
+            scoreboard,
+            transactions,
+        }
+    }
+
+    /// Advance until the season is complete, returning every step
+    pub fn run_to_completion(&mut self) -> Vec<SimulatedWeek> {
+        let mut weeks = Vec::new();
+        while self.phase != SeasonPhase::Complete {
+            weeks.push(self.advance());
+        }
+        weeks
+    }
+
+    /// Teams by wins, then points for
+    fn ranked(&self) -> Vec<usize> {
+        let mut teams: Vec<usize> = (0..self.records.len()).collect();
+        teams.sort_by(|&a, &b| {
+            let (a_record, b_record) = (&self.records[a], &self.records[b]);
+            (b_record.wins * 2 + b_record.ties)
+                .cmp(&(a_record.wins * 2 + a_record.ties))
+                .then(b_record.points_for.cmp(&a_record.points_for))
+                .then(a.cmp(&b))
+        });
+        teams
+    }
+
+    fn playoff_seeds(&self) -> Vec<usize> {
+        self.ranked().into_iter().take(self.config.num_playoff_teams as usize).collect()
+    }
+
+    /// Pairings for a regular season week by the circle method; with an odd
+    /// number of teams one sits out each week
+    fn round_robin(&self, week: u32) -> Vec<(usize, usize)> {
+        let n = self.records.len();
+        let slots = n + n % 2;
+        let rounds = slots - 1;
+        let round = (week as usize - 1) % rounds;
+        // Team 0 stays put; the others rotate through the remaining slots
+        let at = |slot: usize| if slot == 0 { 0 } else { (slot - 1 + round) % rounds + 1 };
+        (0..slots / 2)
+            .map(|i| (at(i), at(slots - 1 - i)))
+            .filter(|&(a, b)| a < n && b < n)
+            .collect()
+    }
+
+    /// Play `pairs` this week, updating records outside the playoffs
+    fn play(&mut self, pairs: &[(usize, usize)]) -> Scoreboard {
+        let mut matchups = Vec::new();
+        for &(a, b) in pairs {
+            let (a_points, b_points) = (self.score(a), self.score(b));
+            if self.phase == SeasonPhase::RegularSeason {
+                for (team, points, opponent) in [(a, a_points, b_points), (b, b_points, a_points)] {
+                    let record = &mut self.records[team];
+                    record.points_for += points;
+                    match points.cmp(&opponent) {
+                        std::cmp::Ordering::Greater => record.wins += 1,
+                        std::cmp::Ordering::Less => record.losses += 1,
+                        std::cmp::Ordering::Equal => record.ties += 1,
+                    }
+                }
+            }
+            let side = |team: usize, points: Points| MatchupTeam {
+                team_key: self.team_key(team),
+                name: format!("Team {}", team + 1),
+                points,
+                projected_points: Points::from_f64(115.0).unwrap_or_default(),
+                players: Vec::new(),
+            };
+            matchups.push(Matchup {
+                teams: vec![side(a, a_points), side(b, b_points)],
+            });
+        }
+        Scoreboard {
+            league_key: self.config.league_key.clone(),
+            week: self.week as i32,
+            matchups,
+        }
+    }
+
+    /// Add every team's points this week to its total, for leagues without matchups
+    fn score_week(&mut self) {
+        for team in 0..self.records.len() {
+            let points = self.score(team);
+            self.records[team].points_for += points;
+        }
+    }
+
+    /// A team's points this week, in a range typical of the sport
+    fn score(&self, team: usize) -> Points {
+        let (base, spread) = score_range(self.config.sport());
+        let roll = self.roll(("score", self.week, team));
+        // Always finite: a small base plus a bounded roll
+        Points::from_f64(base + (roll % spread) as f64 / 10.0).unwrap_or_default()
+    }
+
+    /// Up to two add/drops a week by random teams, dated mid-week
+    fn waiver_moves(&mut self) -> Vec<Transaction> {
+        let count = self.roll(("moves", self.week)) % 3;
+        let game = self.config.sport().to_string();
+        let mut made = Vec::new();
+        for i in 0..count {
+            let roll = self.roll(("move", self.week, i));
+            let team = self.team_key((roll % self.records.len() as u64) as usize);
+            let added = 40_000 + (roll >> 16) % 5_000;
+            let dropped = 30_000 + (roll >> 32) % 5_000;
+            let at = time::start_of_day(self.week_range(self.week).start + Duration::days(2)) + Duration::minutes(i as i64);
+            let player = |id: u64, source: Option<String>, destination: Option<String>| TransactionPlayer {
+                player_key: format!("{}.p.{}", game, id),
+                name: format!("Player {}", id),
+                source_team_key: source,
+                destination_team_key: destination,
+            };
+            made.push(Transaction {
+                transaction_key: format!("{}.tr.{}", self.config.league_key, self.transactions.len() + made.len() + 1),
+                kind: TransactionType::AddDrop,
+                status: "successful".to_string(),
+                timestamp: at.timestamp(),
+                players: vec![player(added, None, Some(team.clone())), player(dropped, Some(team), None)],
+                by_commissioner: false,
+            });
+        }
+        made.reverse();
+        self.transactions.splice(0..0, made.iter().cloned());
+        made
+    }
+
+    fn roll(&self, key: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (self.config.seed, &self.config.league_key, key).hash(&mut hasher);
+        hasher.finish()
+    }
+}
@@ -0,0 +1,534 @@
+//! Multi-user sessions
+//! Stores many users' OAuth tokens, refreshes them before they expire, and hands out per-user clients
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::login::RefreshState;
+use crate::{AccessToken, BatchResult, CancellationToken, KeyError, TokenRevoked, YahooFantasyClient};
+
+/// How long before expiry a token is refreshed
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// Share of a token's lifetime after which `keep_token_fresh` refreshes it
+pub const PROACTIVE_REFRESH_FRACTION: f64 = 0.8;
+
+/// How often `SessionManager::run` looks for tokens due a refresh
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A user's tokens and when they stop working
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StoredToken {
+    pub token: AccessToken,
+    /// `None` when Yahoo didn't say
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl StoredToken {
+    /// Tokens obtained at `obtained_at`, expiring `token.expires_in` seconds later
+    pub fn new(token: AccessToken, obtained_at: DateTime<Utc>) -> Self {
+        let expires_at = token.expires_in.map(|secs| obtained_at + ChronoDuration::seconds(secs as i64));
+        Self { token, expires_at }
+    }
+
+    pub fn is_expired(&self, now: &DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= *now)
+    }
+
+    /// Whether the token can be refreshed and expires within `margin` of `now`
+    pub fn needs_refresh(&self, margin: Duration, now: &DateTime<Utc>) -> bool {
+        let margin = ChronoDuration::from_std(margin).unwrap_or(ChronoDuration::MAX);
+        self.token.session_handle.is_some() && self.expires_at.is_some_and(|at| at - *now <= margin)
+    }
+
+    /// When `keep_token_fresh` refreshes the token: `PROACTIVE_REFRESH_FRACTION`
+    /// of its lifetime, or `DEFAULT_REFRESH_MARGIN` before expiry when the
+    /// lifetime is unknown. `None` if it can't be refreshed or never expires.
+    pub fn proactive_refresh_at(&self) -> Option<DateTime<Utc>> {
+        let expires_at = self.expires_at.filter(|_| self.token.session_handle.is_some())?;
+        let early = match self.token.expires_in {
+            Some(lifetime) => ChronoDuration::milliseconds((lifetime as f64 * (1.0 - PROACTIVE_REFRESH_FRACTION) * 1000.0) as i64),
+            None => ChronoDuration::from_std(DEFAULT_REFRESH_MARGIN).unwrap_or(ChronoDuration::zero()),
+        };
+        Some(expires_at - early)
+    }
+
+    /// How long the token has left as of `now`, and whether it can be refreshed
+    pub fn status(&self, now: &DateTime<Utc>) -> TokenStatus {
+        let refreshable = self.token.session_handle.is_some();
+        let margin = ChronoDuration::from_std(DEFAULT_REFRESH_MARGIN).unwrap_or(ChronoDuration::zero());
+        TokenStatus {
+            expires_at: self.expires_at,
+            remaining: self.expires_at.map(|at| (at - *now).to_std().unwrap_or(Duration::ZERO)),
+            refreshable,
+            refresh_at: self.expires_at.filter(|_| refreshable).map(|at| at - margin),
+        }
+    }
+}
+
+/// How long a client's token has left, for warning users before they have to
+/// log in again or scheduling refreshes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct TokenStatus {
+    /// `None` when Yahoo didn't say
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Validity left, zero once expired; `None` when the expiry is unknown
+    pub remaining: Option<Duration>,
+    /// Whether the token has a session handle to refresh it with
+    pub refreshable: bool,
+    /// When the client refreshes the token on its next request, `DEFAULT_REFRESH_MARGIN`
+    /// before expiry; `None` if it can't be refreshed or the expiry is unknown
+    pub refresh_at: Option<DateTime<Utc>>,
+}
+
+impl TokenStatus {
+    pub fn is_expired(&self) -> bool {
+        self.remaining == Some(Duration::ZERO)
+    }
+
+    /// Whether the user will have to log in again once the token expires
+    pub fn needs_login(&self) -> bool {
+        !self.refreshable && self.expires_at.is_some()
+    }
+}
+
+/// Where users' tokens are kept between runs. The client loads its token from
+/// here when built with `ClientBuilder::token_store` and saves every refresh back.
+pub trait TokenStore: Send + Sync {
+    /// `user`'s saved token, if any
+    fn load(&self, user: &str) -> Result<Option<StoredToken>, Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Save `token` for `user`, replacing any earlier one
+    fn save(&self, user: &str, token: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Forget `user`'s token
+    fn clear(&self, user: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Users with saved tokens, in order
+    fn users(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+impl fmt::Debug for dyn TokenStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TokenStore")
+    }
+}
+
+/// Tokens by user id that live only as long as the process
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore {
+    tokens: Mutex<BTreeMap<String, StoredToken>>,
+}
+
+impl MemoryTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self, user: &str) -> Result<Option<StoredToken>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tokens.lock().unwrap().get(user).cloned())
+    }
+
+    fn save(&self, user: &str, token: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tokens.lock().unwrap().insert(user.to_string(), token.clone());
+        Ok(())
+    }
+
+    fn clear(&self, user: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.tokens.lock().unwrap().remove(user);
+        Ok(())
+    }
+
+    fn users(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tokens.lock().unwrap().keys().cloned().collect())
+    }
+}
+
+/// Tokens by user id in a JSON file that is rewritten on every change. The
+/// file holds secrets; keep it private.
+#[derive(Debug)]
+pub struct FileTokenStore {
+    path: PathBuf,
+    tokens: Mutex<BTreeMap<String, StoredToken>>,
+}
+
+impl FileTokenStore {
+    /// Load the store from `path` if it exists; changes are written back immediately
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let tokens = match std::fs::read_to_string(&path) {
+            Ok(json) => {
+                let file: serde_json::Value = serde_json::from_str(&json)?;
+                if is_encrypted(&file) {
+                    return Err(format!("{} is encrypted; open it with EncryptedFileTokenStore", path.display()).into());
+                }
+                serde_json::from_value(file)?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path,
+            tokens: Mutex::new(tokens),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the store to its file, atomically replacing the previous contents
+    fn persist(&self, tokens: &BTreeMap<String, StoredToken>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        write_atomically(&self.path, &serde_json::to_vec(tokens)?)
+    }
+}
+
+/// Whether a token file is an `EncryptedFileTokenStore` envelope: a top-level
+/// string `ciphertext` and `nonce`, where a plain store only has token objects
+fn is_encrypted(file: &serde_json::Value) -> bool {
+    ["ciphertext", "nonce"].iter().all(|field| file.get(field).is_some_and(serde_json::Value::is_string))
+}
+
+/// Replace `path` with `contents` through a synced temporary file, so a crash
+/// leaves either the old file or the new one, never a partial write that loses
+/// a rotated session handle
+pub(crate) fn write_atomically(path: &Path, contents: &[u8]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let tmp = path.with_extension("tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Owner-only from creation, so the tokens are never readable by others before the rename
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    // `mode` only applies to new files; a temp file left by a crash keeps its own
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self, user: &str) -> Result<Option<StoredToken>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tokens.lock().unwrap().get(user).cloned())
+    }
+
+    fn save(&self, user: &str, token: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.insert(user.to_string(), token.clone());
+        self.persist(&tokens)
+    }
+
+    fn clear(&self, user: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tokens = self.tokens.lock().unwrap();
+        if tokens.remove(user).is_some() {
+            self.persist(&tokens)?;
+        }
+        Ok(())
+    }
+
+    fn users(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(self.tokens.lock().unwrap().keys().cloned().collect())
+    }
+}
+
+/// Service tokens are filed under in the OS credential store by default
+#[cfg(feature = "keyring")]
+pub const DEFAULT_KEYRING_SERVICE: &str = "yahoo-fantasy-sdk";
+
+/// Account listing the users with tokens, since credential stores can't be enumerated
+#[cfg(feature = "keyring")]
+const KEYRING_INDEX: &str = ".users";
+
+/// Tokens in the OS credential store (macOS Keychain, Windows Credential
+/// Manager, or the Secret Service on Linux), one entry per user, so they are
+/// never written to a plaintext file
+#[cfg(feature = "keyring")]
+#[derive(Debug)]
+pub struct KeyringTokenStore {
+    service: String,
+    /// Held while the user index is read and rewritten
+    index: Mutex<()>,
+}
+
+#[cfg(feature = "keyring")]
+impl Default for KeyringTokenStore {
+    fn default() -> Self {
+        Self::with_service(DEFAULT_KEYRING_SERVICE)
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl KeyringTokenStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store filed under `service`, e.g. the app's name, so apps don't share tokens
+    pub fn with_service(service: &str) -> Self {
+        Self {
+            service: service.to_string(),
+            index: Mutex::new(()),
+        }
+    }
+
+    fn entry(&self, account: &str) -> Result<keyring::Entry, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(keyring::Entry::new(&self.service, account)?)
+    }
+
+    fn read(&self, account: &str) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.entry(account)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn read_index(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.read(KEYRING_INDEX)? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Add or remove `user` from the index
+    fn update_index(&self, user: &str, present: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let _guard = self.index.lock().unwrap();
+        let mut users = self.read_index()?;
+        if users.iter().any(|u| u == user) == present {
+            return Ok(());
+        }
+        if present {
+            users.push(user.to_string());
+            users.sort();
+        } else {
+            users.retain(|u| u != user);
+        }
+        self.entry(KEYRING_INDEX)?.set_password(&serde_json::to_string(&users)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "keyring")]
+impl TokenStore for KeyringTokenStore {
+    fn load(&self, user: &str) -> Result<Option<StoredToken>, Box<dyn std::error::Error + Send + Sync>> {
+        match self.read(user)? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn save(&self, user: &str, token: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.entry(user)?.set_password(&serde_json::to_string(token)?)?;
+        self.update_index(user, true)
+    }
+
+    fn clear(&self, user: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self.entry(user)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.into()),
+        }
+        self.update_index(user, false)
+    }
+
+    fn users(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let _guard = self.index.lock().unwrap();
+        self.read_index()
+    }
+}
+
+/// A token store and the user a client's token is saved under
+#[derive(Debug, Clone)]
+pub(crate) struct StoreBinding {
+    pub(crate) store: Arc<dyn TokenStore>,
+    pub(crate) user: String,
+}
+
+impl YahooFantasyClient {
+    /// Refresh the client's token in the background at `proactive_refresh_at`,
+    /// so the first request after an idle period doesn't wait for a refresh.
+    /// Runs until `cancel` fires or the client shuts down, retrying failed
+    /// refreshes every minute and passing their errors to `on_error`. Fails if
+    /// the token can't be refreshed at all, or with `TokenRevoked`.
+    pub async fn keep_token_fresh(&self, cancel: &CancellationToken, mut on_error: impl FnMut(&(dyn std::error::Error + Send + Sync))) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        while !cancel.is_cancelled() && !self.is_shut_down() {
+            let wait = match self.stored_token() {
+                Some(current) if current.token.session_handle.is_none() => return Err("the access token has no session handle to refresh with".into()),
+                Some(current) => match current.proactive_refresh_at() {
+                    Some(at) if at <= Utc::now() => match self.refresh_stored_token(&current).await {
+                        Ok(()) => continue,
+                        Err(e) if e.is::<TokenRevoked>() => return Err(e),
+                        Err(e) => {
+                            on_error(e.as_ref());
+                            REFRESH_CHECK_INTERVAL
+                        }
+                    },
+                    // Wake up now and then, in case the token is replaced
+                    Some(at) => (at - Utc::now()).to_std().unwrap_or(Duration::ZERO).min(REFRESH_CHECK_INTERVAL),
+                    None => REFRESH_CHECK_INTERVAL,
+                },
+                None => REFRESH_CHECK_INTERVAL,
+            };
+            tokio::select! {
+                _ = tokio::time::sleep(wait) => {}
+                _ = cancel.cancelled() => break,
+                _ = self.shutdown.cancelled() => break,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Signed-in users of a hosted app. Each user gets a client sharing the app
+/// client's rate limiter and transport, reused until their token changes so
+/// their response cache survives between requests. Those clients save tokens
+/// they refresh themselves back to the store, and every refresh of a user's
+/// token, by the manager or any of their clients, waits on the same lock.
+#[derive(Debug)]
+pub struct SessionManager {
+    client: YahooFantasyClient,
+    store: Arc<dyn TokenStore>,
+    refresh_margin: Duration,
+    clients: Mutex<HashMap<String, Arc<YahooFantasyClient>>>,
+    /// Refresh lock for each user, shared by all of the user's clients
+    refreshes: Mutex<HashMap<String, Arc<tokio::sync::Mutex<RefreshState>>>>,
+}
+
+impl SessionManager {
+    pub fn new(client: YahooFantasyClient, store: Arc<dyn TokenStore>) -> Self {
+        Self {
+            client,
+            store,
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+            clients: Mutex::new(HashMap::new()),
+            refreshes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Refresh tokens this long before they expire
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// The shared app-level client
+    pub fn client(&self) -> &YahooFantasyClient {
+        &self.client
+    }
+
+    pub fn store(&self) -> &dyn TokenStore {
+        self.store.as_ref()
+    }
+
+    /// Sign `user` in with tokens just returned by `get_access_token`
+    pub fn insert(&self, user: &str, token: AccessToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.store.save(user, &StoredToken::new(token, Utc::now()))
+    }
+
+    /// Sign `user` out, forgetting their tokens and client
+    pub fn remove(&self, user: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.clients.lock().unwrap().remove(user);
+        self.refreshes.lock().unwrap().remove(user);
+        self.store.clear(user)
+    }
+
+    /// Client acting as `user`, refreshing their token first if it is about to expire
+    pub async fn client_for(&self, user: &str) -> Result<Arc<YahooFantasyClient>, Box<dyn std::error::Error + Send + Sync>> {
+        let client = self.cached_client(user)?.ok_or_else(|| format!("no session for user {}", user))?;
+        let stored = client.stored_token().ok_or_else(|| format!("no session for user {}", user))?;
+        if stored.needs_refresh(self.refresh_margin, &Utc::now()) {
+            match client.refresh_stored_token(&stored).await {
+                Ok(()) => {}
+                // The old token still works until it expires; try again next time
+                Err(_) if !stored.is_expired(&Utc::now()) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(client)
+    }
+
+    /// The user's client, reused while its token matches the store's
+    fn cached_client(&self, user: &str) -> Result<Option<Arc<YahooFantasyClient>>, Box<dyn std::error::Error + Send + Sync>> {
+        let Some(stored) = self.store.load(user)? else {
+            return Ok(None);
+        };
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(user) {
+            if client.stored_token().is_some_and(|current| current.token == stored.token) {
+                return Ok(Some(client.clone()));
+            }
+        }
+        let mut client = self.client.for_stored_token(stored);
+        client.token_store = Some(StoreBinding {
+            store: self.store.clone(),
+            user: user.to_string(),
+        });
+        client.refresh = self.refreshes.lock().unwrap().entry(user.to_string()).or_default().clone();
+        let client = Arc::new(client);
+        clients.insert(user.to_string(), client.clone());
+        Ok(Some(client))
+    }
+
+    /// Refresh every token expiring within the refresh margin; returns the users
+    /// refreshed. Failing to list the store's users is reported under an empty key.
+    pub async fn refresh_due(&self) -> BatchResult<String> {
+        let mut result = BatchResult::default();
+        let users = match self.store.users() {
+            Ok(users) => users,
+            Err(error) => {
+                result.failed.push(KeyError { key: String::new(), error });
+                return result;
+            }
+        };
+        for user in users {
+            let client = match self.cached_client(&user) {
+                Ok(Some(client)) => client,
+                Ok(None) => continue,
+                Err(error) => {
+                    result.failed.push(KeyError { key: user, error });
+                    continue;
+                }
+            };
+            let Some(stored) = client.stored_token().filter(|stored| stored.needs_refresh(self.refresh_margin, &Utc::now())) else {
+                continue;
+            };
+            match client.refresh_stored_token(&stored).await {
+                Ok(()) => result.succeeded.push(user),
+                Err(error) => result.failed.push(KeyError { key: user, error }),
+            }
+        }
+        result
+    }
+
+    /// Refresh due tokens every minute until `cancel` fires or the client shuts
+    /// down, passing each failed refresh to `on_error`
+    pub async fn run(&self, cancel: &CancellationToken, mut on_error: impl FnMut(&KeyError)) {
+        while !cancel.is_cancelled() && !self.client.is_shut_down() {
+            for error in &self.refresh_due().await.failed {
+                on_error(error);
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(REFRESH_CHECK_INTERVAL) => {}
+                _ = cancel.cancelled() => break,
+            }
+        }
+    }
+}
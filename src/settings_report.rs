@@ -0,0 +1,256 @@
+//! League settings report
+//! Normalizes `LeagueSettings` into labeled sections that can be rendered or diffed
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{LeagueSettings, Tiebreaker};
+
+/// One labeled setting, e.g. "Waiver period" = "2 days"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReportEntry {
+    pub label: String,
+    pub value: String,
+}
+
+/// A group of related settings
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ReportSection {
+    pub title: String,
+    pub entries: Vec<ReportEntry>,
+}
+
+/// Human-readable settings, normalized so two reports can be compared entry by entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct SettingsReport {
+    pub league_key: String,
+    pub sections: Vec<ReportSection>,
+}
+
+/// A difference between two settings reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum SettingChange {
+    Added {
+        section: String,
+        label: String,
+        value: String,
+    },
+    Removed {
+        section: String,
+        label: String,
+        value: String,
+    },
+    Changed {
+        section: String,
+        label: String,
+        before: String,
+        after: String,
+    },
+}
+
+impl SettingsReport {
+    pub fn from_settings(settings: &LeagueSettings) -> Self {
+        let general = vec![
+            entry("Scoring type", scoring_type_label(&settings.scoring_type)),
+            entry("Draft type", &settings.draft_type),
+        ];
+
+        let mut modifiers = settings.stat_modifiers.clone();
+        modifiers.sort_by_key(|m| m.stat_id);
+        let scoring = modifiers
+            .iter()
+            .map(|m| entry(&m.name, &m.value.to_string()))
+            .collect();
+
+        let roster = settings
+            .roster_slots
+            .iter()
+            .map(|s| {
+                let role = if s.is_starting { "starting" } else { "reserve" };
+                entry(&s.position, &format!("{} ({})", s.count, role))
+            })
+            .collect();
+
+        let mut waivers = vec![
+            entry("Waiver type", waiver_type_label(&settings.waiver_type)),
+            entry("Waiver period", &days(settings.waiver_days)),
+        ];
+        if let Some(budget) = settings.faab_budget {
+            waivers.push(entry("FAAB budget", &format!("${}", budget)));
+        }
+        waivers.push(entry("Trade deadline", settings.trade_end_date.as_deref().unwrap_or("None")));
+
+        let playoffs = vec![
+            entry("Playoff teams", &optional(settings.num_playoff_teams)),
+            entry("Playoff start week", &optional(settings.playoff_start_week)),
+            entry("Consolation games", yes_no(settings.has_playoff_consolation_games)),
+            entry("Reseeding", yes_no(settings.uses_playoff_reseeding)),
+            entry("Tiebreakers", &tiebreakers(&settings.tiebreakers)),
+        ];
+
+        Self {
+            league_key: settings.league_key.clone(),
+            sections: vec![
+                section("General", general),
+                section("Scoring", scoring),
+                section("Roster", roster),
+                section("Waivers", waivers),
+                section("Playoffs", playoffs),
+            ],
+        }
+    }
+
+    /// Changes from `self` to `other`, in section order
+    pub fn diff(&self, other: &SettingsReport) -> Vec<SettingChange> {
+        let mut changes = Vec::new();
+
+        for before in &self.sections {
+            let after = other.section(&before.title);
+            for old in &before.entries {
+                match after.and_then(|s| s.entry(&old.label)) {
+                    Some(new) if new.value != old.value => changes.push(SettingChange::Changed {
+                        section: before.title.clone(),
+                        label: old.label.clone(),
+                        before: old.value.clone(),
+                        after: new.value.clone(),
+                    }),
+                    Some(_) => {}
+                    None => changes.push(SettingChange::Removed {
+                        section: before.title.clone(),
+                        label: old.label.clone(),
+                        value: old.value.clone(),
+                    }),
+                }
+            }
+        }
+
+        for after in &other.sections {
+            let before = self.section(&after.title);
+            for new in &after.entries {
+                if before.and_then(|s| s.entry(&new.label)).is_none() {
+                    changes.push(SettingChange::Added {
+                        section: after.title.clone(),
+                        label: new.label.clone(),
+                        value: new.value.clone(),
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+
+    pub fn section(&self, title: &str) -> Option<&ReportSection> {
+        self.sections.iter().find(|s| s.title == title)
+    }
+}
+
+impl ReportSection {
+    pub fn entry(&self, label: &str) -> Option<&ReportEntry> {
+        self.entries.iter().find(|e| e.label == label)
+    }
+}
+
+/// Plain-text rendering, one indented line per setting
+impl fmt::Display for SettingsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "League {}", self.league_key)?;
+        for section in &self.sections {
+            writeln!(f)?;
+            writeln!(f, "{}", section.title)?;
+            let width = section.entries.iter().map(|e| e.label.len()).max().unwrap_or(0);
+            for entry in &section.entries {
+                writeln!(f, "  {:width$}  {}", entry.label, entry.value, width = width)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SettingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingChange::Added { section, label, value } => write!(f, "+ {} / {}: {}", section, label, value),
+            SettingChange::Removed { section, label, value } => write!(f, "- {} / {}: {}", section, label, value),
+            SettingChange::Changed {
+                section,
+                label,
+                before,
+                after,
+            } => write!(f, "~ {} / {}: {} -> {}", section, label, before, after),
+        }
+    }
+}
+
+fn entry(label: &str, value: &str) -> ReportEntry {
+    ReportEntry {
+        label: label.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn section(title: &str, entries: Vec<ReportEntry>) -> ReportSection {
+    ReportSection {
+        title: title.to_string(),
+        entries,
+    }
+}
+
+fn scoring_type_label(code: &str) -> &str {
+    match code {
+        "head" => "Head-to-head points",
+        "headone" => "Head-to-head one win",
+        "point" => "Total points",
+        "roto" => "Rotisserie",
+        other => other,
+    }
+}
+
+fn waiver_type_label(code: &str) -> &str {
+    match code {
+        "R" => "Rolling list",
+        "FR" => "Continual rolling list",
+        "FCFS" => "First come, first served",
+        other => other,
+    }
+}
+
+fn tiebreakers(rules: &[Tiebreaker]) -> String {
+    if rules.is_empty() {
+        return String::from("None");
+    }
+    rules
+        .iter()
+        .map(|rule| match rule {
+            Tiebreaker::HeadToHead => "Head-to-head",
+            Tiebreaker::PointsFor => "Points for",
+            Tiebreaker::PointsAgainst => "Points against",
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn days(n: u32) -> String {
+    if n == 1 {
+        String::from("1 day")
+    } else {
+        format!("{} days", n)
+    }
+}
+
+fn optional(value: Option<u32>) -> String {
+    value.map_or_else(|| String::from("None"), |v| v.to_string())
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "Yes"
+    } else {
+        "No"
+    }
+}
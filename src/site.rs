@@ -0,0 +1,396 @@
+//! Static league history site
+//! Renders a league archive into self-contained HTML pages from templates embedded in the crate
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::DraftPick;
+use crate::progress::ProgressTracker;
+use crate::{time, League, Points, Progress, Scoreboard, Standings, Transaction, Workflow, YahooFantasyClient};
+
+/// Page shell; `{title}`, `{league}`, `{nav}`, and `{content}` are filled in per page
+const LAYOUT: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} · {league}</title>
+<link rel="stylesheet" href="style.css">
+</head>
+<body>
+<header><h1>{league}</h1><nav>{nav}</nav></header>
+<main>
+<h2>{title}</h2>
+{content}
+</main>
+</body>
+</html>
+"#;
+
+const STYLESHEET: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:0 auto;padding:1rem;color:#222}\
+header{border-bottom:1px solid #ccc;margin-bottom:1rem}\
+nav a{margin-right:1rem}\
+table{border-collapse:collapse;width:100%;margin-bottom:1.5rem}\
+th,td{text-align:left;padding:.3rem .6rem;border-bottom:1px solid #eee}\
+td.num,th.num{text-align:right}\n";
+
+/// Pages in navigation order, as (file name, title)
+const PAGES: &[(&str, &str)] = &[
+    ("index.html", "Overview"),
+    ("standings.html", "Standings"),
+    ("schedule.html", "Schedule"),
+    ("draft.html", "Draft"),
+    ("transactions.html", "Transactions"),
+    ("records.html", "Records"),
+];
+
+/// Everything a league history site shows for one season
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LeagueArchive {
+    pub league_key: String,
+    pub name: String,
+    pub season: Option<i32>,
+    pub standings: Standings,
+    /// One scoreboard per completed week
+    pub scoreboards: Vec<Scoreboard>,
+    pub draft: Vec<DraftPick>,
+    pub transactions: Vec<Transaction>,
+    /// Player names by key; players without one are shown by key
+    #[serde(default)]
+    pub player_names: BTreeMap<String, String>,
+}
+
+/// A single-game record, e.g. the highest weekly score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct GameRecord {
+    pub label: String,
+    pub week: i32,
+    pub team_key: String,
+    pub opponent_key: String,
+    pub points: Points,
+    pub opponent_points: Points,
+}
+
+impl LeagueArchive {
+    fn team_name<'a>(&'a self, team_key: &'a str) -> &'a str {
+        self.standings
+            .teams
+            .iter()
+            .find(|t| t.team_key == team_key)
+            .map_or(team_key, |t| t.name.as_str())
+    }
+
+    fn player_name<'a>(&'a self, player_key: &'a str) -> &'a str {
+        self.player_names.get(player_key).map_or(player_key, String::as_str)
+    }
+
+    /// Highest and lowest scores, biggest blowout, and closest game
+    pub fn records(&self) -> Vec<GameRecord> {
+        let games: Vec<GameRecord> = self
+            .scoreboards
+            .iter()
+            .flat_map(|s| s.matchups.iter().map(move |m| (s.week, m)))
+            .filter_map(|(week, matchup)| {
+                let [a, b] = matchup.teams.as_slice() else {
+                    return None;
+                };
+                let (winner, loser) = if a.points >= b.points { (a, b) } else { (b, a) };
+                Some(GameRecord {
+                    label: String::new(),
+                    week,
+                    team_key: winner.team_key.clone(),
+                    opponent_key: loser.team_key.clone(),
+                    points: winner.points,
+                    opponent_points: loser.points,
+                })
+            })
+            .collect();
+
+        let labeled = |label: &str, game: Option<&GameRecord>| {
+            game.map(|g| GameRecord {
+                label: label.to_string(),
+                ..g.clone()
+            })
+        };
+        let margin = |g: &&GameRecord| g.points - g.opponent_points;
+        let lowest = games.iter().min_by_key(|g| g.opponent_points).map(|g| GameRecord {
+            team_key: g.opponent_key.clone(),
+            opponent_key: g.team_key.clone(),
+            points: g.opponent_points,
+            opponent_points: g.points,
+            ..g.clone()
+        });
+
+        [
+            labeled("Highest score", games.iter().max_by_key(|g| g.points)),
+            labeled("Lowest score", lowest.as_ref()),
+            labeled("Biggest blowout", games.iter().max_by_key(margin)),
+            labeled("Closest game", games.iter().min_by_key(margin)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Every page as (file name, HTML), plus the stylesheet
+    pub fn render(&self) -> Vec<(String, String)> {
+        let contents = [
+            self.overview_html(),
+            self.standings_html(),
+            self.schedule_html(),
+            self.draft_html(),
+            self.transactions_html(),
+            self.records_html(),
+        ];
+        let nav = PAGES
+            .iter()
+            .map(|(file, title)| format!("<a href=\"{}\">{}</a>", file, title))
+            .collect::<Vec<_>>()
+            .join("");
+        let league = escape(&self.name);
+
+        let mut files: Vec<(String, String)> = PAGES
+            .iter()
+            .zip(contents)
+            .map(|((file, title), content)| {
+                let html = LAYOUT
+                    .replace("{title}", title)
+                    .replace("{league}", &league)
+                    .replace("{nav}", &nav)
+                    .replace("{content}", &content);
+                (file.to_string(), html)
+            })
+            .collect();
+        files.push(("style.css".to_string(), STYLESHEET.to_string()));
+        files
+    }
+
+    /// Write the site into `dir`, creating it if needed; returns the files written
+    pub fn export(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, Box<dyn std::error::Error + Send + Sync>> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut written = Vec::new();
+        for (file, contents) in self.render() {
+            let path = dir.join(file);
+            std::fs::write(&path, contents)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    fn overview_html(&self) -> String {
+        let season = self.season.map_or_else(String::new, |s| format!("<p>Season {}</p>\n", s));
+        let leader = self
+            .standings
+            .teams
+            .iter()
+            .min_by_key(|t| t.rank)
+            .map_or_else(String::new, |t| {
+                format!("<p>First place after week {}: <strong>{}</strong></p>\n", self.standings.week, escape(&t.name))
+            });
+        format!(
+            "{}{}<p>{} teams · {} weeks played · {} transactions</p>\n",
+            season,
+            leader,
+            self.standings.teams.len(),
+            self.scoreboards.len(),
+            self.transactions.len()
+        )
+    }
+
+    fn standings_html(&self) -> String {
+        let mut teams: Vec<_> = self.standings.teams.iter().collect();
+        teams.sort_by_key(|t| t.rank);
+        let rows = teams
+            .iter()
+            .map(|t| {
+                format!(
+                    "<tr><td class=\"num\">{}</td><td>{}</td><td>{}-{}-{}</td><td class=\"num\">{}</td></tr>\n",
+                    t.rank,
+                    escape(&t.name),
+                    t.wins,
+                    t.losses,
+                    t.ties,
+                    t.points_for
+                )
+            })
+            .collect::<String>();
+        table(&["#", "Team", "Record", "Points for"], &rows)
+    }
+
+    fn schedule_html(&self) -> String {
+        let mut html = String::new();
+        for scoreboard in &self.scoreboards {
+            html.push_str(&format!("<h3>Week {}</h3>\n", scoreboard.week));
+            let rows = scoreboard
+                .matchups
+                .iter()
+                .map(|m| {
+                    let cells = m
+                        .teams
+                        .iter()
+                        .map(|t| format!("<td>{}</td><td class=\"num\">{}</td>", escape(self.team_name(&t.team_key)), t.points))
+                        .collect::<String>();
+                    format!("<tr>{}</tr>\n", cells)
+                })
+                .collect::<String>();
+            html.push_str(&table(&["Team", "Points", "Opponent", "Points"], &rows));
+        }
+        html
+    }
+
+    fn draft_html(&self) -> String {
+        let has_costs = self.draft.iter().any(|p| p.cost.is_some());
+        let rows = self
+            .draft
+            .iter()
+            .map(|p| {
+                let cost = if has_costs {
+                    format!("<td class=\"num\">{}</td>", p.cost.map_or_else(String::new, |c| format!("${}", c)))
+                } else {
+                    String::new()
+                };
+                format!(
+                    "<tr><td class=\"num\">{}</td><td class=\"num\">{}</td><td>{}</td><td>{}</td>{}</tr>\n",
+                    p.pick,
+                    p.round,
+                    escape(self.team_name(&p.team_key)),
+                    escape(self.player_name(&p.player_key)),
+                    cost
+                )
+            })
+            .collect::<String>();
+        let headers: &[&str] = if has_costs {
+            &["Pick", "Round", "Team", "Player", "Cost"]
+        } else {
+            &["Pick", "Round", "Team", "Player"]
+        };
+        table(headers, &rows)
+    }
+
+    fn transactions_html(&self) -> String {
+        let mut transactions: Vec<_> = self.transactions.iter().collect();
+        transactions.sort_by_key(|t| std::cmp::Reverse(t.timestamp));
+        let rows = transactions
+            .iter()
+            .map(|t| {
+                let date = time::from_timestamp(t.timestamp)
+                    .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_default();
+                let moves = t
+                    .players
+                    .iter()
+                    .map(|p| {
+                        let from = p.source_team_key.as_deref().map_or("Free agency", |k| self.team_name(k));
+                        let to = p.destination_team_key.as_deref().map_or("Waivers", |k| self.team_name(k));
+                        format!("{}: {} → {}", escape(&p.name), escape(from), escape(to))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("<br>");
+                let kind = serde_json::to_value(t.kind)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                format!("<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n", date, escape(&kind), moves)
+            })
+            .collect::<String>();
+        table(&["Date", "Type", "Moves"], &rows)
+    }
+
+    fn records_html(&self) -> String {
+        let rows = self
+            .records()
+            .iter()
+            .map(|r| {
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td class=\"num\">{}</td><td>vs {} ({})</td><td class=\"num\">{}</td></tr>\n",
+                    escape(&r.label),
+                    escape(self.team_name(&r.team_key)),
+                    r.points,
+                    escape(self.team_name(&r.opponent_key)),
+                    r.opponent_points,
+                    r.week
+                )
+            })
+            .collect::<String>();
+        table(&["Record", "Team", "Points", "Opponent", "Week"], &rows)
+    }
+}
+
+fn table(headers: &[&str], rows: &str) -> String {
+    let head = headers.iter().map(|h| format!("<th>{}</th>", h)).collect::<String>();
+    format!("<table>\n<thead><tr>{}</tr></thead>\n<tbody>\n{}</tbody>\n</table>\n", head, rows)
+}
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl YahooFantasyClient {
+    /// Collect a league's standings, weekly results, draft, and transactions into an archive
+    pub async fn league_archive(&self, league: &League) -> Result<LeagueArchive, Box<dyn std::error::Error + Send + Sync>> {
+        self.league_archive_with_progress(league, |_| {}).await
+    }
+
+    /// Like `league_archive`, calling `on_progress` before each request and when done
+    pub async fn league_archive_with_progress(
+        &self,
+        league: &League,
+        on_progress: impl FnMut(&Progress),
+    ) -> Result<LeagueArchive, Box<dyn std::error::Error + Send + Sync>> {
+        let league_key = league.league_key.as_str();
+        let weeks = |week: i32| Workflow::LeagueArchive { weeks: week.max(0) as u32 }.requests();
+        let mut progress = ProgressTracker::new(weeks(league.current_week), on_progress);
+
+        progress.start("standings");
+        let standings = self.get_standings(league_key).await?;
+        progress.complete();
+        progress.set_total(weeks(standings.week));
+        let mut scoreboards = Vec::new();
+        for week in 1..=standings.week {
+            progress.start(format!("week {} scoreboard", week));
+            scoreboards.push(self.get_scoreboard(league_key, week).await?);
+            progress.complete();
+        }
+        progress.start("draft results");
+        let draft = self.get_draft_results(league_key).await?;
+        progress.complete();
+        progress.start("transactions");
+        let transactions = self.get_transactions(league_key).await?;
+        progress.complete();
+        progress.finish();
+
+        let player_names = transactions
+            .iter()
+            .flat_map(|t| &t.players)
+            .map(|p| (p.player_key.clone(), p.name.clone()))
+            .collect();
+
+        Ok(LeagueArchive {
+            league_key: league_key.to_string(),
+            name: league.name.clone(),
+            season: league.season,
+            standings,
+            scoreboards,
+            draft,
+            transactions,
+            player_names,
+        })
+    }
+}
@@ -0,0 +1,126 @@
+//! Standings movement tracking
+//! Detects rank, clinch/elimination, and waiver priority changes between snapshots
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Standings, TeamStanding};
+
+/// A change in a team's standing between two snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StandingsEvent {
+    RankChange {
+        team_key: String,
+        previous_rank: u32,
+        rank: u32,
+    },
+    ClinchedPlayoffs {
+        team_key: String,
+    },
+    Eliminated {
+        team_key: String,
+    },
+    WaiverPriorityChange {
+        team_key: String,
+        previous_priority: u32,
+        priority: u32,
+    },
+}
+
+/// Compare two standings snapshots of the same league
+pub fn diff_standings(previous: &Standings, current: &Standings) -> Vec<StandingsEvent> {
+    let mut events = Vec::new();
+    if previous.league_key != current.league_key {
+        return events;
+    }
+
+    for team in &current.teams {
+        let Some(before) = previous.teams.iter().find(|t| t.team_key == team.team_key) else {
+            continue;
+        };
+
+        if before.rank != team.rank {
+            events.push(StandingsEvent::RankChange {
+                team_key: team.team_key.clone(),
+                previous_rank: before.rank,
+                rank: team.rank,
+            });
+        }
+
+        if newly_set(before.clinched_playoffs, team.clinched_playoffs) {
+            events.push(StandingsEvent::ClinchedPlayoffs {
+                team_key: team.team_key.clone(),
+            });
+        }
+
+        if newly_set(before.eliminated, team.eliminated) {
+            events.push(StandingsEvent::Eliminated {
+                team_key: team.team_key.clone(),
+            });
+        }
+
+        if let (Some(previous_priority), Some(priority)) = (before.waiver_priority, team.waiver_priority) {
+            if previous_priority != priority {
+                events.push(StandingsEvent::WaiverPriorityChange {
+                    team_key: team.team_key.clone(),
+                    previous_priority,
+                    priority,
+                });
+            }
+        }
+    }
+
+    events
+}
+
+fn newly_set(before: Option<bool>, after: Option<bool>) -> bool {
+    after == Some(true) && before != Some(true)
+}
+
+/// Keeps per-week standings snapshots and reports movement as new ones arrive
+#[derive(Debug, Default)]
+pub struct StandingsTracker {
+    snapshots: Vec<Standings>,
+}
+
+impl StandingsTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a snapshot and return events relative to the latest earlier week.
+    /// Re-recording a week replaces its snapshot.
+    pub fn record(&mut self, standings: Standings) -> Vec<StandingsEvent> {
+        let events = self
+            .snapshots
+            .iter()
+            .filter(|s| s.league_key == standings.league_key && s.week < standings.week)
+            .max_by_key(|s| s.week)
+            .map(|previous| diff_standings(previous, &standings))
+            .unwrap_or_default();
+
+        self.snapshots
+            .retain(|s| !(s.league_key == standings.league_key && s.week == standings.week));
+        self.snapshots.push(standings);
+        events
+    }
+
+    /// Snapshot for a league week, if recorded
+    pub fn snapshot(&self, league_key: &str, week: i32) -> Option<&Standings> {
+        self.snapshots
+            .iter()
+            .find(|s| s.league_key == league_key && s.week == week)
+    }
+
+    /// A team's standing across all recorded weeks, in week order
+    pub fn history(&self, team_key: &str) -> Vec<(i32, &TeamStanding)> {
+        let mut history: Vec<(i32, &TeamStanding)> = self
+            .snapshots
+            .iter()
+            .filter_map(|s| s.teams.iter().find(|t| t.team_key == team_key).map(|t| (s.week, t)))
+            .collect();
+        history.sort_by_key(|(week, _)| *week);
+        history
+    }
+}
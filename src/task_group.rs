@@ -0,0 +1,139 @@
+//! Structured concurrency
+//! Runs many SDK calls concurrently with bounded concurrency and an overall deadline, collecting partial failures
+
+use std::future::{poll_fn, Future};
+use std::task::Poll;
+use std::time::Duration;
+
+use crate::{BatchResult, BoxFuture, KeyError, TimeoutError, YahooFantasyClient};
+
+/// Tasks run at once by default
+const DEFAULT_CONCURRENCY: usize = 4;
+
+type Task<'c, T> = BoxFuture<'c, Result<T, Box<dyn std::error::Error + Send + Sync>>>;
+
+/// A set of keyed SDK calls run together. Every call still goes through the
+/// client's rate limiter, so the limit is shared across the group.
+pub struct TaskGroup<'c, T> {
+    client: &'c YahooFantasyClient,
+    concurrency: usize,
+    deadline: Option<Duration>,
+    tasks: Vec<(String, Task<'c, T>)>,
+}
+
+impl<T> std::fmt::Debug for TaskGroup<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TaskGroup")
+            .field("concurrency", &self.concurrency)
+            .field("deadline", &self.deadline)
+            .field("tasks", &self.tasks.len())
+            .finish()
+    }
+}
+
+impl<'c, T> TaskGroup<'c, T> {
+    pub fn new(client: &'c YahooFantasyClient) -> Self {
+        Self {
+            client,
+            concurrency: DEFAULT_CONCURRENCY,
+            deadline: None,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Run at most `limit` tasks at once
+    pub fn concurrency(mut self, limit: usize) -> Self {
+        self.concurrency = limit.max(1);
+        self
+    }
+
+    /// Give up on tasks still unfinished `deadline` after `run` starts; they fail with `TimeoutError::Deadline`
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Queue a call, identified by `key` in the result. Tasks start in the order queued.
+    pub fn spawn<F, Fut>(&mut self, key: &str, task: F)
+    where
+        F: FnOnce(&'c YahooFantasyClient) -> Fut,
+        Fut: Future<Output = Result<T, Box<dyn std::error::Error + Send + Sync>>> + Send + 'c,
+    {
+        self.tasks.push((key.to_string(), Box::pin(task(self.client))));
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Run every task and collect the outcomes. Successes are `(key, value)`
+    /// pairs in the order queued; a failed task doesn't stop the others.
+    pub async fn run(self) -> BatchResult<(String, T)> {
+        let limit = self.concurrency;
+        let mut queued = self.tasks.into_iter().enumerate();
+        let mut active: Vec<(usize, String, Task<'c, T>)> = Vec::new();
+        let mut finished: Vec<(usize, String, T)> = Vec::new();
+        let mut failed = Vec::new();
+        let mut deadline = self.deadline.map(|d| (d, Box::pin(tokio::time::sleep(d))));
+
+        poll_fn(|cx| loop {
+            if let Some((limit, sleep)) = &mut deadline {
+                if sleep.as_mut().poll(cx).is_ready() {
+                    let unfinished = active.drain(..).map(|(_, key, _)| key).chain(queued.by_ref().map(|(_, (key, _))| key));
+                    failed.extend(unfinished.map(|key| KeyError {
+                        key,
+                        error: Box::new(TimeoutError::Deadline(*limit)),
+                    }));
+                    return Poll::Ready(());
+                }
+            }
+
+            while active.len() < limit {
+                match queued.next() {
+                    Some((index, (key, task))) => active.push((index, key, task)),
+                    None => break,
+                }
+            }
+            if active.is_empty() {
+                return Poll::Ready(());
+            }
+
+            let running = active.len();
+            let mut i = 0;
+            while i < active.len() {
+                match active[i].2.as_mut().poll(cx) {
+                    Poll::Ready(outcome) => {
+                        let (index, key, _) = active.swap_remove(i);
+                        match outcome {
+                            Ok(value) => finished.push((index, key, value)),
+                            Err(error) => failed.push(KeyError { key, error }),
+                        }
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+            // Nothing finished, so every task is waiting on a wake-up
+            if active.len() == running {
+                return Poll::Pending;
+            }
+        })
+        .await;
+
+        finished.sort_by_key(|(index, _, _)| *index);
+        BatchResult {
+            succeeded: finished.into_iter().map(|(_, key, value)| (key, value)).collect(),
+            failed,
+        }
+    }
+}
+
+impl YahooFantasyClient {
+    /// Group of concurrent calls sharing this client's rate limiter
+    pub fn task_group<T>(&self) -> TaskGroup<'_, T> {
+        TaskGroup::new(self)
+    }
+}
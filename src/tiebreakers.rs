@@ -0,0 +1,206 @@
+//! Standings computation and verification
+//! Orders teams from raw head-to-head results using the league's tiebreakers, and checks Yahoo's standings against that
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{HeadToHeadMatrix, HeadToHeadRecord};
+use crate::{Points, Standings, Tiebreaker, YahooFantasyClient};
+
+/// A team's place in standings computed from results
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ComputedStanding {
+    pub team_key: String,
+    pub rank: u32,
+    pub record: HeadToHeadRecord,
+    /// Still level with a neighbour after every tiebreaker; such teams are ordered by team key
+    pub unresolved_tie: bool,
+}
+
+/// A way Yahoo's standings disagree with the computed ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StandingsMismatch {
+    /// In one set of standings but not the other
+    MissingTeam { team_key: String },
+    Record {
+        team_key: String,
+        /// (wins, losses, ties)
+        reported: (u32, u32, u32),
+        computed: (u32, u32, u32),
+    },
+    PointsFor {
+        team_key: String,
+        reported: Points,
+        computed: Points,
+    },
+    Rank {
+        team_key: String,
+        reported: u32,
+        computed: u32,
+    },
+}
+
+/// Computed standings and every way Yahoo's differ. Mismatches point to a
+/// parsing bug or a tiebreaker the rules don't model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct StandingsVerification {
+    pub league_key: String,
+    pub computed: Vec<ComputedStanding>,
+    pub mismatches: Vec<StandingsMismatch>,
+}
+
+impl StandingsVerification {
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Rank every team in `matrix` by win percentage, breaking ties with `tiebreakers`
+/// in order. Whenever a rule splits a tied group, each smaller group starts over
+/// from the first rule, so head-to-head is recomputed among only the teams still tied.
+pub fn compute_standings(matrix: &HeadToHeadMatrix, tiebreakers: &[Tiebreaker]) -> Vec<ComputedStanding> {
+    let team_keys = matrix.team_keys();
+    let mut teams: Vec<(&str, HeadToHeadRecord)> = team_keys
+        .iter()
+        .map(|team_key| (*team_key, matrix.record_against(team_key, &team_keys)))
+        .collect();
+    teams.sort_by(|a, b| compare_win_percentage(&b.1, &a.1).then_with(|| a.0.cmp(b.0)));
+
+    let mut ordered = Vec::new();
+    for group in split_ties(teams, |a, b| compare_win_percentage(&b.1, &a.1)) {
+        order_group(matrix, group, tiebreakers, tiebreakers, &mut ordered);
+    }
+
+    ordered
+        .into_iter()
+        .enumerate()
+        .map(|(i, (team_key, record, unresolved_tie))| ComputedStanding {
+            team_key: team_key.to_string(),
+            rank: i as u32 + 1,
+            record,
+            unresolved_tie,
+        })
+        .collect()
+}
+
+fn order_group<'a>(
+    matrix: &HeadToHeadMatrix,
+    mut group: Vec<(&'a str, HeadToHeadRecord)>,
+    all_rules: &[Tiebreaker],
+    rules: &[Tiebreaker],
+    ordered: &mut Vec<(&'a str, HeadToHeadRecord, bool)>,
+) {
+    let Some((rule, remaining)) = rules.split_first().filter(|_| group.len() > 1) else {
+        let unresolved = group.len() > 1;
+        group.sort_by(|a, b| a.0.cmp(b.0));
+        ordered.extend(group.into_iter().map(|(team_key, record)| (team_key, record, unresolved)));
+        return;
+    };
+
+    let keys: Vec<&str> = group.iter().map(|(team_key, _)| *team_key).collect();
+    let compare = |a: &(&str, HeadToHeadRecord), b: &(&str, HeadToHeadRecord)| -> Ordering {
+        match rule {
+            Tiebreaker::HeadToHead => compare_win_percentage(&matrix.record_against(b.0, &keys), &matrix.record_against(a.0, &keys)),
+            Tiebreaker::PointsFor => b.1.points_for.cmp(&a.1.points_for),
+            Tiebreaker::PointsAgainst => a.1.points_against.cmp(&b.1.points_against),
+        }
+    };
+    group.sort_by(|a, b| compare(a, b).then_with(|| a.0.cmp(b.0)));
+
+    let size = group.len();
+    for subgroup in split_ties(group, compare) {
+        let next = if subgroup.len() < size { all_rules } else { remaining };
+        order_group(matrix, subgroup, all_rules, next, ordered);
+    }
+}
+
+/// Split a sorted list into runs that `compare` considers equal
+fn split_ties<T>(sorted: Vec<T>, compare: impl Fn(&T, &T) -> Ordering) -> Vec<Vec<T>> {
+    let mut groups: Vec<Vec<T>> = Vec::new();
+    for item in sorted {
+        match groups.last_mut() {
+            Some(group) if compare(&group[0], &item) == Ordering::Equal => group.push(item),
+            _ => groups.push(vec![item]),
+        }
+    }
+    groups
+}
+
+/// Compare win percentages exactly, counting ties as half a win
+fn compare_win_percentage(a: &HeadToHeadRecord, b: &HeadToHeadRecord) -> Ordering {
+    let fraction = |r: &HeadToHeadRecord| (u64::from(2 * r.wins + r.ties), u64::from(2 * r.games().max(1)));
+    let ((an, ad), (bn, bd)) = (fraction(a), fraction(b));
+    (an * bd).cmp(&(bn * ad))
+}
+
+/// Compare Yahoo's standings with computed ones. Ranks inside an unresolved tie aren't checked.
+pub fn verify_standings(reported: &Standings, computed: &[ComputedStanding]) -> Vec<StandingsMismatch> {
+    let mut mismatches = Vec::new();
+
+    for team in &reported.teams {
+        let Some(expected) = computed.iter().find(|c| c.team_key == team.team_key) else {
+            mismatches.push(StandingsMismatch::MissingTeam {
+                team_key: team.team_key.clone(),
+            });
+            continue;
+        };
+
+        let record = &expected.record;
+        if (team.wins, team.losses, team.ties) != (record.wins, record.losses, record.ties) {
+            mismatches.push(StandingsMismatch::Record {
+                team_key: team.team_key.clone(),
+                reported: (team.wins, team.losses, team.ties),
+                computed: (record.wins, record.losses, record.ties),
+            });
+        }
+        if !(team.points_for - record.points_for).is_negligible() {
+            mismatches.push(StandingsMismatch::PointsFor {
+                team_key: team.team_key.clone(),
+                reported: team.points_for,
+                computed: record.points_for,
+            });
+        }
+        if team.rank != expected.rank && !expected.unresolved_tie {
+            mismatches.push(StandingsMismatch::Rank {
+                team_key: team.team_key.clone(),
+                reported: team.rank,
+                computed: expected.rank,
+            });
+        }
+    }
+
+    for expected in computed {
+        if !reported.teams.iter().any(|t| t.team_key == expected.team_key) {
+            mismatches.push(StandingsMismatch::MissingTeam {
+                team_key: expected.team_key.clone(),
+            });
+        }
+    }
+    mismatches
+}
+
+impl YahooFantasyClient {
+    /// Recompute a league's standings from its scoreboards and tiebreaker settings and compare them to Yahoo's
+    pub async fn verify_standings(&self, league_key: &str) -> Result<StandingsVerification, Box<dyn std::error::Error + Send + Sync>> {
+        let settings = self.get_league_settings(league_key).await?;
+        let standings = self.get_standings(league_key).await?;
+        let mut scoreboards = Vec::new();
+        for week in 1..=standings.week {
+            scoreboards.push(self.get_scoreboard(league_key, week).await?);
+        }
+        let matrix = HeadToHeadMatrix::from_scoreboards(league_key, &scoreboards);
+
+        let computed = compute_standings(&matrix, &settings.tiebreakers);
+        let mismatches = verify_standings(&standings, &computed);
+        Ok(StandingsVerification {
+            league_key: league_key.to_string(),
+            computed,
+            mismatches,
+        })
+    }
+}
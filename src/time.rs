@@ -0,0 +1,100 @@
+//! Time-zone aware date handling
+//! Yahoo's fantasy calendar runs on US/Pacific; that assumption lives here and nowhere else
+
+use std::time::SystemTime;
+
+use chrono::{DateTime, Days, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+
+pub use yahoo_fantasy_models::WeekRange;
+
+/// Time zone Yahoo uses for coverage dates, lock times, and week boundaries
+pub const YAHOO_TZ: Tz = chrono_tz::US::Pacific;
+
+/// Date format used in API paths and payloads
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// An instant expressed in Yahoo's time zone
+pub type YahooDateTime = DateTime<Tz>;
+
+/// Current time in Yahoo's time zone
+pub fn now() -> YahooDateTime {
+    Utc::now().with_timezone(&YAHOO_TZ)
+}
+
+/// Today's coverage date, which rolls over at Pacific midnight rather than UTC
+pub fn today() -> NaiveDate {
+    now().date_naive()
+}
+
+/// Coverage date an instant falls on
+pub fn coverage_date(instant: &DateTime<impl TimeZone>) -> NaiveDate {
+    instant.with_timezone(&YAHOO_TZ).date_naive()
+}
+
+/// Parse a `YYYY-MM-DD` coverage date
+pub fn parse_date(raw: &str) -> Result<NaiveDate, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(NaiveDate::parse_from_str(raw.trim(), DATE_FORMAT)?)
+}
+
+pub fn format_date(date: NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Convert a Unix timestamp (as used for transaction times) to Yahoo time
+pub fn from_timestamp(secs: i64) -> Result<YahooDateTime, Box<dyn std::error::Error + Send + Sync>> {
+    DateTime::from_timestamp(secs, 0)
+        .map(|utc| utc.with_timezone(&YAHOO_TZ))
+        .ok_or_else(|| format!("timestamp {} out of range", secs).into())
+}
+
+/// Start of a coverage date, correct across DST transitions
+pub fn start_of_day(date: NaiveDate) -> YahooDateTime {
+    let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+    YAHOO_TZ
+        .from_local_datetime(&midnight)
+        .earliest()
+        // Midnight doesn't exist on this date; DST gaps in US/Pacific are at 2am, so this is unreachable
+        .unwrap_or_else(|| YAHOO_TZ.from_utc_datetime(&midnight))
+}
+
+/// Half-open `[start, end)` bounds of a coverage date
+pub fn day_bounds(date: NaiveDate) -> (YahooDateTime, YahooDateTime) {
+    let next = date.checked_add_days(Days::new(1)).unwrap_or(date);
+    (start_of_day(date), start_of_day(next))
+}
+
+pub fn to_system_time(instant: &YahooDateTime) -> SystemTime {
+    SystemTime::from(instant.with_timezone(&Utc))
+}
+
+/// Instants bounding a `WeekRange`, which depend on Yahoo's time zone
+pub trait WeekInstants {
+    /// Whether an instant falls in this week, judged by its Pacific date
+    fn contains_instant<Z: TimeZone>(&self, instant: &DateTime<Z>) -> bool;
+
+    /// Instant the week begins
+    fn starts_at(&self) -> YahooDateTime;
+
+    /// Instant the week ends (exclusive)
+    fn ends_at(&self) -> YahooDateTime;
+}
+
+impl WeekInstants for WeekRange {
+    fn contains_instant<Z: TimeZone>(&self, instant: &DateTime<Z>) -> bool {
+        self.contains(coverage_date(instant))
+    }
+
+    fn starts_at(&self) -> YahooDateTime {
+        start_of_day(self.start)
+    }
+
+    fn ends_at(&self) -> YahooDateTime {
+        day_bounds(self.end).1
+    }
+}
+
+/// Week containing `date`, if any
+pub fn week_for_date(weeks: &[WeekRange], date: NaiveDate) -> Option<&WeekRange> {
+    weeks.iter().find(|w| w.contains(date))
+}
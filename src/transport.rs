@@ -0,0 +1,527 @@
+//! Transport request types
+//! HTTP verbs, bodies, the caching/retry semantics that follow from them, and the pluggable HTTP layer
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::xml::XmlBody;
+use crate::http::TimeoutError;
+use crate::{BoxFuture, Secret};
+
+// Without a TLS backend reqwest builds a client that can't reach Yahoo's HTTPS API
+#[cfg(not(any(feature = "rustls-tls", feature = "native-tls")))]
+compile_error!("enable the `rustls-tls` (default) or `native-tls` feature so requests can use HTTPS");
+
+/// Content type Yahoo expects for write bodies
+pub const XML_CONTENT_TYPE: &str = "application/xml";
+
+/// HTTP method of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl Method {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+        }
+    }
+
+    /// Whether repeating the request has the same effect as sending it once
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, Method::Post)
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A request ready to be signed and sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Request {
+    method: Method,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<String>,
+    force_retry: bool,
+    #[serde(default = "default_cost")]
+    cost: f64,
+    #[serde(default)]
+    options: RequestOptions,
+}
+
+/// Settings that override the client's defaults for one request
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct RequestOptions {
+    /// Bound on the whole request instead of the client's timeout, for endpoints that are routinely slow
+    pub timeout: Option<Duration>,
+}
+
+impl Request {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+            force_retry: false,
+            cost: 1.0,
+            options: RequestOptions::default(),
+        }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::Get, url)
+    }
+
+    pub fn post(url: impl Into<String>, body: &impl XmlBody) -> Self {
+        Self::new(Method::Post, url).with_body(body)
+    }
+
+    pub fn put(url: impl Into<String>, body: &impl XmlBody) -> Self {
+        Self::new(Method::Put, url).with_body(body)
+    }
+
+    pub fn delete(url: impl Into<String>) -> Self {
+        Self::new(Method::Delete, url)
+    }
+
+    /// Attach an XML body and the matching content type
+    pub fn with_body(mut self, body: &impl XmlBody) -> Self {
+        self.body = Some(body.to_xml());
+        self.set_header("Content-Type", XML_CONTENT_TYPE);
+        self
+    }
+
+    /// Add or replace a header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.set_header(name, value);
+        self
+    }
+
+    /// Allow automatic retries even though the method is not idempotent.
+    /// Only use this when the endpoint is known to deduplicate.
+    pub fn retry_non_idempotent(mut self) -> Self {
+        self.force_retry = true;
+        self
+    }
+
+    /// Rate limit tokens the request consumes; 1 unless set
+    pub fn with_cost(mut self, cost: f64) -> Self {
+        self.cost = cost.max(0.0);
+        self
+    }
+
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn method(&self) -> Method {
+        self.method
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn header_value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+
+    pub fn options(&self) -> &RequestOptions {
+        &self.options
+    }
+
+    /// Only reads are served from or stored in the response cache
+    pub fn is_cacheable(&self) -> bool {
+        self.method == Method::Get
+    }
+
+    /// Whether the transport may retry this request on a transient failure
+    pub fn is_retryable(&self) -> bool {
+        self.method.is_idempotent() || self.force_retry
+    }
+
+    fn set_header(&mut self, name: &str, value: &str) {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+}
+
+fn default_cost() -> f64 {
+    1.0
+}
+
+/// Rate limit tokens a request to `url` is worth: one, plus half a token for
+/// each `;out=` subresource and a tenth for each key past the first in a
+/// `*_keys=` selector, since those multiply the work Yahoo does per call
+pub fn weighted_cost(url: &str) -> f64 {
+    let path = url.split('?').next().unwrap_or(url);
+    let mut cost = 1.0;
+    for param in path.split(['/', ';']) {
+        let Some((name, value)) = param.split_once('=') else {
+            continue;
+        };
+        let items = value.split(',').filter(|v| !v.is_empty()).count();
+        if name == "out" {
+            cost += 0.5 * items as f64;
+        } else if name.ends_with("_keys") {
+            cost += 0.1 * items.saturating_sub(1) as f64;
+        }
+    }
+    cost
+}
+
+/// Status, headers, and body of an HTTP response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Future of the next body chunk, `None` once the body is complete
+pub type NextChunk<'a> = BoxFuture<'a, Result<Option<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>>>;
+
+/// Source of a response body's chunks, in order
+pub trait BodyChunks: Send {
+    fn next_chunk(&mut self) -> NextChunk<'_>;
+}
+
+/// Body already in memory, delivered as a single chunk
+struct Buffered(Option<Vec<u8>>);
+
+impl BodyChunks for Buffered {
+    fn next_chunk(&mut self) -> NextChunk<'_> {
+        let chunk = self.0.take();
+        Box::pin(async move { Ok(chunk) })
+    }
+}
+
+/// A response whose body is read in chunks as it arrives instead of being buffered whole
+pub struct StreamingResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    body: Box<dyn BodyChunks>,
+}
+
+impl StreamingResponse {
+    pub fn new(status: u16, headers: Vec<(String, String)>, body: impl BodyChunks + 'static) -> Self {
+        Self {
+            status,
+            headers,
+            body: Box::new(body),
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Next chunk of the body, or `None` once it has all been read
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+        self.body.next_chunk().await
+    }
+
+    /// Copy the rest of the body to `writer`, returning the bytes written
+    pub async fn write_to(&mut self, writer: &mut impl std::io::Write) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut written = 0;
+        while let Some(chunk) = self.chunk().await? {
+            writer.write_all(&chunk)?;
+            written += chunk.len() as u64;
+        }
+        Ok(written)
+    }
+
+    /// Read the rest of the body into a string
+    pub async fn text(mut self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let mut body = Vec::new();
+        self.write_to(&mut body).await?;
+        Ok(String::from_utf8(body)?)
+    }
+
+    /// Wrap the body so each chunk must arrive within `timeout`
+    pub(crate) fn with_chunk_timeout(self, timeout: Duration) -> Self {
+        Self {
+            body: Box::new(TimedChunks { inner: self.body, timeout }),
+            ..self
+        }
+    }
+}
+
+impl fmt::Debug for StreamingResponse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamingResponse")
+            .field("status", &self.status)
+            .field("headers", &self.headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<HttpResponse> for StreamingResponse {
+    fn from(response: HttpResponse) -> Self {
+        Self::new(response.status, response.headers, Buffered(Some(response.body.into_bytes())))
+    }
+}
+
+struct TimedChunks {
+    inner: Box<dyn BodyChunks>,
+    timeout: Duration,
+}
+
+impl BodyChunks for TimedChunks {
+    fn next_chunk(&mut self) -> NextChunk<'_> {
+        Box::pin(async move {
+            tokio::time::timeout(self.timeout, self.inner.next_chunk())
+                .await
+                .map_err(|_| TimeoutError::Request(self.timeout))?
+        })
+    }
+}
+
+/// Sends requests over the network for the client. Implement it to use a
+/// custom TLS or corporate HTTP stack, or to stub the API in tests.
+pub trait HttpTransport: Send + Sync {
+    /// Send `request` as-is; it is already signed. Non-success statuses are
+    /// returned as responses, not errors.
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>>;
+
+    /// Like `send`, but return once the headers arrive and read the body in
+    /// chunks. By default the body is buffered by `send` and returned as one chunk.
+    fn send_streaming<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<StreamingResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move { Ok(self.send(request).await?.into()) })
+    }
+}
+
+impl fmt::Debug for dyn HttpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HttpTransport")
+    }
+}
+
+/// HTTP/HTTPS proxy for every request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProxyConfig {
+    url: String,
+    /// Username and password; the password is redacted from debug output
+    basic_auth: Option<(String, Secret)>,
+    no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Proxy at `url`, e.g. `http://proxy.corp:8080`
+    pub fn new(url: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        reqwest::Proxy::all(url)?;
+        Ok(Self {
+            url: url.to_string(),
+            basic_auth: None,
+            no_proxy: Vec::new(),
+        })
+    }
+
+    pub fn basic_auth(mut self, username: &str, password: &str) -> Self {
+        self.basic_auth = Some((username.to_string(), Secret::new(password)));
+        self
+    }
+
+    /// Hosts, domains, or IP ranges to reach directly, in `NO_PROXY` syntax
+    pub fn no_proxy(mut self, hosts: &[&str]) -> Self {
+        self.no_proxy.extend(hosts.iter().map(|h| h.to_string()));
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn to_reqwest(&self) -> reqwest::Proxy {
+        let mut proxy = reqwest::Proxy::all(&self.url).expect("proxy URL is validated in ProxyConfig::new");
+        if let Some((username, password)) = &self.basic_auth {
+            proxy = proxy.basic_auth(username, password.expose());
+        }
+        proxy.no_proxy(reqwest::NoProxy::from_string(&self.no_proxy.join(",")))
+    }
+}
+
+/// Connection reuse for the default transport. Raise `max_idle_per_host` when
+/// fetching many leagues concurrently over HTTP/1.1 so requests don't each open a socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolConfig {
+    /// Idle connections kept open per host; 0 disables reuse
+    pub max_idle_per_host: usize,
+    /// How long an idle connection is kept; `None` keeps it indefinitely
+    pub idle_timeout: Option<Duration>,
+    /// Interval for TCP keep-alive probes on open connections; `None` disables them
+    pub tcp_keepalive: Option<Duration>,
+    /// Offer HTTP/2 during the TLS handshake so concurrent requests share one
+    /// connection; servers that don't support it fall back to HTTP/1.1
+    pub http2: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_per_host: 16,
+            idle_timeout: Some(Duration::from_secs(90)),
+            tcp_keepalive: Some(Duration::from_secs(60)),
+            http2: true,
+        }
+    }
+}
+
+/// Default transport, built on reqwest. TLS comes from rustls (the default
+/// `rustls-tls` feature) or the platform library (`native-tls`, with default
+/// features off). With the `compression` feature, requests send
+/// `Accept-Encoding: gzip, deflate` and responses are decompressed transparently.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    /// Reported in `TimeoutError::Connect` when connecting times out
+    connect_timeout: Option<Duration>,
+}
+
+impl ReqwestTransport {
+    /// Send through a preconfigured reqwest client
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            connect_timeout: None,
+        }
+    }
+
+    /// Client with a connect timeout, connection pool settings, and optionally a proxy for every request.
+    /// Fails if reqwest can't build the client, e.g. when the TLS backend can't be initialized.
+    pub fn configured(proxy: Option<&ProxyConfig>, connect_timeout: Duration, pool: &PoolConfig) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .pool_max_idle_per_host(pool.max_idle_per_host)
+            .pool_idle_timeout(pool.idle_timeout)
+            .tcp_keepalive(pool.tcp_keepalive);
+        if !pool.http2 {
+            builder = builder.http1_only();
+        }
+        // rustls wins when both TLS features are enabled, since it's the default
+        #[cfg(feature = "rustls-tls")]
+        {
+            builder = builder.use_rustls_tls();
+        }
+        #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
+        {
+            builder = builder.use_native_tls();
+        }
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy.to_reqwest());
+        }
+        Ok(Self {
+            client: builder.build()?,
+            connect_timeout: Some(connect_timeout),
+        })
+    }
+}
+
+impl ReqwestTransport {
+    /// Send `request` and wait for the response headers
+    async fn start(&self, request: &Request) -> Result<reqwest::Response, Box<dyn std::error::Error + Send + Sync>> {
+        let method = reqwest::Method::from_bytes(request.method().as_str().as_bytes())?;
+        let mut builder = self.client.request(method, request.url());
+        for (name, value) in request.headers() {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body() {
+            builder = builder.body(body.to_string());
+        }
+
+        builder.send().await.map_err(|e| match self.connect_timeout {
+            Some(timeout) if e.is_connect() && e.is_timeout() => Box::new(TimeoutError::Connect(timeout)) as Box<dyn std::error::Error + Send + Sync>,
+            _ => e.into(),
+        })
+    }
+}
+
+fn response_headers(response: &reqwest::Response) -> Vec<(String, String)> {
+    response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+struct ReqwestChunks(reqwest::Response);
+
+impl BodyChunks for ReqwestChunks {
+    fn next_chunk(&mut self) -> NextChunk<'_> {
+        Box::pin(async move { Ok(self.0.chunk().await?.map(|chunk| chunk.to_vec())) })
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            let response = self.start(request).await?;
+            let status = response.status().as_u16();
+            let headers = response_headers(&response);
+            let body = response.text().await?;
+            Ok(HttpResponse { status, headers, body })
+        })
+    }
+
+    fn send_streaming<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<StreamingResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            let response = self.start(request).await?;
+            let status = response.status().as_u16();
+            let headers = response_headers(&response);
+            Ok(StreamingResponse::new(status, headers, ReqwestChunks(response)))
+        })
+    }
+}
@@ -0,0 +1,229 @@
+//! Durable write queue
+//! Roster changes and transactions are persisted before sending and retried until confirmed
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::time;
+use crate::transport::Request;
+
+/// Attempts before a write is given up on
+const DEFAULT_MAX_ATTEMPTS: u32 = 8;
+/// Delay after the first failure; doubles with each further failure
+const BASE_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Where a queued write stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum WriteStatus {
+    Pending,
+    /// The API confirmed the write
+    Completed,
+    /// Out of attempts; needs attention
+    Failed,
+}
+
+/// A write waiting for, or done with, delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct QueuedWrite {
+    pub id: u64,
+    pub request: Request,
+    pub status: WriteStatus,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    /// Unix seconds
+    pub enqueued_at: i64,
+    /// Unix seconds before which the write won't be retried
+    pub next_attempt_at: i64,
+}
+
+/// Outcome of one `process` pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessReport {
+    pub completed: usize,
+    pub retrying: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    next_id: u64,
+    writes: Vec<QueuedWrite>,
+}
+
+/// Write queue, optionally persisted to a JSON file so pending writes survive restarts
+#[derive(Debug)]
+pub struct WriteQueue {
+    path: Option<PathBuf>,
+    max_attempts: u32,
+    state: Mutex<QueueState>,
+}
+
+impl Default for WriteQueue {
+    fn default() -> Self {
+        Self {
+            path: None,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            state: Mutex::new(QueueState::default()),
+        }
+    }
+}
+
+impl WriteQueue {
+    /// Queue that lives only as long as the process
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Load the queue from `path` if it exists; every change is written back immediately
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = path.as_ref().to_path_buf();
+        let state = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => QueueState::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self {
+            path: Some(path),
+            state: Mutex::new(state),
+            ..Self::default()
+        })
+    }
+
+    pub fn max_attempts(mut self, attempts: u32) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Persist a write and return its id; it is sent on the next `process`
+    pub fn enqueue(&self, request: Request) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let now = time::now().timestamp();
+        state.writes.push(QueuedWrite {
+            id,
+            request,
+            status: WriteStatus::Pending,
+            attempts: 0,
+            last_error: None,
+            enqueued_at: now,
+            next_attempt_at: now,
+        });
+        self.persist(&state)?;
+        Ok(id)
+    }
+
+    pub fn get(&self, id: u64) -> Option<QueuedWrite> {
+        self.state.lock().unwrap().writes.iter().find(|w| w.id == id).cloned()
+    }
+
+    /// Writes with the given status, oldest first
+    pub fn with_status(&self, status: WriteStatus) -> Vec<QueuedWrite> {
+        self.state
+            .lock()
+            .unwrap()
+            .writes
+            .iter()
+            .filter(|w| w.status == status)
+            .cloned()
+            .collect()
+    }
+
+    /// Send every pending write that is due, in enqueue order. A write is marked
+    /// completed only when `send` returns `Ok`; failures back off and retry until
+    /// `max_attempts`. Progress is persisted after each write.
+    pub async fn process<F, Fut>(&self, mut send: F) -> Result<ProcessReport, Box<dyn std::error::Error + Send + Sync>>
+    where
+        F: FnMut(Request) -> Fut,
+        Fut: Future<Output = Result<(), Box<dyn std::error::Error + Send + Sync>>>,
+    {
+        let mut report = ProcessReport::default();
+        let now = time::now().timestamp();
+        let due: Vec<u64> = self
+            .with_status(WriteStatus::Pending)
+            .into_iter()
+            .filter(|w| w.next_attempt_at <= now)
+            .map(|w| w.id)
+            .collect();
+
+        for id in due {
+            let Some(request) = self.get(id).map(|w| w.request) else {
+                continue;
+            };
+            let result = send(request).await;
+
+            let mut state = self.state.lock().unwrap();
+            let Some(write) = state.writes.iter_mut().find(|w| w.id == id) else {
+                continue;
+            };
+            write.attempts += 1;
+            match result {
+                Ok(()) => {
+                    write.status = WriteStatus::Completed;
+                    write.last_error = None;
+                    report.completed += 1;
+                }
+                Err(e) => {
+                    write.last_error = Some(e.to_string());
+                    if write.attempts >= self.max_attempts {
+                        write.status = WriteStatus::Failed;
+                        report.failed += 1;
+                    } else {
+                        write.next_attempt_at = time::now().timestamp() + backoff(write.attempts).as_secs() as i64;
+                        report.retrying += 1;
+                    }
+                }
+            }
+            self.persist(&state)?;
+        }
+        Ok(report)
+    }
+
+    /// Requeue a failed write with a fresh attempt budget
+    pub fn retry(&self, id: u64) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        let Some(write) = state.writes.iter_mut().find(|w| w.id == id && w.status == WriteStatus::Failed) else {
+            return Ok(false);
+        };
+        write.status = WriteStatus::Pending;
+        write.attempts = 0;
+        write.next_attempt_at = time::now().timestamp();
+        self.persist(&state)?;
+        Ok(true)
+    }
+
+    /// Forget completed writes; returns how many were removed
+    pub fn prune_completed(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let mut state = self.state.lock().unwrap();
+        let before = state.writes.len();
+        state.writes.retain(|w| w.status != WriteStatus::Completed);
+        let removed = before - state.writes.len();
+        self.persist(&state)?;
+        Ok(removed)
+    }
+
+    /// Write the queue to its file, atomically replacing the previous contents
+    fn persist(&self, state: &QueueState) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(state)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+fn backoff(attempts: u32) -> Duration {
+    BASE_BACKOFF
+        .checked_mul(1 << attempts.saturating_sub(1).min(16))
+        .map_or(MAX_BACKOFF, |d| d.min(MAX_BACKOFF))
+}
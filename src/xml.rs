@@ -0,0 +1,371 @@
+//! Request body XML serialization
+//! Typed builders for the `<fantasy_content>` bodies used by write operations
+
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use crate::{time, AcquisitionUsage};
+
+/// Text holding a character XML 1.0 can't represent, even as a character reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidXmlChar {
+    pub character: char,
+    /// Byte offset of the character in the text
+    pub offset: usize,
+}
+
+impl fmt::Display for InvalidXmlChar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "U+{:04X} at byte {} can't be sent in an XML body", self.character as u32, self.offset)
+    }
+}
+
+impl std::error::Error for InvalidXmlChar {}
+
+/// Escape text for use in XML element content or attribute values. Fails on
+/// control characters other than tab, newline, and carriage return, and on
+/// the noncharacters U+FFFE and U+FFFF, which XML 1.0 forbids outright.
+pub fn escape(text: &str) -> Result<String, InvalidXmlChar> {
+    let mut escaped = String::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => return Err(InvalidXmlChar { character: c, offset }),
+            c => escaped.push(c),
+        }
+    }
+    Ok(escaped)
+}
+
+/// Minimal indenting XML writer; all text goes through `escape`
+struct XmlWriter {
+    out: String,
+    depth: usize,
+}
+
+impl XmlWriter {
+    fn new() -> Self {
+        Self {
+            out: String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"),
+            depth: 0,
+        }
+    }
+
+    fn open(&mut self, name: &str) {
+        self.indent();
+        self.out.push_str(&format!("<{}>\n", name));
+        self.depth += 1;
+    }
+
+    fn close(&mut self, name: &str) {
+        self.depth -= 1;
+        self.indent();
+        self.out.push_str(&format!("</{}>\n", name));
+    }
+
+    fn leaf(&mut self, name: &str, text: &str) -> Result<(), InvalidXmlChar> {
+        let text = escape(text)?;
+        self.indent();
+        self.out.push_str(&format!("<{}>{}</{}>\n", name, text, name));
+        Ok(())
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+    }
+
+    fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// A request body that can be serialized to Yahoo's XML format
+pub trait XmlBody {
+    fn to_xml(&self) -> String;
+}
+
+/// Kind of a single player move within a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveType {
+    Add,
+    Drop,
+    /// Player changing teams as part of a trade
+    Trade,
+}
+
+impl MoveType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MoveType::Add => "add",
+            MoveType::Drop => "drop",
+            MoveType::Trade => "pending_trade",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PlayerMove {
+    player_key: String,
+    move_type: MoveType,
+    source_team_key: Option<String>,
+    destination_team_key: Option<String>,
+}
+
+/// Body for `league/{league_key}/transactions` writes
+#[derive(Debug, Clone)]
+pub struct TransactionBody {
+    xml: String,
+    warnings: Vec<String>,
+}
+
+impl TransactionBody {
+    /// Problems a dry-run build let through, e.g. an acquisition limit the move would break
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+impl XmlBody for TransactionBody {
+    fn to_xml(&self) -> String {
+        self.xml.clone()
+    }
+}
+
+/// Builder for transaction bodies
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    faab_bid: Option<u32>,
+    trader_team_key: Option<String>,
+    tradee_team_key: Option<String>,
+    trade_note: Option<String>,
+    moves: Vec<PlayerMove>,
+    acquisitions: Option<AcquisitionUsage>,
+    dry_run: bool,
+}
+
+impl TransactionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a player to `team_key`
+    pub fn add(mut self, player_key: &str, team_key: &str) -> Self {
+        self.moves.push(PlayerMove {
+            player_key: player_key.to_string(),
+            move_type: MoveType::Add,
+            source_team_key: None,
+            destination_team_key: Some(team_key.to_string()),
+        });
+        self
+    }
+
+    /// Drop a player from `team_key`
+    pub fn drop_player(mut self, player_key: &str, team_key: &str) -> Self {
+        self.moves.push(PlayerMove {
+            player_key: player_key.to_string(),
+            move_type: MoveType::Drop,
+            source_team_key: Some(team_key.to_string()),
+            destination_team_key: None,
+        });
+        self
+    }
+
+    /// Move a player between teams as part of a trade proposal
+    pub fn trade(mut self, player_key: &str, source_team_key: &str, destination_team_key: &str) -> Self {
+        self.moves.push(PlayerMove {
+            player_key: player_key.to_string(),
+            move_type: MoveType::Trade,
+            source_team_key: Some(source_team_key.to_string()),
+            destination_team_key: Some(destination_team_key.to_string()),
+        });
+        self
+    }
+
+    /// FAAB bid for waiver claims
+    pub fn faab_bid(mut self, bid: u32) -> Self {
+        self.faab_bid = Some(bid);
+        self
+    }
+
+    /// Teams involved in a trade proposal
+    pub fn trade_teams(mut self, trader_team_key: &str, tradee_team_key: &str) -> Self {
+        self.trader_team_key = Some(trader_team_key.to_string());
+        self.tradee_team_key = Some(tradee_team_key.to_string());
+        self
+    }
+
+    pub fn trade_note(mut self, note: &str) -> Self {
+        self.trade_note = Some(note.to_string());
+        self
+    }
+
+    /// Refuse adds that would break the team's acquisition limits, e.g. from
+    /// `get_acquisition_usage`
+    pub fn acquisition_limits(mut self, usage: AcquisitionUsage) -> Self {
+        self.acquisitions = Some(usage);
+        self
+    }
+
+    /// Build even if a limit would be broken, recording it in `TransactionBody::warnings` instead
+    pub fn dry_run(mut self, enabled: bool) -> Self {
+        self.dry_run = enabled;
+        self
+    }
+
+    /// Validate the moves and produce the body. Fails with `InvalidXmlChar`
+    /// if a key or the trade note has a character XML can't carry.
+    pub fn build(self) -> Result<TransactionBody, Box<dyn std::error::Error + Send + Sync>> {
+        let adds = self.moves.iter().filter(|m| m.move_type == MoveType::Add).count();
+        let drops = self.moves.iter().filter(|m| m.move_type == MoveType::Drop).count();
+        let trades = self.moves.iter().filter(|m| m.move_type == MoveType::Trade).count();
+
+        let transaction_type = match (adds, drops, trades) {
+            (1, 0, 0) => "add",
+            (0, 1, 0) => "drop",
+            (1, 1, 0) => "add/drop",
+            (0, 0, n) if n > 0 => "pending_trade",
+            _ => return Err("transaction must be a single add, drop, add/drop, or a trade".into()),
+        };
+
+        if transaction_type == "pending_trade" && self.trader_team_key.is_none() {
+            return Err("trade transactions require trader and tradee team keys".into());
+        }
+
+        let mut warnings = Vec::new();
+        if let Some(Err(e)) = self.acquisitions.map(|usage| usage.check(adds as u32)) {
+            if !self.dry_run {
+                return Err(e.into());
+            }
+            warnings.push(e.to_string());
+        }
+
+        Ok(TransactionBody {
+            xml: self.render(transaction_type)?,
+            warnings,
+        })
+    }
+
+    fn render(&self, transaction_type: &str) -> Result<String, InvalidXmlChar> {
+        let mut w = XmlWriter::new();
+        w.open("fantasy_content");
+        w.open("transaction");
+        w.leaf("type", transaction_type)?;
+        if let Some(bid) = self.faab_bid {
+            w.leaf("faab_bid", &bid.to_string())?;
+        }
+        if let Some(trader) = &self.trader_team_key {
+            w.leaf("trader_team_key", trader)?;
+        }
+        if let Some(tradee) = &self.tradee_team_key {
+            w.leaf("tradee_team_key", tradee)?;
+        }
+        if let Some(note) = &self.trade_note {
+            w.leaf("trade_note", note)?;
+        }
+        w.open("players");
+        for m in &self.moves {
+            w.open("player");
+            w.leaf("player_key", &m.player_key)?;
+            w.open("transaction_data");
+            w.leaf("type", m.move_type.as_str())?;
+            if let Some(source) = &m.source_team_key {
+                w.leaf("source_team_key", source)?;
+            }
+            if let Some(destination) = &m.destination_team_key {
+                w.leaf("destination_team_key", destination)?;
+            }
+            w.close("transaction_data");
+            w.close("player");
+        }
+        w.close("players");
+        w.close("transaction");
+        w.close("fantasy_content");
+        Ok(w.finish())
+    }
+}
+
+/// Coverage a roster change applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RosterCoverage {
+    /// Weekly leagues (NFL)
+    Week(u32),
+    /// Daily leagues, by Pacific coverage date
+    Date(NaiveDate),
+}
+
+/// Body for `team/{team_key}/roster` writes
+#[derive(Debug, Clone)]
+pub struct RosterBody {
+    xml: String,
+}
+
+impl XmlBody for RosterBody {
+    fn to_xml(&self) -> String {
+        self.xml.clone()
+    }
+}
+
+/// Builder for roster position changes
+#[derive(Debug, Clone)]
+pub struct RosterBuilder {
+    coverage: RosterCoverage,
+    players: Vec<(String, String)>,
+}
+
+impl RosterBuilder {
+    pub fn new(coverage: RosterCoverage) -> Self {
+        Self {
+            coverage,
+            players: Vec::new(),
+        }
+    }
+
+    /// Move a player into `position` (e.g. "WR", "BN")
+    pub fn player(mut self, player_key: &str, position: &str) -> Self {
+        self.players.push((player_key.to_string(), position.to_string()));
+        self
+    }
+
+    /// Fails with `InvalidXmlChar` if a key or position has a character XML can't carry
+    pub fn build(self) -> Result<RosterBody, Box<dyn std::error::Error + Send + Sync>> {
+        if self.players.is_empty() {
+            return Err("roster change must include at least one player".into());
+        }
+        Ok(RosterBody { xml: self.render()? })
+    }
+
+    fn render(&self) -> Result<String, InvalidXmlChar> {
+        let mut w = XmlWriter::new();
+        w.open("fantasy_content");
+        w.open("roster");
+        match &self.coverage {
+            RosterCoverage::Week(week) => {
+                w.leaf("coverage_type", "week")?;
+                w.leaf("week", &week.to_string())?;
+            }
+            RosterCoverage::Date(date) => {
+                w.leaf("coverage_type", "date")?;
+                w.leaf("date", &time::format_date(*date))?;
+            }
+        }
+        w.open("players");
+        for (player_key, position) in &self.players {
+            w.open("player");
+            w.leaf("player_key", player_key)?;
+            w.leaf("position", position)?;
+            w.close("player");
+        }
+        w.close("players");
+        w.close("roster");
+        w.close("fantasy_content");
+        Ok(w.finish())
+    }
+}
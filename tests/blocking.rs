@@ -0,0 +1,30 @@
+//! Blocking client driven through the shared background runtime
+
+#![cfg(feature = "blocking")]
+
+mod common;
+
+use common::{fixture, FixtureTransport};
+use yahoo_fantasy_sdk::blocking::YahooFantasyClient;
+
+#[test]
+fn blocking_clients_share_a_runtime() {
+    let transport = FixtureTransport::new().route("game/449/game_weeks", &fixture("game/game_weeks.json"));
+    let first = YahooFantasyClient::try_from(transport.client()).unwrap();
+    let second = YahooFantasyClient::try_from(transport.client()).unwrap();
+
+    assert_eq!(first.get_game_weeks("449").unwrap().len(), 3);
+    assert_eq!(second.get_game_weeks("449").unwrap().len(), 3);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn streaming_responses_read_without_a_runtime() {
+    let transport = FixtureTransport::new().route("game/449/game_weeks", &fixture("game/game_weeks.json"));
+    let client = YahooFantasyClient::try_from(transport.client()).unwrap();
+    let request = client.request(yahoo_fantasy_sdk::Method::Get, "game/449/game_weeks");
+
+    let response = client.execute_streaming(&request).unwrap();
+    assert!(response.is_success());
+    assert_eq!(response.text().unwrap(), fixture("game/game_weeks.json"));
+}
@@ -0,0 +1,49 @@
+//! Signing a client in with a token obtained elsewhere
+
+mod common;
+
+use common::FixtureTransport;
+use yahoo_fantasy_sdk::AccessToken;
+
+const REFRESHED: &str = "oauth_token=fresh&oauth_token_secret=fresh-secret&oauth_session_handle=rotated&oauth_expires_in=3600";
+
+fn token(name: &str) -> AccessToken {
+    AccessToken {
+        token: name.to_string().into(),
+        secret: format!("{}-secret", name).into(),
+        session_handle: Some("handle".to_string().into()),
+        expires_in: None,
+    }
+}
+
+#[tokio::test]
+async fn refreshed_token_is_kept_when_the_check_call_fails() {
+    // No fixture for users;use_login=1, so the check call fails
+    let transport = FixtureTransport::new().route("get_token", REFRESHED);
+    let client = transport.client();
+
+    assert!(client.bootstrap(token("exported")).await.is_err());
+    let kept = client.access_token().unwrap();
+    assert_eq!(kept.token.expose(), "fresh");
+    assert_eq!(kept.session_handle.unwrap().expose(), "rotated");
+}
+
+#[tokio::test]
+async fn previous_token_is_restored_when_the_refresh_fails() {
+    let transport = FixtureTransport::new();
+    let mut client = transport.client();
+    client.set_access_token(token("previous"));
+
+    assert!(client.bootstrap(token("exported")).await.is_err());
+    assert_eq!(client.access_token().unwrap().token.expose(), "previous");
+}
+
+#[tokio::test]
+async fn bootstrap_returns_the_refreshed_token() {
+    let transport = FixtureTransport::new().route("get_token", REFRESHED).route("users;use_login=1", "{}");
+    let client = transport.client();
+
+    let stored = client.bootstrap(token("exported")).await.unwrap();
+    assert_eq!(stored.token.token.expose(), "fresh");
+    assert!(stored.expires_at.is_some());
+}
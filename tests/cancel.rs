@@ -0,0 +1,35 @@
+//! Waiting on a cancellation token
+
+use std::time::Duration;
+
+use yahoo_fantasy_sdk::CancellationToken;
+
+#[tokio::test]
+async fn cancel_wakes_every_waiter() {
+    let cancel = CancellationToken::new();
+    let waiters: Vec<_> = (0..4)
+        .map(|_| {
+            let cancel = cancel.clone();
+            tokio::spawn(async move { cancel.cancelled().await })
+        })
+        .collect();
+    tokio::task::yield_now().await;
+
+    cancel.cancel();
+    for waiter in waiters {
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+    }
+}
+
+#[tokio::test]
+async fn cancelled_resolves_at_once_for_a_cancelled_token() {
+    let cancel = CancellationToken::new();
+    cancel.cancel();
+    tokio::time::timeout(Duration::ZERO, cancel.cancelled()).await.unwrap();
+}
+
+#[tokio::test]
+async fn cancelled_waits_while_the_token_is_live() {
+    let cancel = CancellationToken::new();
+    assert!(tokio::time::timeout(Duration::from_millis(50), cancel.cancelled()).await.is_err());
+}
@@ -0,0 +1,61 @@
+//! Shared test helpers
+//! A canned-response transport for driving the client without a network
+
+#![allow(dead_code)]
+
+use std::sync::{Arc, Mutex};
+
+use yahoo_fantasy_sdk::{BoxFuture, ClientBuilder, HttpResponse, HttpTransport, Request, YahooFantasyClient};
+
+/// Answers requests from fixtures keyed by resource path, and records every URL it is sent
+#[derive(Clone, Default)]
+pub struct FixtureTransport {
+    routes: Vec<(String, String)>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl FixtureTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer requests for `path`, relative to the API root and without the query string, with `body`
+    pub fn route(mut self, path: &str, body: &str) -> Self {
+        self.routes.push((path.to_string(), body.to_string()));
+        self
+    }
+
+    /// URLs requested so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Builder for a client sending every request to this transport
+    pub fn builder(&self) -> ClientBuilder {
+        YahooFantasyClient::builder("key".to_string(), "secret".to_string()).transport(self.clone())
+    }
+
+    pub fn client(&self) -> YahooFantasyClient {
+        self.builder().build().unwrap()
+    }
+}
+
+impl HttpTransport for FixtureTransport {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            self.requests.lock().unwrap().push(request.url().to_string());
+            let path = request.url().split('?').next().unwrap_or_default();
+            let body = self.routes.iter().find(|(route, _)| path.ends_with(&format!("/{}", route))).map(|(_, body)| body.clone());
+            Ok(match body {
+                Some(body) => HttpResponse { status: 200, headers: Vec::new(), body },
+                None => HttpResponse { status: 404, headers: Vec::new(), body: format!("no fixture for {}", path) },
+            })
+        })
+    }
+}
+
+/// Contents of `tests/fixtures/<name>`
+pub fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("could not read {}: {}", path, e))
+}
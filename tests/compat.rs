@@ -0,0 +1,96 @@
+//! Archived responses from several seasons, parsed through the client and `SeasonCompat`
+
+mod common;
+
+use common::{fixture, FixtureTransport};
+use serde_json::json;
+use yahoo_fantasy_sdk::SeasonCompat;
+
+#[tokio::test]
+async fn games_from_every_season_parse_into_the_current_model() {
+    for (name, key) in [("games_2008.json", "199"), ("games_2012.json", "273")] {
+        let transport = FixtureTransport::new().route("games;is_available=1", &fixture(&format!("compat/{}", name)));
+        let games = transport.client().get_games().await.unwrap();
+        assert_eq!(games.len(), 1, "{}", name);
+        assert_eq!(games[0].game_key, key);
+        assert_eq!(games[0].code, "nfl");
+    }
+
+    let legacy = FixtureTransport::new().route("games;is_available=1", &fixture("compat/games_2008.json")).client().get_games().await.unwrap();
+    assert_eq!(legacy[0].season, 2008);
+    assert_eq!(legacy[0].is_game_over, None);
+
+    let archived = FixtureTransport::new().route("games;is_available=1", &fixture("compat/games_2012.json")).client().get_games().await.unwrap();
+    assert_eq!(archived[0].season, 2012);
+    assert_eq!(archived[0].is_game_over, Some(true));
+
+    let current = FixtureTransport::new().route("games;is_available=1", &fixture("compat/games_2024.json")).client().get_games().await.unwrap();
+    let codes: Vec<_> = current.iter().map(|g| (g.code.as_str(), g.season, g.is_game_over)).collect();
+    assert_eq!(codes, [("nfl", 2024, Some(false)), ("nba", 2024, Some(false))]);
+}
+
+#[tokio::test]
+async fn leagues_from_2010_use_their_legacy_field_names() {
+    let transport = FixtureTransport::new().route("users;use_login=1/games;game_keys=242/leagues", &fixture("compat/leagues_2010.json"));
+    let leagues = transport.client().get_leagues("242").await.unwrap();
+    assert_eq!(leagues.len(), 1);
+    let league = &leagues[0];
+    assert_eq!(league.league_key, "242.l.81934");
+    assert_eq!(league.num_teams, 10);
+    assert_eq!(league.current_week, 16);
+    // The league has no season of its own; it comes from the game
+    assert_eq!(league.season, Some(2010));
+    assert_eq!(league.uses_fractional_points, Some(true));
+}
+
+#[tokio::test]
+async fn archived_leagues_are_pinned_to_their_last_week() {
+    let transport = FixtureTransport::new().route("users;use_login=1/games;game_keys=348/leagues", &fixture("compat/leagues_2015.json"));
+    let leagues = transport.client().get_leagues("348").await.unwrap();
+    assert_eq!(leagues[0].num_teams, 12);
+    assert_eq!(leagues[0].current_week, 16);
+    assert_eq!(leagues[0].season, Some(2015));
+    // Dropped after 2012, so a stray flag is ignored
+    assert_eq!(leagues[0].uses_fractional_points, None);
+}
+
+#[tokio::test]
+async fn current_leagues_parse_without_fixups() {
+    let transport = FixtureTransport::new().route("users;use_login=1/games;game_keys=449/leagues", &fixture("compat/leagues_2024.json"));
+    let leagues = transport.client().get_leagues("449").await.unwrap();
+    let summary: Vec<_> = leagues.iter().map(|l| (l.league_key.as_str(), l.num_teams, l.current_week, l.scoring_type.as_deref())).collect();
+    assert_eq!(summary, [("449.l.123456", 12, 9, Some("head")), ("449.l.654321", 10, 9, Some("point"))]);
+}
+
+#[test]
+fn aliases_only_apply_up_to_their_last_season() {
+    let payload = json!({"league_key": "348.l.1", "name": "L", "num_teams": "12", "num_team": "8", "week": "3", "current_week": "5"});
+    let league = SeasonCompat::for_season(2010).parse_league(payload).unwrap();
+    // The current names win when a payload has both
+    assert_eq!((league.num_teams, league.current_week), (12, 5));
+
+    let legacy_only = json!({"league_key": "348.l.1", "name": "L", "num_team": "8", "week": "3"});
+    assert!(SeasonCompat::for_season(2012).parse_league(legacy_only.clone()).is_err());
+    let league = SeasonCompat::for_season(2011).parse_league(legacy_only).unwrap();
+    assert_eq!((league.num_teams, league.current_week), (8, 3));
+}
+
+#[test]
+fn unknown_seasons_get_no_season_specific_fixups() {
+    let compat = SeasonCompat::unknown();
+    assert_eq!(compat.season(), None);
+
+    // Legacy names aren't guessed at without a season
+    let legacy = json!({"league_key": "l", "name": "L", "num_team": "8", "week": "3"});
+    assert!(compat.parse_league(legacy).is_err());
+
+    let league = compat
+        .parse_league(json!({"league_key": "l", "name": "L", "num_teams": "8", "current_week": "3", "uses_fractional_points": "1"}))
+        .unwrap();
+    assert_eq!(league.season, None);
+    assert_eq!(league.uses_fractional_points, Some(true));
+
+    let game = compat.parse_game(json!({"game_key": "449", "name": "Football", "code": "nfl", "season": "2024"})).unwrap();
+    assert_eq!(game.season, 2024);
+    assert!(compat.parse_game(json!({"game_key": "449", "name": "Football", "code": "nfl"})).is_err());
+}
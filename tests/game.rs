@@ -0,0 +1,43 @@
+//! Game resources parsed from recorded Yahoo responses
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{fixture, FixtureTransport};
+use yahoo_fantasy_sdk::WeekRange;
+
+fn date(raw: &str) -> NaiveDate {
+    raw.parse().unwrap()
+}
+
+#[tokio::test]
+async fn game_weeks_parse_into_date_ranges() {
+    let transport = FixtureTransport::new().route("game/449/game_weeks", &fixture("game/game_weeks.json"));
+    let weeks = transport.client().get_game_weeks("449").await.unwrap();
+
+    let week = |week, start, end| WeekRange { week, start: date(start), end: date(end) };
+    assert_eq!(weeks, [week(1, "2024-09-05", "2024-09-09"), week(2, "2024-09-10", "2024-09-16"), week(3, "2024-09-17", "2024-09-23")]);
+    assert!(weeks[0].contains(date("2024-09-08")));
+}
+
+#[tokio::test]
+async fn game_week_with_a_bad_date_is_an_error() {
+    let body = fixture("game/game_weeks.json").replace("2024-09-16", "Sept 16");
+    let transport = FixtureTransport::new().route("game/449/game_weeks", &body);
+    assert!(transport.client().get_game_weeks("449").await.is_err());
+}
+
+#[tokio::test]
+async fn games_requested_by_code_match_their_game_keys() {
+    let games = fixture("compat/games_2024.json");
+    let transport = FixtureTransport::new().route("games;game_keys=nfl,454", &games).route("games;game_keys=nfl,mlb", &games);
+    let client = transport.client();
+
+    let batch = client.get_games_by_keys(&["nfl".to_string(), "454".to_string()]).await;
+    assert!(batch.is_complete(), "{:?}", batch.failed);
+    assert_eq!(batch.succeeded.len(), 2);
+
+    // A code Yahoo didn't answer for still fails
+    let batch = client.get_games_by_keys(&["nfl".to_string(), "mlb".to_string()]).await;
+    assert_eq!(batch.failed_keys().collect::<Vec<_>>(), ["mlb"]);
+}
@@ -0,0 +1,265 @@
+//! League resources parsed from recorded Yahoo responses
+
+mod common;
+
+use common::{fixture, FixtureTransport};
+use yahoo_fantasy_sdk::{diff_scoreboards, diff_standings, DraftPick, DraftStatus, LeagueSearchFilters, LeagueVisibility, Points, RosterSlot, ScoreboardEvent, StandingsEvent, StatGroup, TransactionType};
+
+const SCOREBOARD: &str = "league/449.l.12345/scoreboard;week=9/matchups/teams/roster;week=9/players/stats;type=week;week=9";
+
+#[tokio::test]
+async fn scoreboard_parses_every_matchup_for_the_week() {
+    let transport = FixtureTransport::new().route(SCOREBOARD, &fixture("league/scoreboard.json"));
+    let scoreboard = transport.client().get_scoreboard("449.l.12345", 9).await.unwrap();
+
+    assert_eq!(scoreboard.league_key, "449.l.12345");
+    assert_eq!(scoreboard.week, 9);
+    let sides: Vec<Vec<_>> = scoreboard
+        .matchups
+        .iter()
+        .map(|m| m.teams.iter().map(|t| (t.team_key.as_str(), t.name.as_str(), t.points)).collect())
+        .collect();
+    assert_eq!(
+        sides,
+        [
+            vec![("449.l.12345.t.1", "Team Alpha", points("87.50")), ("449.l.12345.t.2", "Team Beta", points("91.20"))],
+            vec![("449.l.12345.t.3", "Team Gamma", points("101")), ("449.l.12345.t.4", "Team Delta", points("99.95"))],
+        ]
+    );
+    assert_eq!(scoreboard.matchups[0].teams[0].projected_points, points("112.30"));
+    // Finished matchups may come without projections
+    assert_eq!(scoreboard.matchups[1].teams[0].projected_points, Points::default());
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn scoreboard_lists_points_for_starters_only() {
+    let transport = FixtureTransport::new().route(SCOREBOARD, &fixture("league/scoreboard.json"));
+    let scoreboard = transport.client().get_scoreboard("449.l.12345", 9).await.unwrap();
+
+    let starters: Vec<Vec<_>> = scoreboard.matchups[0].teams.iter().map(|t| t.players.iter().map(|p| (p.player_key.as_str(), p.points)).collect()).collect();
+    assert_eq!(starters, [vec![("449.p.30977", points("27.72"))], vec![("449.p.31883", points("9.1"))]]);
+    // Teams without a roster in the response have no player points
+    assert!(scoreboard.matchups[1].teams[0].players.is_empty());
+}
+
+#[tokio::test]
+async fn scoring_plays_come_from_consecutive_scoreboards() {
+    let before = FixtureTransport::new().route(SCOREBOARD, &fixture("league/scoreboard.json"));
+    let after = FixtureTransport::new().route(SCOREBOARD, &fixture("league/scoreboard.json").replace("\"27.72\"", "\"33.72\""));
+    let previous = before.client().get_scoreboard("449.l.12345", 9).await.unwrap();
+    let current = after.client().get_scoreboard("449.l.12345", 9).await.unwrap();
+
+    let events = diff_scoreboards(&previous, &current);
+    assert_eq!(
+        events,
+        [
+            ScoreboardEvent::ScoringPlay {
+                week: 9,
+                team_key: "449.l.12345.t.1".to_string(),
+                player_key: "449.p.30977".to_string(),
+                points_delta: points("6"),
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn scoreboard_without_a_scoreboard_is_an_error() {
+    let transport = FixtureTransport::new().route(SCOREBOARD, r#"{"fantasy_content":{"league":[{"league_key":"449.l.12345"}]}}"#);
+    let err = transport.client().get_scoreboard("449.l.12345", 9).await.unwrap_err();
+    assert!(err.to_string().contains("scoreboard"), "{}", err);
+}
+
+#[tokio::test]
+async fn standings_come_back_in_rank_order() {
+    let transport = FixtureTransport::new().route("league/449.l.12345/standings", &fixture("league/standings.json"));
+    let standings = transport.client().get_standings("449.l.12345").await.unwrap();
+
+    assert_eq!(standings.league_key, "449.l.12345");
+    assert_eq!(standings.week, 15);
+    let lines: Vec<_> = standings.teams.iter().map(|t| (t.team_key.as_str(), t.rank, t.wins, t.losses, t.ties, t.points_for)).collect();
+    assert_eq!(
+        lines,
+        [
+            ("449.l.12345.t.2", 1, 10, 3, 1, points("1654.20")),
+            ("449.l.12345.t.1", 2, 9, 5, 0, points("1598.70")),
+        ]
+    );
+    assert_eq!(standings.teams[0].waiver_priority, Some(4));
+    assert_eq!(standings.teams[0].clinched_playoffs, Some(true));
+    assert_eq!(standings.teams[1].clinched_playoffs, None);
+}
+
+#[tokio::test]
+async fn standings_events_serialize_with_their_kind() {
+    let transport = FixtureTransport::new().route("league/449.l.12345/standings", &fixture("league/standings.json"));
+    let current = transport.client().get_standings("449.l.12345").await.unwrap();
+    let mut previous = current.clone();
+    previous.teams[0].rank = 2;
+    previous.teams[1].rank = 1;
+
+    let events = diff_standings(&previous, &current);
+    assert_eq!(
+        serde_json::to_value(&events[0]).unwrap(),
+        serde_json::json!({ "kind": "rank_change", "team_key": "449.l.12345.t.2", "previous_rank": 2, "rank": 1 })
+    );
+    let round_trip: Vec<StandingsEvent> = serde_json::from_str(&serde_json::to_string(&events).unwrap()).unwrap();
+    assert_eq!(round_trip, events);
+}
+
+#[tokio::test]
+async fn league_status_finds_the_logged_in_users_team() {
+    let transport = FixtureTransport::new().route("league/449.l.12345;out=settings,teams", &fixture("league/status.json"));
+    let status = transport.client().get_league_status("449.l.12345").await.unwrap();
+
+    assert_eq!(status.league_key, "449.l.12345");
+    assert_eq!(status.visibility, LeagueVisibility::Private);
+    assert_eq!(status.draft_status, DraftStatus::Postdraft);
+    assert_eq!(status.my_team_key.as_deref(), Some("449.l.12345.t.2"));
+    assert!(status.is_ready());
+}
+
+#[tokio::test]
+async fn league_status_flags_an_unnamed_team_as_pending_setup() {
+    let body = fixture("league/status.json").replace("\"postdraft\"", "\"predraft\"").replace("\"private\"", "\"public\"").replace("{ \"name\": \"Team Beta\" }", "{ \"name\": \"\" }");
+    let transport = FixtureTransport::new().route("league/449.l.12345;out=settings,teams", &body);
+    let status = transport.client().get_league_status("449.l.12345").await.unwrap();
+
+    assert_eq!(status.visibility, LeagueVisibility::Public);
+    assert_eq!(status.draft_status, DraftStatus::Predraft);
+    assert!(status.team_pending_setup);
+    assert!(!status.is_ready());
+}
+
+#[tokio::test]
+async fn public_leagues_are_filtered_after_parsing() {
+    let transport = FixtureTransport::new().route("game/nfl/leagues;league_type=public;out=settings", &fixture("league/public_leagues.json"));
+    let client = transport.client();
+
+    let all = client.search_public_leagues("nfl", &LeagueSearchFilters::default()).await.unwrap();
+    let summaries: Vec<_> = all.iter().map(|l| (l.league_key.as_str(), l.scoring_type.as_str(), l.draft_type.as_str(), l.num_teams, l.open_slots)).collect();
+    assert_eq!(
+        summaries,
+        [
+            ("449.l.90001", "head", "live", 7, 3),
+            ("449.l.90002", "point", "autopick", 12, 0),
+            ("449.l.90003", "roto", "auction", 7, 5),
+        ]
+    );
+
+    let joinable = LeagueSearchFilters { joinable_only: true, ..Default::default() };
+    let keys: Vec<_> = client.search_public_leagues("nfl", &joinable).await.unwrap().into_iter().map(|l| l.league_key).collect();
+    assert_eq!(keys, ["449.l.90001", "449.l.90003"]);
+
+    let auctions = LeagueSearchFilters { draft_type: Some("auction".to_string()), ..Default::default() };
+    let keys: Vec<_> = client.search_public_leagues("nfl", &auctions).await.unwrap().into_iter().map(|l| l.league_key).collect();
+    assert_eq!(keys, ["449.l.90003"]);
+}
+
+#[tokio::test]
+async fn draft_results_skip_picks_still_to_be_made() {
+    let transport = FixtureTransport::new().route("league/449.l.12345/draftresults", &fixture("league/draftresults.json"));
+    let picks = transport.client().get_draft_results("449.l.12345").await.unwrap();
+
+    let pick = |pick, round, team: &str, player: &str, cost| DraftPick {
+        pick,
+        round,
+        team_key: format!("449.l.12345.t.{}", team),
+        player_key: format!("449.p.{}", player),
+        cost,
+    };
+    assert_eq!(picks, [pick(1, 1, "1", "30123", None), pick(2, 1, "2", "32671", Some(58)), pick(3, 2, "2", "31862", None)]);
+}
+
+#[tokio::test]
+async fn settings_join_modifiers_to_their_stat_names() {
+    let transport = FixtureTransport::new().route("league/449.l.12345/settings", &fixture("league/settings.json"));
+    let settings = transport.client().get_league_settings("449.l.12345").await.unwrap();
+
+    assert_eq!(settings.league_key, "449.l.12345");
+    assert_eq!((settings.scoring_type.as_str(), settings.draft_type.as_str()), ("head", "live"));
+    let modifiers: Vec<_> = settings.stat_modifiers.iter().map(|m| (m.stat_id, m.name.as_str(), m.display_name.as_str(), m.value)).collect();
+    assert_eq!(
+        modifiers,
+        [
+            (4, "Passing Yards", "Pass Yds", points("0.04")),
+            (5, "Passing Touchdowns", "Pass TD", points("4")),
+            (11, "Receptions", "Rec", points("0.5")),
+            (32, "Sack", "Sack", points("1")),
+        ]
+    );
+    assert_eq!(settings.modifiers_for(StatGroup::TeamDefense).count(), 1);
+
+    assert_eq!((settings.waiver_type.as_str(), settings.waiver_days, settings.faab_budget), ("R", 2, Some(100)));
+    assert_eq!(settings.trade_end_date.as_deref(), Some("2024-11-23"));
+    assert_eq!((settings.playoff_start_week, settings.num_playoff_teams), (Some(15), Some(4)));
+    assert!(settings.has_playoff_consolation_games);
+    assert!(!settings.uses_playoff_reseeding);
+    assert_eq!((settings.max_weekly_adds, settings.max_season_adds), (Some(4), None));
+    assert_eq!((settings.max_innings_pitched, settings.max_games_played), (None, None));
+}
+
+#[tokio::test]
+async fn settings_expand_flex_and_bench_slots() {
+    let transport = FixtureTransport::new().route("league/449.l.12345/settings", &fixture("league/settings.json"));
+    let settings = transport.client().get_league_settings("449.l.12345").await.unwrap();
+
+    let slot = |position: &str, accepts: &[&str], count, is_starting| RosterSlot {
+        position: position.to_string(),
+        accepts: accepts.iter().map(|p| p.to_string()).collect(),
+        count,
+        is_starting,
+    };
+    let everyone = ["QB", "WR", "RB", "TE", "K", "DEF"];
+    assert_eq!(
+        settings.roster_slots,
+        [
+            slot("QB", &["QB"], 1, true),
+            slot("WR", &["WR"], 2, true),
+            slot("RB", &["RB"], 2, true),
+            slot("TE", &["TE"], 1, true),
+            slot("W/R/T", &["WR", "RB", "TE"], 1, true),
+            slot("K", &["K"], 1, true),
+            slot("DEF", &["DEF"], 1, true),
+            slot("BN", &everyone, 6, false),
+            slot("IR", &everyone, 1, false),
+        ]
+    );
+    assert!(!settings.is_superflex());
+}
+
+#[tokio::test]
+async fn transactions_record_each_players_move() {
+    let transport = FixtureTransport::new().route("league/449.l.12345/transactions", &fixture("league/transactions.json"));
+    let transactions = transport.client().get_transactions("449.l.12345").await.unwrap();
+
+    let summary: Vec<_> = transactions.iter().map(|t| (t.transaction_key.as_str(), t.kind, t.timestamp, t.players.len(), t.is_commissioner_action())).collect();
+    assert_eq!(
+        summary,
+        [
+            ("449.l.12345.tr.3", TransactionType::AddDrop, 1_730_700_000, 2, false),
+            ("449.l.12345.tr.2", TransactionType::Trade, 1_730_300_000, 2, false),
+            ("449.l.12345.tr.1", TransactionType::Commish, 1_729_900_000, 0, true),
+        ]
+    );
+
+    let moves: Vec<_> = transactions[0]
+        .players
+        .iter()
+        .map(|p| (p.player_key.as_str(), p.name.as_str(), p.source_team_key.as_deref(), p.destination_team_key.as_deref()))
+        .collect();
+    assert_eq!(
+        moves,
+        [
+            ("449.p.33393", "Jaylen Warren", None, Some("449.l.12345.t.2")),
+            ("449.p.31012", "Kyle Pitts", Some("449.l.12345.t.2"), None),
+        ]
+    );
+    let trade = &transactions[1].players[1];
+    assert_eq!((trade.source_team_key.as_deref(), trade.destination_team_key.as_deref()), (Some("449.l.12345.t.2"), Some("449.l.12345.t.1")));
+}
+
+fn points(total: &str) -> Points {
+    total.parse().unwrap()
+}
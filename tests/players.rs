@@ -0,0 +1,157 @@
+//! Player and roster resources parsed from recorded Yahoo responses
+
+mod common;
+
+use chrono::NaiveDate;
+use common::{fixture, FixtureTransport};
+use yahoo_fantasy_sdk::{CapKind, FreeAgentSearch, Points, RosterCoverage, StatCoverage};
+
+const FREE_AGENTS: &str = "league/449.l.12345/players;status=FA;sort=PTS;sort_type=week;sort_week=3;count=3/stats;type=week;week=3";
+
+fn transport() -> FixtureTransport {
+    FixtureTransport::new().route("game/449/stat_categories", &fixture("players/stat_categories.json"))
+}
+
+#[tokio::test]
+async fn player_stats_are_keyed_by_abbreviation() {
+    let transport = transport().route("player/449.p.30977/stats;type=week;week=3", &fixture("players/player_stats.json"));
+    let stats = transport.client().get_player_stats("449.p.30977", &StatCoverage::Week(3)).await.unwrap();
+
+    assert_eq!(stats.player_key, "449.p.30977");
+    assert_eq!(stats.coverage, StatCoverage::Week(3));
+    assert_eq!(stats.points, points("27.72"));
+    let lines: Vec<(&str, f64)> = stats.stats.iter().map(|(stat, value)| (stat.as_str(), *value)).collect();
+    // "-" values are skipped, and stats without a category keep their ID
+    assert_eq!(lines, [("57", 2.0), ("GP", 1.0), ("Pass TD", 4.0), ("Pass Yds", 263.0), ("Rush Yds", 12.0)]);
+}
+
+#[tokio::test]
+async fn stat_categories_are_fetched_once_per_game() {
+    let transport = transport().route("player/449.p.30977/stats;type=week;week=3", &fixture("players/player_stats.json"));
+    let client = transport.client();
+    client.get_player_stats("449.p.30977", &StatCoverage::Week(3)).await.unwrap();
+    client.get_player_stats("449.p.30977", &StatCoverage::Week(3)).await.unwrap();
+
+    let categories = transport.requests().iter().filter(|url| url.contains("stat_categories")).count();
+    assert_eq!(categories, 1);
+}
+
+#[tokio::test]
+async fn daily_stats_mark_the_player_as_playing() {
+    let transport = transport().route("player/449.p.30977/stats;type=date;date=2024-09-22", &fixture("players/player_stats.json"));
+    let date = NaiveDate::from_ymd_opt(2024, 9, 22).unwrap();
+    let line = transport.client().get_player_stats_for_date("449.p.30977", date).await.unwrap();
+
+    assert_eq!(line.date, date);
+    assert!(line.played);
+    assert_eq!(line.stats.get("Pass Yds"), Some(&263.0));
+    assert_eq!(line.points, points("27.72"));
+}
+
+#[tokio::test]
+async fn free_agents_are_ranked_by_points() {
+    let transport = transport().route(FREE_AGENTS, &fixture("players/free_agents.json"));
+    let search = FreeAgentSearch {
+        position: None,
+        rank_by: StatCoverage::Week(3),
+        count: 3,
+    };
+    let free_agents = transport.client().search_free_agents("449.l.12345", &search).await.unwrap();
+
+    let ranked: Vec<(&str, &str, Points)> = free_agents.iter().map(|p| (p.player_key.as_str(), p.name.as_str(), p.stats.points)).collect();
+    assert_eq!(
+        ranked,
+        [
+            ("449.p.31883", "Dallas Goedert", points("20.5")),
+            ("449.p.33423", "Tyjae Spears", points("7.4")),
+            ("449.p.34021", "Jalin Hyatt", Points::ZERO),
+        ]
+    );
+    assert_eq!(free_agents[0].eligible_positions, ["TE"]);
+    assert_eq!(free_agents[1].stats.stats.get("Rec Yds"), Some(&21.0));
+    assert_eq!(free_agents[1].stats.coverage, StatCoverage::Week(3));
+}
+
+#[tokio::test]
+async fn free_agent_search_filters_by_position() {
+    let path = FREE_AGENTS.replace("status=FA", "status=FA;position=TE");
+    let transport = transport().route(&path, &fixture("players/free_agents.json"));
+    let search = FreeAgentSearch {
+        position: Some("TE".to_string()),
+        rank_by: StatCoverage::Week(3),
+        count: 3,
+    };
+    let free_agents = transport.client().search_free_agents("449.l.12345", &search).await.unwrap();
+
+    let keys: Vec<&str> = free_agents.iter().map(|p| p.player_key.as_str()).collect();
+    assert_eq!(keys, ["449.p.31883"]);
+}
+
+#[tokio::test]
+async fn roster_parses_slots_statuses_and_byes() {
+    let transport = FixtureTransport::new().route("team/449.l.12345.t.3/roster;week=3", &fixture("players/roster.json"));
+    let roster = transport.client().get_roster("449.l.12345.t.3", &RosterCoverage::Week(3)).await.unwrap();
+
+    assert_eq!(roster.team_key, "449.l.12345.t.3");
+    let players: Vec<(&str, &str, Option<&str>, bool)> = roster
+        .players
+        .iter()
+        .map(|p| (p.player_key.as_str(), p.selected_position.as_str(), p.status.as_deref(), p.has_game))
+        .collect();
+    assert_eq!(
+        players,
+        [
+            ("449.p.30977", "QB", None, true),
+            ("449.p.31883", "W/R/T", Some("Q"), true),
+            ("449.p.33423", "BN", None, false),
+        ]
+    );
+    assert_eq!(roster.players[1].eligible_positions, ["TE", "W/R/T"]);
+}
+
+#[tokio::test]
+async fn roster_points_default_to_zero() {
+    let transport = FixtureTransport::new().route("team/449.l.12345.t.3/roster;week=3/players/stats;type=week;week=3", &fixture("players/roster_points.json"));
+    let points_by_player = transport.client().get_roster_points("449.l.12345.t.3", 3).await.unwrap();
+
+    let scored: Vec<(&str, Points)> = points_by_player.iter().map(|p| (p.player_key.as_str(), p.points)).collect();
+    assert_eq!(scored, [("449.p.30977", points("27.72")), ("449.p.31883", points("9.1")), ("449.p.33423", Points::ZERO)]);
+}
+
+#[tokio::test]
+async fn cap_usage_reads_the_team_week_stats() {
+    let settings = fixture("league/settings.json").replace("\"max_adds\": \"\",", "\"max_adds\": \"\", \"max_games_played\": \"40\",");
+    let transport = transport()
+        .route("league/449.l.12345/settings", &settings)
+        .route("team/449.l.12345.t.3/stats;type=week;week=3", &fixture("players/team_stats.json"));
+    let caps = transport.client().get_cap_usage("449.l.12345.t.3", 3).await.unwrap();
+
+    assert_eq!(caps.len(), 1);
+    assert_eq!(caps[0].kind, CapKind::GamesPlayed);
+    assert_eq!(caps[0].limit, 40.0);
+    assert_eq!(caps[0].used, 9.0);
+}
+
+#[tokio::test]
+async fn players_by_keys_are_fetched_25_at_a_time() {
+    // Yahoo answers for a game code with the canonical key
+    let keys: Vec<String> = ["nfl.p.30977".to_string(), "449.p.31883".to_string()].into_iter().chain((1..=28).map(|id| format!("449.p.{}", id))).collect();
+    let transport = transport()
+        .route("game/nfl/stat_categories", &fixture("players/stat_categories.json"))
+        .route(&format!("players;player_keys={}/stats;type=week;week=3", keys[..25].join(",")), &fixture("players/players_by_keys.json"))
+        .route(&format!("players;player_keys={}/stats;type=week;week=3", keys[25..].join(",")), r#"{"fantasy_content":{"players":[]}}"#);
+    let batch = transport.client().get_players_by_keys(&keys, &StatCoverage::Week(3)).await;
+
+    let scored: Vec<(&str, Points)> = batch.succeeded.iter().map(|p| (p.player_key.as_str(), p.points)).collect();
+    assert_eq!(scored, [("449.p.30977", points("27.72")), ("449.p.31883", points("9.1"))]);
+    assert_eq!(batch.succeeded[0].stats.get("Pass Yds"), Some(&263.0));
+    assert_eq!(batch.succeeded[1].stats.get("Rec Yds"), Some(&61.0));
+    assert_eq!(batch.failed_keys().collect::<Vec<_>>(), keys[2..]);
+
+    let chunks: Vec<usize> = transport.requests().iter().filter(|url| url.contains("player_keys=")).map(|url| url.matches(".p.").count()).collect();
+    assert_eq!(chunks, [25, 5]);
+}
+
+fn points(total: &str) -> Points {
+    total.parse().unwrap()
+}
@@ -0,0 +1,43 @@
+//! `Points` conversions and arithmetic, with and without the `decimal` feature
+
+use yahoo_fantasy_sdk::Points;
+
+fn points(value: &str) -> Points {
+    value.parse().unwrap()
+}
+
+#[test]
+fn non_finite_floats_are_rejected() {
+    assert_eq!(Points::from_f64(f64::NAN), None);
+    assert_eq!(Points::from_f64(f64::INFINITY), None);
+    assert_eq!(Points::from_f64(f64::NEG_INFINITY), None);
+    assert_eq!(Points::from_f64(12.5), Some(points("12.5")));
+}
+
+#[test]
+fn scaling_by_a_non_finite_factor_fails() {
+    assert_eq!(points("10").scale(f64::NAN), None);
+    assert_eq!(points("10").scale(f64::INFINITY), None);
+}
+
+#[test]
+fn scaling_overflow_is_reported() {
+    assert_eq!(Points::from_f64(1e300).and_then(|big| big.scale(1e300)), None);
+}
+
+#[cfg(feature = "decimal")]
+#[test]
+fn decimal_scale_uses_the_factor_as_written() {
+    // 0.1 as a binary fraction is 0.1000000000000000055511151231..., which
+    // would leave a tail on the product
+    assert_eq!(points("123").scale(0.1), Some(points("12.3")));
+    assert_eq!(points("87").scale(0.04).unwrap().to_string(), "3.48");
+    assert_eq!(Points::from_f64(1e30), None);
+    assert_eq!(points("79228162514264337593543950335").scale(2.0), None);
+}
+
+#[test]
+fn json_numbers_must_be_finite_points() {
+    assert_eq!(serde_json::from_str::<Points>("\"87.50\"").unwrap(), points("87.5"));
+    assert_eq!(serde_json::from_str::<Points>("4").unwrap(), points("4"));
+}
@@ -0,0 +1,73 @@
+//! Watching a resource on the planner's interval
+
+mod common;
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use common::FixtureTransport;
+use yahoo_fantasy_sdk::{CancellationToken, GameTimeSchedule, PollWatcher, PollingPlanner};
+
+/// Run a watch for `runtime` with quota to spare, returning how many polls it made
+async fn count_polls(watcher: &PollWatcher, runtime: Duration) -> usize {
+    let client = FixtureTransport::new().builder().rate_limits(1000.0, 1000.0).build().unwrap();
+    let cancel = CancellationToken::new();
+    let polls = AtomicUsize::new(0);
+    let stop = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(runtime).await;
+        stop.cancel();
+    });
+    client
+        .watch(watcher, &cancel, || async { Ok(polls.fetch_add(1, Ordering::SeqCst)) }, |result: Result<usize, _>| assert!(result.is_ok()))
+        .await;
+    polls.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn polls_on_the_live_interval_while_games_are_on() {
+    let schedule = GameTimeSchedule::from_callback(Duration::from_millis(20), Duration::from_secs(60), |_| true);
+    let polls = count_polls(&PollWatcher::new("scoreboard", schedule), Duration::from_millis(210)).await;
+    assert!((5..=12).contains(&polls), "{} polls", polls);
+}
+
+#[tokio::test]
+async fn drops_to_the_idle_interval_between_games() {
+    let schedule = GameTimeSchedule::from_callback(Duration::from_millis(20), Duration::from_secs(60), |_| false);
+    let polls = count_polls(&PollWatcher::new("scoreboard", schedule), Duration::from_millis(210)).await;
+    assert_eq!(polls, 1);
+}
+
+#[tokio::test]
+async fn a_game_starting_mid_wait_brings_the_next_poll_forward() {
+    let live = Arc::new(AtomicBool::new(false));
+    let signal = live.clone();
+    let schedule = GameTimeSchedule::from_callback(Duration::from_secs(1), Duration::from_secs(600), move |_| signal.load(Ordering::SeqCst));
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        live.store(true, Ordering::SeqCst);
+    });
+    // Idle, the second poll would be ten minutes out; the game starts after
+    // 100ms and the re-plan a second in finds the live interval already elapsed
+    let polls = count_polls(&PollWatcher::new("scoreboard", schedule), Duration::from_millis(2500)).await;
+    assert!((2..=4).contains(&polls), "{} polls", polls);
+}
+
+#[tokio::test]
+async fn polling_slows_to_fit_the_rate_limit() {
+    let client = FixtureTransport::new().builder().rate_limits(100.0, 0.83).build().unwrap();
+    let schedule = GameTimeSchedule::from_callback(Duration::from_millis(20), Duration::from_secs(60), |_| true);
+    // Fifty polls a second is far over budget; the plan stretches to 80% of the 0.83/s refill
+    let interval = PollWatcher::new("scoreboard", schedule).next_interval(client.rate_limiter(), std::time::SystemTime::now());
+    assert!((Duration::from_millis(1500)..Duration::from_millis(1510)).contains(&interval), "{:?}", interval);
+}
+
+#[tokio::test]
+async fn watchers_leave_their_shared_planner_when_they_stop() {
+    let planner = Arc::new(Mutex::new(PollingPlanner::new()));
+    let schedule = GameTimeSchedule::from_callback(Duration::from_millis(20), Duration::from_secs(60), |_| true);
+    let watcher = PollWatcher::new("transactions", schedule).planner(planner.clone());
+    count_polls(&watcher, Duration::from_millis(50)).await;
+    assert!(planner.lock().unwrap().targets().is_empty());
+}
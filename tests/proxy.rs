@@ -0,0 +1,13 @@
+//! Proxy settings for the default transport
+
+use yahoo_fantasy_sdk::ProxyConfig;
+
+#[test]
+fn proxy_passwords_stay_out_of_debug_output() {
+    let proxy = ProxyConfig::new("http://proxy.corp:8080").unwrap().basic_auth("svc-fantasy", "hunter2");
+    let debug = format!("{:?}", proxy);
+
+    assert!(debug.contains("svc-fantasy"), "{}", debug);
+    assert!(!debug.contains("hunter2"), "{}", debug);
+    assert_eq!(proxy, ProxyConfig::new("http://proxy.corp:8080").unwrap().basic_auth("svc-fantasy", "hunter2"));
+}
@@ -0,0 +1,83 @@
+//! Rate limit tokens are reserved atomically and refunded for requests never sent
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use yahoo_fantasy_sdk::{BoxFuture, Cancelled, CancellationToken, HttpResponse, HttpTransport, Method, RateLimiter, Request, YahooFantasyClient};
+
+/// Transport whose requests never get a response
+struct Unanswered;
+
+impl HttpTransport for Unanswered {
+    fn send<'a>(&'a self, _: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(std::future::pending())
+    }
+}
+
+#[test]
+fn concurrent_acquires_never_spend_more_than_the_bucket() {
+    let limiter = Arc::new(RateLimiter::with_limits(10.0, 0.0));
+    let acquired: usize = thread::scope(|scope| {
+        let handles: Vec<_> = (0..32).map(|_| scope.spawn(|| limiter.try_acquire(1.0))).collect();
+        handles.into_iter().map(|h| h.join().unwrap() as usize).sum()
+    });
+    assert_eq!(acquired, 10);
+    assert!(!limiter.can_make_request());
+}
+
+#[test]
+fn refunded_tokens_can_be_spent_again() {
+    let limiter = RateLimiter::with_limits(1.0, 0.0);
+    assert!(limiter.try_acquire(1.0));
+    assert!(!limiter.try_acquire(1.0));
+    limiter.refund(1.0);
+    assert!(limiter.try_acquire(1.0));
+}
+
+#[tokio::test]
+async fn cancelling_a_dispatched_request_keeps_its_tokens_spent() {
+    let client = Arc::new(YahooFantasyClient::builder("key".to_string(), "secret".to_string()).transport(Unanswered).rate_limits(5.0, 0.0).build().unwrap());
+    let cancel = CancellationToken::new();
+    let request = client.request(Method::Get, "game/449");
+
+    let sending = tokio::spawn({
+        let (client, cancel) = (client.clone(), cancel.clone());
+        async move { client.execute_cancellable(&request, &cancel).await.map_err(|e| e.downcast_ref::<Cancelled>().copied()) }
+    });
+    while client.in_flight_requests() == 0 {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    assert_eq!(client.rate_limiter().get_remaining_tokens(), 4.0);
+
+    cancel.cancel();
+    assert_eq!(sending.await.unwrap(), Err(Some(Cancelled)));
+    // The transport already had the request, so Yahoo may have counted it
+    assert_eq!(client.rate_limiter().get_remaining_tokens(), 4.0);
+    assert_eq!(client.in_flight_requests(), 0);
+}
+
+#[tokio::test]
+async fn cancelling_before_dispatch_spends_nothing() {
+    let client = Arc::new(YahooFantasyClient::builder("key".to_string(), "secret".to_string()).transport(Unanswered).rate_limits(1.0, 0.0).build().unwrap());
+    let first = tokio::spawn({
+        let client = client.clone();
+        async move { client.execute_cancellable(&client.request(Method::Get, "game/449"), &CancellationToken::new()).await.is_ok() }
+    });
+    while client.in_flight_requests() == 0 {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    // The second request waits on the empty bucket until cancelled
+    let cancel = CancellationToken::new();
+    let waiting = tokio::spawn({
+        let (client, cancel) = (client.clone(), cancel.clone());
+        async move { client.execute_cancellable(&client.request(Method::Get, "game/390"), &cancel).await.map_err(|e| e.downcast_ref::<Cancelled>().copied()) }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    cancel.cancel();
+    assert_eq!(waiting.await.unwrap(), Err(Some(Cancelled)));
+    assert_eq!(client.rate_limiter().get_remaining_tokens(), 0.0);
+    assert_eq!(client.in_flight_requests(), 1);
+    first.abort();
+}
@@ -0,0 +1,119 @@
+//! Concurrent requests with an expired token share a single refresh
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use yahoo_fantasy_sdk::login::ACCESS_TOKEN_URL;
+use yahoo_fantasy_sdk::{AccessToken, BoxFuture, HttpResponse, HttpTransport, Method, Request, TokenRevoked, YahooFantasyClient};
+
+const REQUESTS: usize = 8;
+
+/// Answers the token endpoint after a delay, so every request queues behind
+/// the refresh, and records the token each API request was signed with
+struct TokenEndpoint {
+    refreshes: AtomicUsize,
+    /// Reply to refreshes with this error body instead of a new token
+    reject: Option<&'static str>,
+    signed_with: Mutex<Vec<String>>,
+}
+
+impl TokenEndpoint {
+    fn new(reject: Option<&'static str>) -> Arc<Self> {
+        Arc::new(Self {
+            refreshes: AtomicUsize::new(0),
+            reject,
+            signed_with: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+impl HttpTransport for TokenEndpoint {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            if request.url().starts_with(ACCESS_TOKEN_URL) {
+                self.refreshes.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                return Ok(match self.reject {
+                    Some(body) => HttpResponse { status: 401, headers: Vec::new(), body: body.to_string() },
+                    None => HttpResponse {
+                        status: 200,
+                        headers: Vec::new(),
+                        body: "oauth_token=fresh&oauth_token_secret=fresh-secret&oauth_session_handle=handle&oauth_expires_in=3600".to_string(),
+                    },
+                });
+            }
+            let authorization = request.header_value("Authorization").unwrap_or_default();
+            let token = if authorization.contains("oauth_token=\"fresh\"") { "fresh" } else { "stale" };
+            self.signed_with.lock().unwrap().push(token.to_string());
+            Ok(HttpResponse { status: 200, headers: Vec::new(), body: "{}".to_string() })
+        })
+    }
+}
+
+/// Lets the test keep a handle on the transport it gives the client
+struct SharedTransport(Arc<TokenEndpoint>);
+
+impl HttpTransport for SharedTransport {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        self.0.send(request)
+    }
+}
+
+/// Client whose token expired the moment it was set
+fn expired_client(transport: &Arc<TokenEndpoint>) -> Arc<YahooFantasyClient> {
+    let mut client = YahooFantasyClient::builder("key".to_string(), "secret".to_string())
+        .transport(SharedTransport(transport.clone()))
+        .rate_limits(100.0, 100.0)
+        .build()
+        .unwrap();
+    client.set_access_token(AccessToken {
+        token: "stale".to_string().into(),
+        secret: "stale-secret".to_string().into(),
+        session_handle: Some("handle".to_string().into()),
+        expires_in: Some(0),
+    });
+    Arc::new(client)
+}
+
+/// Send `REQUESTS` requests at once, returning each one's result
+async fn send_concurrently(client: &Arc<YahooFantasyClient>) -> Vec<Result<String, Box<dyn std::error::Error + Send + Sync>>> {
+    let tasks: Vec<_> = (0..REQUESTS)
+        .map(|i| {
+            let client = client.clone();
+            tokio::spawn(async move { client.execute(&client.request(Method::Get, &format!("game/{}", i))).await })
+        })
+        .collect();
+    let mut results = Vec::new();
+    for task in tasks {
+        results.push(task.await.unwrap());
+    }
+    results
+}
+
+#[tokio::test]
+async fn concurrent_requests_share_one_refresh() {
+    let transport = TokenEndpoint::new(None);
+    let client = expired_client(&transport);
+
+    for result in send_concurrently(&client).await {
+        result.unwrap();
+    }
+    assert_eq!(transport.refreshes.load(Ordering::SeqCst), 1);
+    assert_eq!(*transport.signed_with.lock().unwrap(), vec!["fresh"; REQUESTS]);
+    assert_eq!(client.access_token().unwrap().token.expose(), "fresh");
+}
+
+#[tokio::test]
+async fn concurrent_requests_share_one_failed_refresh() {
+    let transport = TokenEndpoint::new(Some("oauth_problem=token_rejected"));
+    let client = expired_client(&transport);
+
+    for result in send_concurrently(&client).await {
+        let error = result.unwrap_err();
+        let revoked = error.downcast_ref::<TokenRevoked>().unwrap_or_else(|| panic!("expected TokenRevoked, got {}", error));
+        assert_eq!(revoked.body, "oauth_problem=token_rejected");
+    }
+    assert_eq!(transport.refreshes.load(Ordering::SeqCst), 1);
+    assert!(transport.signed_with.lock().unwrap().is_empty());
+}
@@ -0,0 +1,98 @@
+//! JSON Schemas for the SDK's serializable types, behind the `schemars` feature
+
+#![cfg(feature = "schemars")]
+
+use schemars::schema_for;
+use yahoo_fantasy_sdk::integrity::BenchPlayer;
+use yahoo_fantasy_sdk::projections::{DefenseVsPosition, OpponentGame};
+use yahoo_fantasy_sdk::schedule::ScheduledGame;
+use yahoo_fantasy_sdk::settings_report::{ReportEntry, ReportSection};
+use yahoo_fantasy_sdk::*;
+
+#[test]
+fn every_serializable_type_has_a_schema() {
+    let schemas = [
+        schema_for!(AccessToken),
+        schema_for!(AuditAction),
+        schema_for!(AuditEntry),
+        schema_for!(AuditTrail),
+        schema_for!(BenchPlayer),
+        schema_for!(ComputedStanding),
+        schema_for!(CrawlCheckpoint),
+        schema_for!(DefenseVsPosition),
+        schema_for!(FieldChange),
+        schema_for!(FormMetrics),
+        schema_for!(FormThresholds),
+        schema_for!(GameRecord),
+        schema_for!(IntegrityFlag),
+        schema_for!(IntegrityReport),
+        schema_for!(IntegrityThresholds),
+        schema_for!(JobCheckpoint<u32>),
+        schema_for!(LeagueArchive),
+        schema_for!(LineupRetro),
+        schema_for!(LineupSnapshot),
+        schema_for!(LogEntry),
+        schema_for!(LoggedEvent),
+        schema_for!(ManagerEfficiency),
+        schema_for!(Method),
+        schema_for!(OpponentGame),
+        schema_for!(ProjectionContext),
+        schema_for!(QueuedWrite),
+        schema_for!(ReportEntry),
+        schema_for!(ReportSection),
+        schema_for!(Request),
+        schema_for!(RequestOptions),
+        schema_for!(RequestToken),
+        schema_for!(RosterStint),
+        schema_for!(RosterTimeline),
+        schema_for!(ScheduledGame),
+        schema_for!(Scope),
+        schema_for!(ScoreEdit),
+        schema_for!(ScoreboardEvent),
+        schema_for!(SeasonPhase),
+        schema_for!(SeasonSchedule),
+        schema_for!(Secret),
+        schema_for!(SettingChange),
+        schema_for!(SettingsReport),
+        schema_for!(StandingsEvent),
+        schema_for!(StandingsMismatch),
+        schema_for!(StandingsVerification),
+        schema_for!(StatCorrection),
+        schema_for!(StoredToken),
+        schema_for!(TokenStatus),
+        schema_for!(ValueKind),
+        schema_for!(WriteStatus),
+    ];
+    assert!(schemas.iter().all(|schema| schema.get("title").is_some()));
+}
+
+#[cfg(feature = "config")]
+#[test]
+fn config_files_have_a_schema() {
+    let config = schema_for!(Config);
+    let mut sections: Vec<&String> = config.as_value()["properties"].as_object().unwrap().keys().collect();
+    sections.sort();
+    assert_eq!(sections, ["cache", "credentials", "leagues", "rate_limit"]);
+}
+
+#[test]
+fn tagged_events_name_their_kinds() {
+    let events = schema_for!(ScoreboardEvent).as_value().to_string();
+    for kind in ["\"lead_change\"", "\"scoring_play\"", "\"projected_result_flip\""] {
+        assert!(events.contains(kind), "{} missing from {}", kind, events);
+    }
+
+    let standings = schema_for!(StandingsEvent).as_value().to_string();
+    for kind in ["\"rank_change\"", "\"clinched_playoffs\"", "\"eliminated\"", "\"waiver_priority_change\""] {
+        assert!(standings.contains(kind), "{} missing from {}", kind, standings);
+    }
+}
+
+#[test]
+fn timestamps_and_secrets_are_strings() {
+    let game = schema_for!(ScheduledGame);
+    assert_eq!(game.as_value()["properties"]["starts_at"]["format"], "date-time");
+
+    let secret = schema_for!(Secret);
+    assert_eq!(secret.as_value()["type"], "string");
+}
@@ -0,0 +1,75 @@
+//! Hosted-app sessions refresh each user's token once, however many tasks need it
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use yahoo_fantasy_sdk::login::ACCESS_TOKEN_URL;
+use yahoo_fantasy_sdk::{AccessToken, BoxFuture, HttpResponse, HttpTransport, MemoryTokenStore, Method, Request, SessionManager, TokenStore, YahooFantasyClient};
+
+/// Answers the token endpoint slowly, rotating the session handle, and any other request with `{}`
+#[derive(Clone, Default)]
+struct RotatingEndpoint {
+    refreshes: Arc<AtomicUsize>,
+}
+
+impl HttpTransport for RotatingEndpoint {
+    fn send<'a>(&'a self, request: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            if !request.url().starts_with(ACCESS_TOKEN_URL) {
+                return Ok(HttpResponse { status: 200, headers: Vec::new(), body: "{}".to_string() });
+            }
+            let refresh = self.refreshes.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            // The old handle is rotated away, so a second refresh with it is refused
+            Ok(match refresh {
+                0 => HttpResponse {
+                    status: 200,
+                    headers: Vec::new(),
+                    body: "oauth_token=fresh&oauth_token_secret=fresh-secret&oauth_session_handle=rotated&oauth_expires_in=3600".to_string(),
+                },
+                _ => HttpResponse { status: 401, headers: Vec::new(), body: "oauth_problem=token_rejected".to_string() },
+            })
+        })
+    }
+}
+
+#[tokio::test]
+async fn a_users_token_is_refreshed_once_across_tasks() {
+    let transport = RotatingEndpoint::default();
+    let client = YahooFantasyClient::builder("key".to_string(), "secret".to_string()).transport(transport.clone()).rate_limits(100.0, 100.0).build().unwrap();
+    let store = Arc::new(MemoryTokenStore::new());
+    let manager = Arc::new(SessionManager::new(client, store.clone()));
+    manager
+        .insert(
+            "me",
+            AccessToken {
+                token: "stale".to_string().into(),
+                secret: "stale-secret".to_string().into(),
+                session_handle: Some("handle".to_string().into()),
+                expires_in: Some(0),
+            },
+        )
+        .unwrap();
+
+    let mut tasks = Vec::new();
+    for _ in 0..4 {
+        let manager = manager.clone();
+        tasks.push(tokio::spawn(async move {
+            let client = manager.client_for("me").await?;
+            client.execute(&client.request(Method::Get, "users;use_login=1")).await.map(drop)
+        }));
+    }
+    let due = tokio::spawn({
+        let manager = manager.clone();
+        async move { manager.refresh_due().await }
+    });
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    assert!(due.await.unwrap().failed.is_empty());
+
+    assert_eq!(transport.refreshes.load(Ordering::SeqCst), 1);
+    assert_eq!(store.load("me").unwrap().unwrap().token.token.expose(), "fresh");
+    assert_eq!(manager.client_for("me").await.unwrap().access_token().unwrap().token.expose(), "fresh");
+}
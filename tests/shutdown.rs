@@ -0,0 +1,66 @@
+//! Shutdown drains in-flight requests
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Semaphore;
+use yahoo_fantasy_sdk::{BoxFuture, HttpResponse, HttpTransport, Method, Request, YahooFantasyClient};
+
+/// Transport that answers each request once a permit is released
+#[derive(Clone)]
+struct Gated(Arc<Semaphore>);
+
+impl HttpTransport for Gated {
+    fn send<'a>(&'a self, _: &'a Request) -> BoxFuture<'a, Result<HttpResponse, Box<dyn std::error::Error + Send + Sync>>> {
+        Box::pin(async move {
+            self.0.acquire().await?.forget();
+            Ok(HttpResponse { status: 200, headers: Vec::new(), body: "{}".to_string() })
+        })
+    }
+}
+
+fn client(gate: &Arc<Semaphore>) -> Arc<YahooFantasyClient> {
+    Arc::new(YahooFantasyClient::builder("key".to_string(), "secret".to_string()).transport(Gated(gate.clone())).build().unwrap())
+}
+
+async fn start_request(client: &Arc<YahooFantasyClient>) {
+    let sending = client.clone();
+    tokio::spawn(async move { sending.execute(&sending.request(Method::Get, "game/449")).await.is_ok() });
+    while client.in_flight_requests() == 0 {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[tokio::test]
+async fn shutdown_returns_when_the_last_request_finishes() {
+    let gate = Arc::new(Semaphore::new(0));
+    let client = client(&gate);
+    start_request(&client).await;
+
+    let shutting_down = tokio::spawn({
+        let client = client.clone();
+        async move { client.shutdown(Duration::from_secs(30)).await }
+    });
+    tokio::task::yield_now().await;
+    assert!(!shutting_down.is_finished());
+
+    gate.add_permits(1);
+    assert!(tokio::time::timeout(Duration::from_secs(1), shutting_down).await.unwrap().unwrap());
+    assert_eq!(client.in_flight_requests(), 0);
+}
+
+#[tokio::test]
+async fn shutdown_gives_up_after_the_timeout() {
+    let gate = Arc::new(Semaphore::new(0));
+    let client = client(&gate);
+    start_request(&client).await;
+
+    assert!(!client.shutdown(Duration::from_millis(20)).await);
+    assert_eq!(client.in_flight_requests(), 1);
+}
+
+#[tokio::test]
+async fn idle_client_shuts_down_at_once() {
+    let client = client(&Arc::new(Semaphore::new(0)));
+    assert!(client.shutdown(Duration::ZERO).await);
+}
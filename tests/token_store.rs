@@ -0,0 +1,84 @@
+//! Loading and saving tokens through a token store
+
+mod common;
+
+use std::sync::Arc;
+
+use common::FixtureTransport;
+use yahoo_fantasy_sdk::{AccessToken, FileTokenStore, StoredToken, TokenStore};
+
+/// Store whose backing storage can't be read
+struct Unreadable;
+
+impl TokenStore for Unreadable {
+    fn load(&self, _: &str) -> Result<Option<StoredToken>, Box<dyn std::error::Error + Send + Sync>> {
+        Err("token file is corrupt".into())
+    }
+
+    fn save(&self, _: &str, _: &StoredToken) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn clear(&self, _: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn users(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Vec::new())
+    }
+}
+
+fn token() -> StoredToken {
+    StoredToken {
+        token: AccessToken {
+            token: "token".to_string().into(),
+            secret: "secret".to_string().into(),
+            session_handle: None,
+            expires_in: None,
+        },
+        expires_at: None,
+    }
+}
+
+#[test]
+fn build_fails_when_the_store_cant_load() {
+    let error = FixtureTransport::new().builder().token_store(Arc::new(Unreadable), "me").build().unwrap_err();
+    assert_eq!(error.to_string(), "token file is corrupt");
+}
+
+#[cfg(unix)]
+#[test]
+fn saved_tokens_are_owner_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = std::env::temp_dir().join(format!("yahoo-fantasy-token-store-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tokens.json");
+    // A world-readable temp file left behind by an earlier crash
+    std::fs::write(dir.join("tokens.tmp"), b"").unwrap();
+    std::fs::set_permissions(dir.join("tokens.tmp"), std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    FileTokenStore::open(&path).unwrap().save("me", &token()).unwrap();
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(mode, 0o600);
+}
+
+#[test]
+fn only_encrypted_envelopes_are_refused() {
+    let dir = std::env::temp_dir().join(format!("yahoo-fantasy-token-detect-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // A user who happens to be called "ciphertext" is still a plain store
+    let plain = dir.join("plain.json");
+    FileTokenStore::open(&plain).unwrap().save("ciphertext", &token()).unwrap();
+    let reopened = FileTokenStore::open(&plain).unwrap().load("ciphertext").unwrap();
+
+    let encrypted = dir.join("encrypted.json");
+    std::fs::write(&encrypted, r#"{"format":"yahoo-fantasy-sdk/encrypted-tokens","version":1,"kdf":"argon2id","salt":"c2FsdA","nonce":"bm9uY2U","ciphertext":"c2VhbGVk"}"#).unwrap();
+    let error = FileTokenStore::open(&encrypted).unwrap_err();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(reopened, Some(token()));
+    assert!(error.to_string().contains("is encrypted"), "{}", error);
+}
@@ -0,0 +1,97 @@
+//! Axum login handlers and session extractor, driven without a server
+
+#![cfg(feature = "axum")]
+
+mod common;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{FromRequestParts, Query, State};
+use axum::http::header::{COOKIE, LOCATION, SET_COOKIE};
+use axum::http::{HeaderMap, HeaderValue, Request, StatusCode};
+use axum::response::{IntoResponse, Response};
+use common::FixtureTransport;
+use yahoo_fantasy_sdk::integrations::web::{login, oauth_callback, CallbackParams, UserClient, WebError, YahooState, LOGIN_STATE_COOKIE, SESSION_COOKIE};
+use yahoo_fantasy_sdk::session::MemoryTokenStore;
+
+fn transport() -> FixtureTransport {
+    FixtureTransport::new()
+        .route("get_request_token", "oauth_token=request&oauth_token_secret=request-secret&xoauth_request_auth_url=https%3A%2F%2Fapi.login.yahoo.com%2Foauth%2Fv2%2Frequest_auth%3Foauth_token%3Drequest")
+        .route("get_token", "oauth_token=access&oauth_token_secret=access-secret&oauth_session_handle=handle&oauth_expires_in=3600")
+}
+
+fn callback_params() -> Query<CallbackParams> {
+    Query(CallbackParams {
+        oauth_token: "request".to_string(),
+        oauth_verifier: "verifier".to_string(),
+    })
+}
+
+/// `name=value` from the response's `Set-Cookie` for `name`
+fn set_cookie(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .find(|cookie| cookie.starts_with(&format!("{}=", name)))
+        .map(|cookie| cookie.split(';').next().unwrap().to_string())
+}
+
+fn cookies(cookie: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(COOKIE, HeaderValue::from_str(cookie).unwrap());
+    headers
+}
+
+fn status(result: Result<Response, WebError>) -> StatusCode {
+    result.unwrap_or_else(IntoResponse::into_response).status()
+}
+
+#[tokio::test]
+async fn callback_completes_only_in_the_browser_that_started_the_login() {
+    let state = YahooState::new(transport().client(), "https://app.example/callback");
+    let started = login(State(state.clone())).await.unwrap();
+    assert_eq!(started.headers()[LOCATION], "https://api.login.yahoo.com/oauth/v2/request_auth?oauth_token=request");
+    let login_state = set_cookie(&started, LOGIN_STATE_COOKIE).unwrap();
+
+    // A callback forged into another browser carries no state, or someone else's
+    assert_eq!(status(oauth_callback(State(state.clone()), HeaderMap::new(), callback_params()).await), StatusCode::UNAUTHORIZED);
+    let forged = cookies(&format!("{}=forged", LOGIN_STATE_COOKIE));
+    assert_eq!(status(oauth_callback(State(state.clone()), forged, callback_params()).await), StatusCode::UNAUTHORIZED);
+
+    let response = oauth_callback(State(state.clone()), cookies(&login_state), callback_params()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(set_cookie(&response, LOGIN_STATE_COOKIE).unwrap(), format!("{}=", LOGIN_STATE_COOKIE));
+    let session = set_cookie(&response, SESSION_COOKIE).unwrap();
+    let session_id = session.split_once('=').unwrap().1;
+    assert_eq!(state.session(session_id).unwrap().token.expose(), "access");
+
+    // The request token is spent
+    assert_eq!(status(oauth_callback(State(state), cookies(&login_state), callback_params()).await), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn user_clients_are_reused_across_requests() {
+    let state = YahooState::new(transport().client(), "https://app.example/callback");
+    let session_id = state.insert_session(state.client().get_access_token(&state.client().get_request_token("oob").await.unwrap(), "verifier").await.unwrap()).unwrap();
+    let (mut parts, ()) = Request::builder().header(COOKIE, format!("theme=dark; {}={}", SESSION_COOKIE, session_id)).body(()).unwrap().into_parts();
+
+    let UserClient(first) = UserClient::from_request_parts(&mut parts, &state).await.unwrap();
+    let UserClient(second) = UserClient::from_request_parts(&mut parts, &state).await.unwrap();
+    assert!(Arc::ptr_eq(&first, &second));
+}
+
+#[tokio::test]
+async fn sessions_end_after_their_ttl() {
+    let client = transport().client();
+    let token = client.get_access_token(&client.get_request_token("oob").await.unwrap(), "verifier").await.unwrap();
+    let state = YahooState::with_store(client, Arc::new(MemoryTokenStore::new()), "https://app.example/callback", Duration::ZERO);
+    let session_id = state.insert_session(token).unwrap();
+
+    assert!(state.session(&session_id).is_none());
+    assert!(state.sessions().store().users().unwrap().is_empty());
+    let (mut parts, ()) = Request::builder().header(COOKIE, format!("{}={}", SESSION_COOKIE, session_id)).body(()).unwrap().into_parts();
+    assert!(matches!(UserClient::from_request_parts(&mut parts, &state).await, Err(WebError::Unauthorized)));
+}
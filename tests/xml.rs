@@ -0,0 +1,94 @@
+//! Write bodies compared against golden files, and XML escaping
+
+mod common;
+
+use chrono::NaiveDate;
+use common::fixture;
+use yahoo_fantasy_sdk::xml::escape;
+use yahoo_fantasy_sdk::{InvalidXmlChar, RosterBuilder, RosterCoverage, TransactionBuilder, XmlBody};
+
+fn assert_golden(name: &str, body: &impl XmlBody) {
+    assert_eq!(body.to_xml(), fixture(&format!("xml/{}", name)), "{} differs from its golden file", name);
+}
+
+#[test]
+fn weekly_roster_body_matches_golden() {
+    let body = RosterBuilder::new(RosterCoverage::Week(7))
+        .player("449.p.30123", "QB")
+        .player("449.p.32671", "W/R/T")
+        .player("449.p.31012", "BN")
+        .build()
+        .unwrap();
+    assert_golden("roster_week.xml", &body);
+}
+
+#[test]
+fn daily_roster_body_matches_golden() {
+    let body = RosterBuilder::new(RosterCoverage::Date(NaiveDate::from_ymd_opt(2024, 11, 3).unwrap()))
+        .player("454.p.6014", "C")
+        .player("454.p.5826", "Util")
+        .build()
+        .unwrap();
+    assert_golden("roster_date.xml", &body);
+}
+
+#[test]
+fn add_drop_body_matches_golden() {
+    let body = TransactionBuilder::new()
+        .add("449.p.33393", "449.l.123456.t.2")
+        .drop_player("449.p.31012", "449.l.123456.t.2")
+        .faab_bid(17)
+        .build()
+        .unwrap();
+    assert_golden("transaction_add_drop.xml", &body);
+}
+
+#[test]
+fn trade_body_matches_golden_with_escaped_note() {
+    let body = TransactionBuilder::new()
+        .trade("449.p.30123", "449.l.123456.t.1", "449.l.123456.t.2")
+        .trade("449.p.32671", "449.l.123456.t.2", "449.l.123456.t.1")
+        .trade_teams("449.l.123456.t.1", "449.l.123456.t.2")
+        .trade_note("Mahomes & a <3 for \"JJ\" -- don't say no")
+        .build()
+        .unwrap();
+    assert_golden("transaction_trade.xml", &body);
+}
+
+#[test]
+fn escape_replaces_markup_characters() {
+    assert_eq!(escape(r#"<a href="x">Tom & Jerry's</a>"#).unwrap(), "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&apos;s&lt;/a&gt;");
+    assert_eq!(escape("plain text").unwrap(), "plain text");
+    assert_eq!(escape("").unwrap(), "");
+}
+
+#[test]
+fn escape_keeps_whitespace_and_non_ascii_text() {
+    assert_eq!(escape("line one\n\tline two\r\n").unwrap(), "line one\n\tline two\r\n");
+    assert_eq!(escape("Señor Fantasy 🏈 — naïve").unwrap(), "Señor Fantasy 🏈 — naïve");
+    // C1 controls and DEL are allowed in XML 1.0
+    assert_eq!(escape("a\u{7f}\u{85}b").unwrap(), "a\u{7f}\u{85}b");
+}
+
+#[test]
+fn escape_rejects_characters_xml_cannot_represent() {
+    assert_eq!(escape("trade\u{0}note"), Err(InvalidXmlChar { character: '\u{0}', offset: 5 }));
+    assert_eq!(escape("bell\u{7}").unwrap_err().character, '\u{7}');
+    assert_eq!(escape("é\u{1b}[0m").unwrap_err().offset, 2);
+    assert!(escape("\u{fffe}").is_err());
+    assert!(escape("\u{ffff}").is_err());
+}
+
+#[test]
+fn builders_fail_instead_of_dropping_invalid_text() {
+    let trade = TransactionBuilder::new()
+        .trade("449.p.30123", "449.l.1.t.1", "449.l.1.t.2")
+        .trade_teams("449.l.1.t.1", "449.l.1.t.2")
+        .trade_note("deal?\u{8}")
+        .build();
+    let error = trade.unwrap_err();
+    assert_eq!(error.downcast_ref::<InvalidXmlChar>().map(|e| e.character), Some('\u{8}'));
+
+    let roster = RosterBuilder::new(RosterCoverage::Week(1)).player("449.p.1\u{1}", "QB").build();
+    assert!(roster.unwrap_err().is::<InvalidXmlChar>());
+}